@@ -0,0 +1,9 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE issues DROP CONSTRAINT IF EXISTS issues_workflow_state_fkey;
+UPDATE issues
+SET status = 'open'
+WHERE status NOT IN ('open', 'in_progress', 'resolved', 'closed');
+ALTER TABLE issues
+    ADD CONSTRAINT valid_status CHECK (status IN ('open', 'in_progress', 'resolved', 'closed'));
+DROP TABLE IF EXISTS workflow_transitions;
+DROP TABLE IF EXISTS workflow_states;
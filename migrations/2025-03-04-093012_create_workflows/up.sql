@@ -0,0 +1,66 @@
+-- Per-project workflow states replace the hard-coded status list
+CREATE TABLE workflow_states (
+    id SERIAL PRIMARY KEY,
+    project_id INTEGER NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    name VARCHAR(50) NOT NULL,
+    category VARCHAR(10) NOT NULL,
+    position INTEGER NOT NULL DEFAULT 0,
+    is_initial BOOLEAN NOT NULL DEFAULT false,
+    CONSTRAINT workflow_states_project_name_key UNIQUE (project_id, name),
+    CONSTRAINT valid_category CHECK (category IN ('open', 'done'))
+);
+
+-- At most one initial state per project
+CREATE UNIQUE INDEX workflow_states_one_initial
+    ON workflow_states (project_id)
+    WHERE is_initial;
+
+CREATE TABLE workflow_transitions (
+    id SERIAL PRIMARY KEY,
+    project_id INTEGER NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    from_state_id INTEGER NOT NULL REFERENCES workflow_states(id) ON DELETE CASCADE,
+    to_state_id INTEGER NOT NULL REFERENCES workflow_states(id) ON DELETE CASCADE,
+    CONSTRAINT workflow_transitions_pair_key UNIQUE (from_state_id, to_state_id),
+    CONSTRAINT no_self_transition CHECK (from_state_id <> to_state_id)
+);
+
+-- Give every existing project the default workflow
+INSERT INTO workflow_states (project_id, name, category, position, is_initial)
+SELECT p.id, s.name, s.category, s.position, s.position = 0
+FROM projects p
+CROSS JOIN (VALUES
+    ('open', 'open', 0),
+    ('in_progress', 'open', 1),
+    ('resolved', 'done', 2),
+    ('closed', 'done', 3)
+) AS s(name, category, position);
+
+INSERT INTO workflow_transitions (project_id, from_state_id, to_state_id)
+SELECT f.project_id, f.id, t.id
+FROM (VALUES
+    ('open', 'in_progress'),
+    ('open', 'resolved'),
+    ('open', 'closed'),
+    ('in_progress', 'open'),
+    ('in_progress', 'resolved'),
+    ('in_progress', 'closed'),
+    ('resolved', 'open'),
+    ('resolved', 'closed'),
+    ('closed', 'open')
+) AS d(from_name, to_name)
+JOIN workflow_states f ON f.name = d.from_name
+JOIN workflow_states t ON t.name = d.to_name AND t.project_id = f.project_id;
+
+-- Issue status now has to name a state of the issue's project workflow
+ALTER TABLE issues DROP CONSTRAINT valid_status;
+ALTER TABLE issues
+    ADD CONSTRAINT issues_workflow_state_fkey
+    FOREIGN KEY (project_id, status)
+    REFERENCES workflow_states (project_id, name)
+    ON UPDATE CASCADE;
+
+-- is_open is derived from the state category from now on
+UPDATE issues i
+SET is_open = (s.category = 'open')
+FROM workflow_states s
+WHERE s.project_id = i.project_id AND s.name = i.status;
@@ -0,0 +1,15 @@
+-- This file should undo anything in `up.sql`
+ALTER TABLE issues
+    ADD COLUMN created_by VARCHAR(100),
+    ADD COLUMN assigned_to VARCHAR(100);
+
+UPDATE issues i SET created_by = u.username FROM users u WHERE u.id = i.created_by_id;
+UPDATE issues i SET assigned_to = u.username FROM users u WHERE u.id = i.assigned_to_id;
+
+ALTER TABLE issues
+    ALTER COLUMN created_by SET NOT NULL,
+    DROP COLUMN created_by_id,
+    DROP COLUMN assigned_to_id;
+
+DROP TRIGGER IF EXISTS update_users_updated_at ON users;
+DROP TABLE IF EXISTS users;
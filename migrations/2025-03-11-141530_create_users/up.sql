@@ -0,0 +1,39 @@
+CREATE TABLE users (
+    id SERIAL PRIMARY KEY,
+    username VARCHAR(100) NOT NULL UNIQUE,
+    display_name VARCHAR(100) NOT NULL,
+    email VARCHAR(255) UNIQUE,
+    is_active BOOLEAN NOT NULL DEFAULT true,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE TRIGGER update_users_updated_at
+    BEFORE UPDATE ON users
+    FOR EACH ROW
+    EXECUTE FUNCTION update_updated_at_column();
+
+-- Backfill one user per distinct name already stored on issues
+INSERT INTO users (username, display_name)
+SELECT name, name
+FROM (
+    SELECT created_by AS name FROM issues
+    UNION
+    SELECT assigned_to FROM issues WHERE assigned_to IS NOT NULL
+) AS names;
+
+-- Swap the free-text columns for foreign keys
+ALTER TABLE issues
+    ADD COLUMN created_by_id INTEGER REFERENCES users(id),
+    ADD COLUMN assigned_to_id INTEGER REFERENCES users(id) ON DELETE SET NULL;
+
+UPDATE issues i SET created_by_id = u.id FROM users u WHERE u.username = i.created_by;
+UPDATE issues i SET assigned_to_id = u.id FROM users u WHERE u.username = i.assigned_to;
+
+ALTER TABLE issues
+    ALTER COLUMN created_by_id SET NOT NULL,
+    DROP COLUMN created_by,
+    DROP COLUMN assigned_to;
+
+CREATE INDEX issues_created_by_id_idx ON issues (created_by_id);
+CREATE INDEX issues_assigned_to_id_idx ON issues (assigned_to_id);
@@ -0,0 +1,22 @@
+CREATE TABLE project_members (
+    project_id INTEGER NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    user_id INTEGER NOT NULL REFERENCES users(id) ON DELETE CASCADE,
+    role VARCHAR(20) NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (project_id, user_id),
+    CONSTRAINT valid_role CHECK (role IN ('viewer', 'reporter', 'developer', 'maintainer', 'admin'))
+);
+
+CREATE INDEX project_members_user_id_idx ON project_members (user_id);
+
+-- Keep existing users working: assignees become developers, other issue
+-- authors reporters, on the projects they already took part in
+INSERT INTO project_members (project_id, user_id, role)
+SELECT DISTINCT project_id, assigned_to_id, 'developer'
+FROM issues
+WHERE assigned_to_id IS NOT NULL;
+
+INSERT INTO project_members (project_id, user_id, role)
+SELECT DISTINCT project_id, created_by_id, 'reporter'
+FROM issues
+ON CONFLICT (project_id, user_id) DO NOTHING;
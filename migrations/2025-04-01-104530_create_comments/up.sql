@@ -0,0 +1,14 @@
+CREATE TABLE comments (
+    id SERIAL PRIMARY KEY,
+    issue_id INTEGER NOT NULL REFERENCES issues(id) ON DELETE CASCADE,
+    author_id INTEGER NOT NULL REFERENCES users(id),
+    parent_id INTEGER REFERENCES comments(id) ON DELETE CASCADE,
+    body TEXT NOT NULL,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    edited_at TIMESTAMPTZ,
+    -- Deleted comments keep their row so replies stay attached
+    deleted_at TIMESTAMPTZ
+);
+
+CREATE INDEX comments_issue_id_idx ON comments (issue_id);
+CREATE INDEX comments_author_id_idx ON comments (author_id);
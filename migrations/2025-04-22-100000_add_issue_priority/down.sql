@@ -0,0 +1,2 @@
+DROP INDEX issues_project_priority_idx;
+ALTER TABLE issues DROP COLUMN priority, DROP COLUMN severity;
@@ -0,0 +1,8 @@
+-- Priority is stored as its level so it sorts naturally: 0 (P0) is most urgent
+ALTER TABLE issues
+    ADD COLUMN priority SMALLINT NOT NULL DEFAULT 2,
+    ADD COLUMN severity VARCHAR(20) NOT NULL DEFAULT 'major',
+    ADD CONSTRAINT valid_priority CHECK (priority BETWEEN 0 AND 4),
+    ADD CONSTRAINT valid_severity CHECK (severity IN ('blocker', 'critical', 'major', 'minor', 'trivial'));
+
+CREATE INDEX issues_project_priority_idx ON issues (project_id, priority, created_at, id);
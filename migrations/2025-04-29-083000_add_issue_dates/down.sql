@@ -0,0 +1,2 @@
+DROP INDEX issues_open_due_date_idx;
+ALTER TABLE issues DROP COLUMN start_date, DROP COLUMN due_date;
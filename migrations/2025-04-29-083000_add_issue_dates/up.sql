@@ -0,0 +1,6 @@
+ALTER TABLE issues
+    ADD COLUMN start_date DATE,
+    ADD COLUMN due_date DATE,
+    ADD CONSTRAINT start_not_after_due CHECK (start_date IS NULL OR due_date IS NULL OR start_date <= due_date);
+
+CREATE INDEX issues_open_due_date_idx ON issues (due_date) WHERE is_open AND due_date IS NOT NULL;
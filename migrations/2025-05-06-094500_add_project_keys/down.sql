@@ -0,0 +1,3 @@
+ALTER TABLE issues DROP COLUMN number;
+ALTER TABLE projects DROP COLUMN key, DROP COLUMN next_issue_number;
+DROP TABLE project_keys;
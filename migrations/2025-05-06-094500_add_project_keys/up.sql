@@ -0,0 +1,56 @@
+-- Every key a project has ever had; old keys keep resolving after a rename
+CREATE TABLE project_keys (
+    key VARCHAR(10) PRIMARY KEY,
+    project_id INTEGER NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX project_keys_project_id_idx ON project_keys (project_id);
+
+ALTER TABLE projects
+    ADD COLUMN key VARCHAR(10),
+    ADD COLUMN next_issue_number INTEGER NOT NULL DEFAULT 1;
+
+-- Existing projects get up to four letters of their name, falling back to
+-- P<id> when that is too short or already taken
+WITH derived AS (
+    SELECT id, UPPER(LEFT(REGEXP_REPLACE(name, '[^A-Za-z]', '', 'g'), 4)) AS prefix
+    FROM projects
+), ranked AS (
+    SELECT id, prefix, ROW_NUMBER() OVER (PARTITION BY prefix ORDER BY id) AS rank
+    FROM derived
+)
+UPDATE projects
+SET key = CASE
+    WHEN LENGTH(ranked.prefix) >= 2 AND ranked.rank = 1 THEN ranked.prefix
+    ELSE 'P' || projects.id
+END
+FROM ranked
+WHERE ranked.id = projects.id;
+
+ALTER TABLE projects
+    ALTER COLUMN key SET NOT NULL,
+    ADD CONSTRAINT projects_key_unique UNIQUE (key),
+    ADD CONSTRAINT projects_key_format CHECK (key ~ '^[A-Z][A-Z0-9]{1,9}$');
+
+INSERT INTO project_keys (key, project_id)
+SELECT key, id FROM projects;
+
+ALTER TABLE issues ADD COLUMN number INTEGER;
+
+UPDATE issues
+SET number = numbered.number
+FROM (
+    SELECT id, ROW_NUMBER() OVER (PARTITION BY project_id ORDER BY id) AS number
+    FROM issues
+) AS numbered
+WHERE numbered.id = issues.id;
+
+ALTER TABLE issues
+    ALTER COLUMN number SET NOT NULL,
+    ADD CONSTRAINT issues_project_number_unique UNIQUE (project_id, number);
+
+UPDATE projects
+SET next_issue_number = COALESCE(
+    (SELECT MAX(number) FROM issues WHERE issues.project_id = projects.id), 0
+) + 1;
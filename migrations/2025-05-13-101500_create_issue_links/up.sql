@@ -0,0 +1,16 @@
+-- Directional relations between issues. Inverse types (blocked_by,
+-- duplicated_by, cloned_by) are stored as the forward type with source and
+-- target swapped.
+CREATE TABLE issue_links (
+    id SERIAL PRIMARY KEY,
+    source_id INTEGER NOT NULL REFERENCES issues(id) ON DELETE CASCADE,
+    target_id INTEGER NOT NULL REFERENCES issues(id) ON DELETE CASCADE,
+    link_type VARCHAR(20) NOT NULL
+        CHECK (link_type IN ('blocks', 'duplicates', 'relates_to', 'clones')),
+    created_by_id INTEGER NOT NULL REFERENCES users(id),
+    created_at TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    CONSTRAINT issue_links_not_self CHECK (source_id <> target_id),
+    CONSTRAINT issue_links_unique UNIQUE (source_id, target_id, link_type)
+);
+
+CREATE INDEX issue_links_target_id_idx ON issue_links (target_id);
@@ -0,0 +1,2 @@
+ALTER TABLE projects DROP COLUMN max_issue_depth;
+ALTER TABLE issues DROP COLUMN parent_id, DROP COLUMN original_estimate_minutes;
@@ -0,0 +1,12 @@
+ALTER TABLE issues
+    ADD COLUMN parent_id INTEGER REFERENCES issues(id) ON DELETE SET NULL,
+    ADD COLUMN original_estimate_minutes INTEGER,
+    ADD CONSTRAINT issues_not_own_parent CHECK (parent_id <> id),
+    ADD CONSTRAINT issues_estimate_not_negative CHECK (original_estimate_minutes >= 0);
+
+CREATE INDEX issues_parent_id_idx ON issues (parent_id);
+
+-- Levels of sub-issues allowed below a top-level issue
+ALTER TABLE projects
+    ADD COLUMN max_issue_depth SMALLINT NOT NULL DEFAULT 3
+        CHECK (max_issue_depth BETWEEN 1 AND 10);
@@ -0,0 +1,17 @@
+CREATE TABLE milestones (
+    id SERIAL PRIMARY KEY,
+    project_id INTEGER NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    name VARCHAR(100) NOT NULL,
+    description TEXT NOT NULL DEFAULT '',
+    target_date DATE,
+    released BOOLEAN NOT NULL DEFAULT FALSE,
+    released_at TIMESTAMPTZ,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (project_id, name),
+    CONSTRAINT released_at_when_released CHECK (released = (released_at IS NOT NULL))
+);
+
+ALTER TABLE issues
+    ADD COLUMN milestone_id INTEGER REFERENCES milestones(id) ON DELETE SET NULL;
+
+CREATE INDEX issues_milestone_id_idx ON issues (milestone_id);
@@ -0,0 +1,26 @@
+CREATE TABLE sprints (
+    id SERIAL PRIMARY KEY,
+    project_id INTEGER NOT NULL REFERENCES projects(id) ON DELETE CASCADE,
+    name VARCHAR(100) NOT NULL,
+    goal TEXT NOT NULL DEFAULT '',
+    state VARCHAR(20) NOT NULL DEFAULT 'planned'
+        CHECK (state IN ('planned', 'active', 'closed')),
+    start_date DATE,
+    end_date DATE,
+    started_at TIMESTAMPTZ,
+    completed_at TIMESTAMPTZ,
+    -- Issue counts at completion, before incomplete ones were moved out
+    completed_issues INTEGER,
+    incomplete_issues INTEGER,
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (project_id, name),
+    CONSTRAINT sprints_dates_ordered CHECK (start_date <= end_date)
+);
+
+-- At most one running sprint per project
+CREATE UNIQUE INDEX sprints_one_active_idx ON sprints (project_id) WHERE state = 'active';
+
+ALTER TABLE issues
+    ADD COLUMN sprint_id INTEGER REFERENCES sprints(id) ON DELETE SET NULL;
+
+CREATE INDEX issues_sprint_id_idx ON issues (sprint_id);
@@ -0,0 +1,2 @@
+DROP TABLE worklogs;
+ALTER TABLE issues DROP COLUMN estimate_points;
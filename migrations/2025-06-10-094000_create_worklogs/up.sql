@@ -0,0 +1,18 @@
+ALTER TABLE issues
+    ADD COLUMN estimate_points INTEGER,
+    ADD CONSTRAINT issues_points_not_negative CHECK (estimate_points >= 0);
+
+CREATE TABLE worklogs (
+    id SERIAL PRIMARY KEY,
+    issue_id INTEGER NOT NULL REFERENCES issues(id) ON DELETE CASCADE,
+    -- Logged time stays on record; users with worklogs are deactivated, not deleted
+    user_id INTEGER NOT NULL REFERENCES users(id),
+    minutes INTEGER NOT NULL CHECK (minutes BETWEEN 1 AND 1440),
+    work_date DATE NOT NULL,
+    note TEXT NOT NULL DEFAULT '',
+    created_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX worklogs_issue_id_idx ON worklogs (issue_id);
+CREATE INDEX worklogs_user_date_idx ON worklogs (user_id, work_date);
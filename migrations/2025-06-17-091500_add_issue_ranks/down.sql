@@ -0,0 +1,3 @@
+DROP INDEX issues_project_rank_idx;
+
+ALTER TABLE issues DROP COLUMN rank;
@@ -0,0 +1,17 @@
+-- Board order. Ranks compare byte by byte, so new positions can be made
+-- between any two neighbours without renumbering the rest.
+ALTER TABLE issues ADD COLUMN rank TEXT COLLATE "C";
+
+-- Existing issues keep their creation order in each project
+UPDATE issues
+SET rank = ranked.rank
+FROM (
+    SELECT id,
+           rtrim('i' || lpad(to_hex(row_number() OVER (PARTITION BY project_id ORDER BY id)), 7, '0'), '0') AS rank
+    FROM issues
+) AS ranked
+WHERE issues.id = ranked.id;
+
+ALTER TABLE issues ALTER COLUMN rank SET NOT NULL;
+
+CREATE UNIQUE INDEX issues_project_rank_idx ON issues (project_id, rank);
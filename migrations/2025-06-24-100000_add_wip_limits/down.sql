@@ -0,0 +1 @@
+ALTER TABLE workflow_states DROP COLUMN wip_limit;
@@ -0,0 +1,2 @@
+-- Most issues allowed in a status at once; NULL means no limit
+ALTER TABLE workflow_states ADD COLUMN wip_limit INTEGER CHECK (wip_limit > 0);
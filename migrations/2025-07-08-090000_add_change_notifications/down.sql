@@ -0,0 +1,4 @@
+DROP TRIGGER projects_notify_delete ON projects;
+DROP TRIGGER projects_notify_update ON projects;
+DROP TRIGGER issues_notify_change ON issues;
+DROP FUNCTION notify_change();
@@ -0,0 +1,41 @@
+-- Announces every committed change to issues and projects on the
+-- `ticket_manager_changes` channel, so that each server instance can pass
+-- changes made by the others on to its own subscribers. Payloads are kept
+-- small; listeners load the row themselves.
+CREATE FUNCTION notify_change() RETURNS trigger AS $$
+DECLARE
+    changed JSONB;
+BEGIN
+    IF TG_OP = 'DELETE' THEN
+        changed := to_jsonb(OLD);
+    ELSE
+        changed := to_jsonb(NEW);
+    END IF;
+
+    PERFORM pg_notify('ticket_manager_changes', json_build_object(
+        'table', TG_TABLE_NAME,
+        'op', lower(TG_OP),
+        'id', (changed ->> 'id')::INTEGER,
+        -- Column holding the project ID, passed by each trigger
+        'project_id', (changed ->> TG_ARGV[0])::INTEGER,
+        -- Pool connections are named after their server instance
+        'origin', current_setting('application_name')
+    )::TEXT);
+    RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER issues_notify_change
+    AFTER INSERT OR UPDATE OR DELETE ON issues
+    FOR EACH ROW EXECUTE FUNCTION notify_change('project_id');
+
+-- New projects have no subscribers yet, and numbering a new issue is not
+-- a change to the project
+CREATE TRIGGER projects_notify_update
+    AFTER UPDATE ON projects
+    FOR EACH ROW WHEN (OLD.next_issue_number = NEW.next_issue_number)
+    EXECUTE FUNCTION notify_change('id');
+
+CREATE TRIGGER projects_notify_delete
+    AFTER DELETE ON projects
+    FOR EACH ROW EXECUTE FUNCTION notify_change('id');
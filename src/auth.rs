@@ -0,0 +1,164 @@
+use crate::error::AppError;
+use crate::handlers::api_response::ApiResponse;
+use crate::models::{ApiToken, ProjectMember, ProjectRole, User};
+use crate::routes::router::AppState;
+
+use axum::{
+    extract::{FromRequestParts, Request, State},
+    http::{header, request::Parts, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+    Json,
+};
+use diesel::PgConnection;
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+
+/// Prefix of every issued token, so leaked tokens are easy to recognise.
+pub const TOKEN_PREFIX: &str = "tm_";
+
+/// Number of leading characters kept in clear to identify a token in listings.
+const DISPLAY_PREFIX_LEN: usize = 10;
+
+/// A fresh random token. Only its hash is ever stored.
+pub fn generate_token() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    format!("{}{}", TOKEN_PREFIX, to_hex(&bytes))
+}
+
+pub fn hash_token(token: &str) -> String {
+    to_hex(&Sha256::digest(token.as_bytes()))
+}
+
+/// The part of a token that may be shown again after it was issued.
+pub fn display_prefix(token: &str) -> &str {
+    &token[..DISPLAY_PREFIX_LEN.min(token.len())]
+}
+
+pub(crate) fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn unauthorized(message: &str) -> Response {
+    (
+        StatusCode::UNAUTHORIZED,
+        [(header::WWW_AUTHENTICATE, "Bearer")],
+        Json(ApiResponse::<()>::error(message)),
+    )
+        .into_response()
+}
+
+/// `?access_token=` of a WebSocket handshake, for browsers, which cannot
+/// set headers on one. Other requests must use the header so tokens stay
+/// out of URLs.
+fn query_token(parts: &Parts) -> Option<&str> {
+    let upgrade = parts.headers.get(header::UPGRADE)?.to_str().ok()?;
+    if !upgrade.eq_ignore_ascii_case("websocket") {
+        return None;
+    }
+    parts
+        .uri
+        .query()?
+        .split('&')
+        .find_map(|pair| pair.strip_prefix("access_token="))
+        .filter(|token| !token.is_empty())
+}
+
+fn bearer_token(parts: &Parts) -> Option<&str> {
+    let Some(value) = parts.headers.get(header::AUTHORIZATION) else {
+        return query_token(parts);
+    };
+    let value = value.to_str().ok()?;
+    let (scheme, token) = value.split_once(' ')?;
+    if !scheme.eq_ignore_ascii_case("bearer") {
+        return None;
+    }
+    Some(token.trim()).filter(|token| !token.is_empty())
+}
+
+/// Layer body: resolves the bearer token to a user and stores it in the
+/// request extensions for the `AuthUser` extractor, or answers 401.
+pub async fn require_auth(
+    State(state): State<Arc<AppState>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let (parts, body) = request.into_parts();
+    let Some(token) = bearer_token(&parts) else {
+        return unauthorized("Missing bearer token");
+    };
+    let token_hash = hash_token(token);
+
+    let mut conn = match state.pool.get() {
+        Ok(conn) => conn,
+        Err(err) => {
+            tracing::error!("Database connection failed: {:?}", err);
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error("Database connection failed")),
+            )
+                .into_response();
+        }
+    };
+
+    match ApiToken::authenticate(&mut conn, &token_hash) {
+        Ok(Some(user)) => {
+            drop(conn);
+            let mut request = Request::from_parts(parts, body);
+            request.extensions_mut().insert(AuthUser(user));
+            next.run(request).await
+        }
+        Ok(None) => unauthorized("Invalid or expired token"),
+        Err(err) => {
+            tracing::error!("Failed to authenticate token: {:?}", err);
+            (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<()>::error("Failed to authenticate token")),
+            )
+                .into_response()
+        }
+    }
+}
+
+/// The user behind the request's bearer token. Only available on routes
+/// behind `require_auth`.
+#[derive(Debug, Clone)]
+pub struct AuthUser(pub User);
+
+impl<S> FromRequestParts<S> for AuthUser
+where
+    S: Send + Sync,
+{
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts
+            .extensions
+            .get::<AuthUser>()
+            .cloned()
+            .ok_or_else(|| unauthorized("Authentication required"))
+    }
+}
+
+/// The user's role on the project, provided it is at least `required`.
+/// Global administrators act as project admins everywhere.
+pub fn require_project_role(
+    conn: &mut PgConnection,
+    user: &User,
+    project_id: i32,
+    required: ProjectRole,
+) -> Result<ProjectRole, AppError> {
+    if user.is_admin {
+        return Ok(ProjectRole::Admin);
+    }
+
+    match ProjectMember::role_of(conn, project_id, user.id)? {
+        Some(role) if role >= required => Ok(role),
+        _ => Err(AppError::Forbidden(format!(
+            "Requires the {} role on project {}",
+            required, project_id
+        ))),
+    }
+}
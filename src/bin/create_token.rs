@@ -0,0 +1,70 @@
+//! Issues an API token from the command line, for bootstrapping the first
+//! administrator before any token exists to call the admin endpoints with.
+//!
+//! Usage: `cargo run --bin create_token -- <username> [--admin]`
+
+use diesel::prelude::*;
+use dotenvy::dotenv;
+
+use ticket_manager::auth::{display_prefix, generate_token, hash_token};
+use ticket_manager::config::DatabaseConfig;
+use ticket_manager::db::create_pool;
+use ticket_manager::db::schema::users;
+use ticket_manager::models::{ApiToken, NewApiToken, NewUser, User};
+
+fn main() {
+    dotenv().ok();
+
+    let mut args = std::env::args().skip(1);
+    let Some(username) = args.next() else {
+        eprintln!("Usage: create_token <username> [--admin]");
+        std::process::exit(2);
+    };
+    let make_admin = args.any(|arg| arg == "--admin");
+
+    let config = DatabaseConfig::from_env().expect("Failed to load database configuration");
+    let pool = create_pool(&config.connection_string);
+    let mut conn = pool.get().expect("Failed to get DB connection");
+
+    let user = users::table
+        .filter(users::username.eq(&username))
+        .select(User::as_select())
+        .first(&mut conn)
+        .optional()
+        .expect("Failed to look up user");
+    let user = match user {
+        Some(user) => user,
+        None => User::create(
+            &mut conn,
+            &NewUser {
+                username: &username,
+                display_name: &username,
+                email: None,
+            },
+        )
+        .expect("Failed to create user"),
+    };
+
+    if make_admin && !user.is_admin {
+        diesel::update(users::table.find(user.id))
+            .set(users::is_admin.eq(true))
+            .execute(&mut conn)
+            .expect("Failed to grant admin");
+    }
+
+    let token = generate_token();
+    let token_hash = hash_token(&token);
+    ApiToken::create(
+        &mut conn,
+        &NewApiToken {
+            user_id: user.id,
+            name: "cli",
+            token_prefix: display_prefix(&token),
+            token_hash: &token_hash,
+            expires_at: None,
+        },
+    )
+    .expect("Failed to create token");
+
+    println!("{}", token);
+}
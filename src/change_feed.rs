@@ -0,0 +1,224 @@
+//! Changes made by other server instances, fed into the local event bus.
+//!
+//! Database triggers announce every committed change to issues and projects
+//! with `pg_notify`. Each instance listens on a dedicated connection and
+//! publishes the changes it did not make itself; its own are published by
+//! the handlers, with more detail. Changes committed while the listener is
+//! reconnecting are not replayed.
+
+use crate::error::AppError;
+use crate::event_bus::EVENT_PROJECT_DELETED;
+use crate::handlers::issue::IssueResponse;
+use crate::handlers::project::ProjectResponse;
+use crate::models::{
+    Issue, Project, EVENT_ISSUE_CREATED, EVENT_ISSUE_DELETED, EVENT_ISSUE_UPDATED,
+    EVENT_PROJECT_UPDATED,
+};
+use crate::routes::router::AppState;
+
+use diesel::prelude::*;
+use diesel::sql_query;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use std::convert::Infallible;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// Channel the triggers notify on.
+pub const CHANNEL: &str = "ticket_manager_changes";
+/// `application_name` of the listening connection.
+pub const LISTENER_NAME: &str = "ticket_manager:change-feed";
+
+/// Identifies this process to the triggers. Pool connections carry it as
+/// their `application_name`, so the listener can skip its own changes.
+pub fn instance_id() -> &'static str {
+    static INSTANCE_ID: OnceLock<String> = OnceLock::new();
+    INSTANCE_ID.get_or_init(|| {
+        let mut bytes = [0u8; 6];
+        rand::thread_rng().fill_bytes(&mut bytes);
+        format!("ticket_manager:{}", crate::auth::to_hex(&bytes))
+    })
+}
+
+#[derive(Debug, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+enum Operation {
+    Insert,
+    Update,
+    Delete,
+}
+
+/// A trigger's notification payload.
+#[derive(Debug, Deserialize)]
+struct Change {
+    table: String,
+    op: Operation,
+    id: i32,
+    project_id: i32,
+    origin: String,
+}
+
+/// Event data for a deleted row, which can no longer be loaded.
+#[derive(Debug, Serialize)]
+struct DeletedRow {
+    id: i32,
+    project_id: i32,
+}
+
+#[derive(Debug, Clone)]
+pub struct FeedConfig {
+    /// Pause between checks for notifications.
+    pub poll_interval: Duration,
+    /// How often an idle connection is checked, so a silently dropped one
+    /// is noticed.
+    pub health_check_interval: Duration,
+    /// Delay before the first reconnection attempt; it doubles with every
+    /// failed one.
+    pub min_reconnect_delay: Duration,
+    pub max_reconnect_delay: Duration,
+}
+
+impl Default for FeedConfig {
+    fn default() -> Self {
+        FeedConfig {
+            poll_interval: Duration::from_millis(100),
+            health_check_interval: Duration::from_secs(30),
+            min_reconnect_delay: Duration::from_secs(1),
+            max_reconnect_delay: Duration::from_secs(30),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct FeedStatus {
+    sessions: AtomicU64,
+}
+
+impl FeedStatus {
+    /// Connections the listener has started listening on, reconnects
+    /// included.
+    pub fn sessions(&self) -> u64 {
+        self.sessions.load(Ordering::SeqCst)
+    }
+}
+
+/// Publishes one change, loading the row so the event carries the same data
+/// as one published by the handlers.
+fn publish(state: &AppState, change: Change) -> Result<(), AppError> {
+    let events = &state.events;
+    let deleted = DeletedRow {
+        id: change.id,
+        project_id: change.project_id,
+    };
+    match (change.table.as_str(), change.op) {
+        ("issues", Operation::Delete) => {
+            events.publish_external(change.project_id, EVENT_ISSUE_DELETED, &deleted);
+        }
+        ("issues", op) => {
+            let mut conn = state.pool.get()?;
+            // Gone again by now; its deletion follows
+            let Some(issue) = Issue::get_by_id(&mut conn, change.id).optional()? else {
+                return Ok(());
+            };
+            let event = if op == Operation::Insert {
+                EVENT_ISSUE_CREATED
+            } else {
+                EVENT_ISSUE_UPDATED
+            };
+            let response = IssueResponse::load(&mut conn, issue)?;
+            events.publish_external(change.project_id, event, &response);
+        }
+        ("projects", Operation::Delete) => {
+            events.publish_external(change.project_id, EVENT_PROJECT_DELETED, &deleted);
+        }
+        ("projects", _) => {
+            let mut conn = state.pool.get()?;
+            if let Some(project) = Project::find_by_id(&mut conn, change.id)? {
+                let response = ProjectResponse::from(project);
+                events.publish_external(change.project_id, EVENT_PROJECT_UPDATED, &response);
+            }
+        }
+        (table, _) => tracing::warn!("Ignoring change notification for table {}", table),
+    }
+    Ok(())
+}
+
+fn handle(state: &AppState, payload: &str) {
+    let change: Change = match serde_json::from_str(payload) {
+        Ok(change) => change,
+        Err(err) => {
+            tracing::warn!("Ignoring malformed change notification: {}", err);
+            return;
+        }
+    };
+    if change.origin == instance_id() {
+        return;
+    }
+    if let Err(err) = publish(state, change) {
+        tracing::error!("Failed to publish change from another instance: {}", err);
+    }
+}
+
+/// Listens until the connection fails.
+fn listen(
+    state: &AppState,
+    database_url: &str,
+    config: &FeedConfig,
+    status: &FeedStatus,
+) -> anyhow::Result<Infallible> {
+    let mut conn = PgConnection::establish(database_url)?;
+    sql_query(format!("SET application_name = '{}'", LISTENER_NAME)).execute(&mut conn)?;
+    sql_query(format!("LISTEN {}", CHANNEL)).execute(&mut conn)?;
+    status.sessions.fetch_add(1, Ordering::SeqCst);
+    tracing::info!("Listening for changes from other instances");
+
+    let mut last_checked = Instant::now();
+    loop {
+        // Fails once the server has closed the connection
+        let notifications = conn.notifications_iter().collect::<QueryResult<Vec<_>>>()?;
+        if notifications.is_empty() {
+            if last_checked.elapsed() >= config.health_check_interval {
+                sql_query("SELECT 1").execute(&mut conn)?;
+                last_checked = Instant::now();
+            }
+            thread::sleep(config.poll_interval);
+            continue;
+        }
+
+        for notification in notifications {
+            handle(state, &notification.payload);
+        }
+        last_checked = Instant::now();
+    }
+}
+
+/// Runs the listener on its own thread until the process exits,
+/// reconnecting with backoff whenever the connection is lost.
+pub fn spawn(state: Arc<AppState>, database_url: String, config: FeedConfig) -> Arc<FeedStatus> {
+    let status = Arc::new(FeedStatus::default());
+    let running = status.clone();
+    thread::Builder::new()
+        .name("change-feed".to_string())
+        .spawn(move || {
+            let mut delay = config.min_reconnect_delay;
+            loop {
+                let sessions = running.sessions();
+                let Err(err) = listen(&state, &database_url, &config, &running);
+                // Only consecutive failures back off further
+                if running.sessions() > sessions {
+                    delay = config.min_reconnect_delay;
+                }
+                tracing::warn!(
+                    "Change feed connection lost: {}; reconnecting in {:?}",
+                    err,
+                    delay
+                );
+                thread::sleep(delay);
+                delay = (delay * 2).min(config.max_reconnect_delay);
+            }
+        })
+        .expect("Failed to start the change feed thread");
+    status
+}
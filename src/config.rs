@@ -26,3 +26,10 @@ impl DatabaseConfig {
         Ok(Self { connection_string })
     }
 }
+
+/// Whether webhooks may point at loopback, private and link-local
+/// addresses. Off unless `WEBHOOK_ALLOW_PRIVATE_HOSTS=true`, for setups that
+/// deliver to services on the server's own network.
+pub fn allow_private_webhook_hosts() -> bool {
+    std::env::var("WEBHOOK_ALLOW_PRIVATE_HOSTS").is_ok_and(|value| value == "true")
+}
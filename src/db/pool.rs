@@ -1,11 +1,28 @@
+use crate::change_feed::instance_id;
 use diesel::pg::PgConnection;
-use diesel::r2d2::{self, ConnectionManager};
+use diesel::r2d2::{self, ConnectionManager, CustomizeConnection};
+use diesel::{sql_query, RunQueryDsl};
 pub type PgPool = r2d2::Pool<ConnectionManager<PgConnection>>;
 
+/// Names each connection after this instance, which the change triggers
+/// record as the origin of a change.
+#[derive(Debug)]
+struct TagWithInstance;
+
+impl CustomizeConnection<PgConnection, r2d2::Error> for TagWithInstance {
+    fn on_acquire(&self, conn: &mut PgConnection) -> Result<(), r2d2::Error> {
+        sql_query(format!("SET application_name = '{}'", instance_id()))
+            .execute(conn)
+            .map(|_| ())
+            .map_err(r2d2::Error::QueryError)
+    }
+}
+
 pub fn create_pool(database_url: &str) -> PgPool {
     let manager = ConnectionManager::<PgConnection>::new(database_url);
     r2d2::Pool::builder()
         .max_size(15)
+        .connection_customizer(Box::new(TagWithInstance))
         .build(manager)
         .expect("Failed to create pool.")
 }
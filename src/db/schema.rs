@@ -1,5 +1,69 @@
 // @generated automatically by Diesel CLI.
 
+diesel::table! {
+    api_tokens (id) {
+        id -> Int4,
+        user_id -> Int4,
+        #[max_length = 100]
+        name -> Varchar,
+        #[max_length = 12]
+        token_prefix -> Varchar,
+        #[max_length = 64]
+        token_hash -> Varchar,
+        created_at -> Timestamptz,
+        last_used_at -> Nullable<Timestamptz>,
+        expires_at -> Nullable<Timestamptz>,
+        revoked_at -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    audit_log (id) {
+        id -> Int8,
+        #[max_length = 20]
+        entity_type -> Varchar,
+        entity_id -> Int4,
+        #[max_length = 50]
+        field -> Varchar,
+        old_value -> Nullable<Text>,
+        new_value -> Nullable<Text>,
+        actor_id -> Int4,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    comments (id) {
+        id -> Int4,
+        issue_id -> Int4,
+        author_id -> Int4,
+        parent_id -> Nullable<Int4>,
+        body -> Text,
+        created_at -> Timestamptz,
+        edited_at -> Nullable<Timestamptz>,
+        deleted_at -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    issue_labels (issue_id, label_id) {
+        issue_id -> Int4,
+        label_id -> Int4,
+    }
+}
+
+diesel::table! {
+    issue_links (id) {
+        id -> Int4,
+        source_id -> Int4,
+        target_id -> Int4,
+        #[max_length = 20]
+        link_type -> Varchar,
+        created_by_id -> Int4,
+        created_at -> Timestamptz,
+    }
+}
+
 diesel::table! {
     issues (id) {
         id -> Int4,
@@ -7,15 +71,71 @@ diesel::table! {
         #[max_length = 200]
         title -> Varchar,
         description -> Text,
-        #[max_length = 100]
-        created_by -> Varchar,
-        #[max_length = 100]
-        assigned_to -> Nullable<Varchar>,
         #[max_length = 50]
         status -> Varchar,
         created_at -> Timestamptz,
         updated_at -> Timestamptz,
         is_open -> Bool,
+        created_by_id -> Int4,
+        assigned_to_id -> Nullable<Int4>,
+        priority -> Int2,
+        #[max_length = 20]
+        severity -> Varchar,
+        start_date -> Nullable<Date>,
+        due_date -> Nullable<Date>,
+        number -> Int4,
+        parent_id -> Nullable<Int4>,
+        original_estimate_minutes -> Nullable<Int4>,
+        milestone_id -> Nullable<Int4>,
+        sprint_id -> Nullable<Int4>,
+        estimate_points -> Nullable<Int4>,
+        rank -> Text,
+    }
+}
+
+diesel::table! {
+    labels (id) {
+        id -> Int4,
+        project_id -> Int4,
+        #[max_length = 50]
+        name -> Varchar,
+        #[max_length = 7]
+        color -> Varchar,
+        description -> Text,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    milestones (id) {
+        id -> Int4,
+        project_id -> Int4,
+        #[max_length = 100]
+        name -> Varchar,
+        description -> Text,
+        target_date -> Nullable<Date>,
+        released -> Bool,
+        released_at -> Nullable<Timestamptz>,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    project_keys (key) {
+        #[max_length = 10]
+        key -> Varchar,
+        project_id -> Int4,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    project_members (project_id, user_id) {
+        project_id -> Int4,
+        user_id -> Int4,
+        #[max_length = 20]
+        role -> Varchar,
+        created_at -> Timestamptz,
     }
 }
 
@@ -27,9 +147,156 @@ diesel::table! {
         description -> Text,
         created_at -> Timestamptz,
         updated_at -> Timestamptz,
+        #[max_length = 10]
+        key -> Varchar,
+        next_issue_number -> Int4,
+        max_issue_depth -> Int2,
+    }
+}
+
+diesel::table! {
+    sprints (id) {
+        id -> Int4,
+        project_id -> Int4,
+        #[max_length = 100]
+        name -> Varchar,
+        goal -> Text,
+        #[max_length = 20]
+        state -> Varchar,
+        start_date -> Nullable<Date>,
+        end_date -> Nullable<Date>,
+        started_at -> Nullable<Timestamptz>,
+        completed_at -> Nullable<Timestamptz>,
+        completed_issues -> Nullable<Int4>,
+        incomplete_issues -> Nullable<Int4>,
+        created_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    users (id) {
+        id -> Int4,
+        #[max_length = 100]
+        username -> Varchar,
+        #[max_length = 100]
+        display_name -> Varchar,
+        #[max_length = 255]
+        email -> Nullable<Varchar>,
+        is_active -> Bool,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+        is_admin -> Bool,
+    }
+}
+
+diesel::table! {
+    webhook_deliveries (id) {
+        id -> Int4,
+        webhook_id -> Int4,
+        #[max_length = 50]
+        event -> Varchar,
+        payload -> Text,
+        #[max_length = 20]
+        status -> Varchar,
+        attempts -> Int4,
+        next_attempt_at -> Timestamptz,
+        last_attempt_at -> Nullable<Timestamptz>,
+        response_status -> Nullable<Int4>,
+        last_error -> Nullable<Text>,
+        created_at -> Timestamptz,
+        delivered_at -> Nullable<Timestamptz>,
+    }
+}
+
+diesel::table! {
+    webhooks (id) {
+        id -> Int4,
+        project_id -> Int4,
+        url -> Text,
+        secret -> Text,
+        events -> Array<Text>,
+        is_active -> Bool,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
+    }
+}
+
+diesel::table! {
+    workflow_states (id) {
+        id -> Int4,
+        project_id -> Int4,
+        #[max_length = 50]
+        name -> Varchar,
+        #[max_length = 10]
+        category -> Varchar,
+        position -> Int4,
+        is_initial -> Bool,
+        wip_limit -> Nullable<Int4>,
+    }
+}
+
+diesel::table! {
+    workflow_transitions (id) {
+        id -> Int4,
+        project_id -> Int4,
+        from_state_id -> Int4,
+        to_state_id -> Int4,
+    }
+}
+
+diesel::table! {
+    worklogs (id) {
+        id -> Int4,
+        issue_id -> Int4,
+        user_id -> Int4,
+        minutes -> Int4,
+        work_date -> Date,
+        note -> Text,
+        created_at -> Timestamptz,
+        updated_at -> Timestamptz,
     }
 }
 
+diesel::joinable!(api_tokens -> users (user_id));
+diesel::joinable!(audit_log -> users (actor_id));
+diesel::joinable!(comments -> issues (issue_id));
+diesel::joinable!(comments -> users (author_id));
+diesel::joinable!(issue_labels -> issues (issue_id));
+diesel::joinable!(issue_labels -> labels (label_id));
+diesel::joinable!(issue_links -> users (created_by_id));
+diesel::joinable!(issues -> milestones (milestone_id));
 diesel::joinable!(issues -> projects (project_id));
+diesel::joinable!(issues -> sprints (sprint_id));
+diesel::joinable!(labels -> projects (project_id));
+diesel::joinable!(milestones -> projects (project_id));
+diesel::joinable!(project_keys -> projects (project_id));
+diesel::joinable!(project_members -> projects (project_id));
+diesel::joinable!(project_members -> users (user_id));
+diesel::joinable!(sprints -> projects (project_id));
+diesel::joinable!(webhook_deliveries -> webhooks (webhook_id));
+diesel::joinable!(webhooks -> projects (project_id));
+diesel::joinable!(workflow_states -> projects (project_id));
+diesel::joinable!(workflow_transitions -> projects (project_id));
+diesel::joinable!(worklogs -> issues (issue_id));
+diesel::joinable!(worklogs -> users (user_id));
 
-diesel::allow_tables_to_appear_in_same_query!(issues, projects,);
+diesel::allow_tables_to_appear_in_same_query!(
+    api_tokens,
+    audit_log,
+    comments,
+    issue_labels,
+    issue_links,
+    issues,
+    labels,
+    milestones,
+    project_keys,
+    project_members,
+    projects,
+    sprints,
+    users,
+    webhook_deliveries,
+    webhooks,
+    workflow_states,
+    workflow_transitions,
+    worklogs,
+);
@@ -22,9 +22,30 @@ pub enum AppError {
     #[error("Issue not found: {0}")]
     IssueNotFound(i32),
 
+    #[error("Comment not found: {0}")]
+    CommentNotFound(i32),
+
+    #[error("Link not found: {0}")]
+    LinkNotFound(i32),
+
+    #[error("Milestone not found: {0}")]
+    MilestoneNotFound(i32),
+
+    #[error("Sprint not found: {0}")]
+    SprintNotFound(i32),
+
+    #[error("Worklog not found: {0}")]
+    WorklogNotFound(i32),
+
+    #[error("Webhook not found: {0}")]
+    WebhookNotFound(i32),
+
     #[error("Validation error: {0}")]
     Validation(String),
 
+    #[error("Forbidden: {0}")]
+    Forbidden(String),
+
     #[error("Serialization error: {0}")]
     Serialization(#[from] serde_json::Error),
 }
@@ -32,8 +53,16 @@ pub enum AppError {
 impl From<AppError> for (i32, String) {
     fn from(error: AppError) -> Self {
         match error {
-            AppError::ProjectNotFound(_) | AppError::IssueNotFound(_) => (404, error.to_string()),
+            AppError::ProjectNotFound(_)
+            | AppError::IssueNotFound(_)
+            | AppError::CommentNotFound(_)
+            | AppError::LinkNotFound(_)
+            | AppError::MilestoneNotFound(_)
+            | AppError::SprintNotFound(_)
+            | AppError::WorklogNotFound(_)
+            | AppError::WebhookNotFound(_) => (404, error.to_string()),
             AppError::Validation(_) => (400, error.to_string()),
+            AppError::Forbidden(_) => (403, error.to_string()),
             AppError::Database(diesel_error) => match diesel_error {
                 DieselError::NotFound => (404, "Record not found".to_string()),
                 DieselError::DatabaseError(kind, _) => {
@@ -51,6 +80,20 @@ impl From<AppError> for (i32, String) {
     }
 }
 
+impl AppError {
+    /// The error in the `(StatusCode, Json<ApiResponse<T>>)` shape handlers return.
+    pub fn into_api_response<T>(self) -> (StatusCode, Json<ApiResponse<T>>) {
+        let log_message = self.to_string();
+        let (code, message) = <(i32, String)>::from(self);
+        if code >= 500 {
+            tracing::error!("{}", log_message);
+        }
+
+        let status = StatusCode::from_u16(code as u16).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+        (status, Json(ApiResponse::Error { error: message }))
+    }
+}
+
 pub fn internal_server_error<T>(
     message: &str,
     err: &dyn std::error::Error,
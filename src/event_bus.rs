@@ -0,0 +1,187 @@
+//! In-process fan-out of project activity to live subscribers.
+//!
+//! Mutating handlers publish after their change has committed; the SSE
+//! endpoint subscribes. The most recent events are kept so that a client
+//! reconnecting with `Last-Event-ID` can catch up on what it missed.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use tokio::sync::broadcast;
+
+pub const EVENT_COMMENT_CREATED: &str = "comment.created";
+pub const EVENT_COMMENT_UPDATED: &str = "comment.updated";
+pub const EVENT_COMMENT_DELETED: &str = "comment.deleted";
+pub const EVENT_PROJECT_DELETED: &str = "project.deleted";
+
+/// Events kept for replay across all projects, and the most a slow
+/// subscriber may fall behind before it is told it missed some.
+pub const DEFAULT_CAPACITY: usize = 1024;
+
+/// What subscribers and webhooks receive for every event.
+#[derive(Serialize)]
+pub struct EventEnvelope<'a, T> {
+    pub event: &'a str,
+    pub project_id: i32,
+    /// User whose request caused the event; unknown for changes made by
+    /// another server instance.
+    pub actor_id: Option<i32>,
+    pub occurred_at: DateTime<Utc>,
+    pub data: &'a T,
+}
+
+impl<'a, T: Serialize> EventEnvelope<'a, T> {
+    pub fn new(event: &'a str, project_id: i32, actor_id: Option<i32>, data: &'a T) -> Self {
+        EventEnvelope {
+            event,
+            project_id,
+            actor_id,
+            occurred_at: Utc::now(),
+            data,
+        }
+    }
+}
+
+/// A published event. `data` is its serialized `EventEnvelope`.
+#[derive(Debug)]
+pub struct ProjectEvent {
+    /// Increases by one with every event published. IDs start from the
+    /// time the process started, so those of an earlier run are older.
+    pub id: u64,
+    pub project_id: i32,
+    pub event: String,
+    pub data: String,
+}
+
+/// A new subscriber's starting point.
+pub struct Subscription {
+    /// Buffered events of the project after the requested ID.
+    pub replay: Vec<Arc<ProjectEvent>>,
+    /// Set when the requested ID is no longer buffered, or was not issued
+    /// by this process; the client has to reload instead.
+    pub missed: bool,
+    /// Where to resume from to get exactly the events after this call.
+    pub last_id: u64,
+    /// Everything published from now on, for every project.
+    pub receiver: broadcast::Receiver<Arc<ProjectEvent>>,
+}
+
+struct History {
+    next_id: u64,
+    events: VecDeque<Arc<ProjectEvent>>,
+}
+
+pub struct EventBus {
+    sender: broadcast::Sender<Arc<ProjectEvent>>,
+    capacity: usize,
+    history: Mutex<History>,
+}
+
+impl EventBus {
+    pub fn new(capacity: usize) -> Self {
+        let (sender, _) = broadcast::channel(capacity);
+        EventBus {
+            sender,
+            capacity,
+            history: Mutex::new(History {
+                next_id: Utc::now().timestamp_micros().max(1) as u64,
+                events: VecDeque::with_capacity(capacity),
+            }),
+        }
+    }
+
+    /// Publishes `data` to the project's subscribers. Call it only once the
+    /// change is committed; subscribers may act on it immediately.
+    pub fn publish<T: Serialize>(&self, project_id: i32, event: &str, actor_id: i32, data: &T) {
+        self.publish_envelope(project_id, event, Some(actor_id), data);
+    }
+
+    /// Publishes a change made by another server instance, whose actor is
+    /// not known here.
+    pub fn publish_external<T: Serialize>(&self, project_id: i32, event: &str, data: &T) {
+        self.publish_envelope(project_id, event, None, data);
+    }
+
+    fn publish_envelope<T: Serialize>(
+        &self,
+        project_id: i32,
+        event: &str,
+        actor_id: Option<i32>,
+        data: &T,
+    ) {
+        let data =
+            match serde_json::to_string(&EventEnvelope::new(event, project_id, actor_id, data)) {
+                Ok(data) => data,
+                Err(err) => {
+                    tracing::error!("Failed to serialize {} event: {:?}", event, err);
+                    return;
+                }
+            };
+
+        // Sending under the lock keeps IDs in order for subscribers
+        let mut history = self.history.lock().expect("event history lock poisoned");
+        let published = Arc::new(ProjectEvent {
+            id: history.next_id,
+            project_id,
+            event: event.to_string(),
+            data,
+        });
+        history.next_id += 1;
+        if history.events.len() == self.capacity {
+            history.events.pop_front();
+        }
+        history.events.push_back(published.clone());
+        // Nobody listening is fine
+        let _ = self.sender.send(published);
+    }
+
+    /// Everything published from now on, for every project.
+    pub fn receiver(&self) -> broadcast::Receiver<Arc<ProjectEvent>> {
+        self.sender.subscribe()
+    }
+
+    /// Subscribes to the project, resuming after `last_event_id` if given.
+    pub fn subscribe(&self, project_id: i32, last_event_id: Option<u64>) -> Subscription {
+        let history = self.history.lock().expect("event history lock poisoned");
+        let receiver = self.sender.subscribe();
+        let last_id = history.next_id - 1;
+        let Some(last_event_id) = last_event_id else {
+            return Subscription {
+                replay: Vec::new(),
+                missed: false,
+                last_id,
+                receiver,
+            };
+        };
+
+        let oldest = history
+            .events
+            .front()
+            .map_or(history.next_id, |event| event.id);
+        let missed = last_event_id >= history.next_id || last_event_id + 1 < oldest;
+        let replay = if missed {
+            Vec::new()
+        } else {
+            history
+                .events
+                .iter()
+                .filter(|event| event.id > last_event_id && event.project_id == project_id)
+                .cloned()
+                .collect()
+        };
+
+        Subscription {
+            replay,
+            missed,
+            last_id,
+            receiver,
+        }
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        EventBus::new(DEFAULT_CAPACITY)
+    }
+}
@@ -4,7 +4,16 @@ use serde::Serialize;
 #[serde(untagged)]
 pub enum ApiResponse<T> {
     Success(T),
-    Error { error: String },
+    Error {
+        error: String,
+    },
+    /// An error a client can act on programmatically: `code` is stable and
+    /// `details` carries whatever context the code implies.
+    DetailedError {
+        error: String,
+        code: String,
+        details: serde_json::Value,
+    },
 }
 
 impl<T> ApiResponse<T> {
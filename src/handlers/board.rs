@@ -0,0 +1,272 @@
+use crate::auth::{require_project_role, AuthUser};
+use crate::error::{internal_server_error, AppError};
+use crate::handlers::api_response::ApiResponse;
+use crate::handlers::comment::find_issue;
+use crate::handlers::issue::{
+    check_status_change, check_wip_limit, IssueResponse, Rejection, UpdateIssueParams,
+};
+use crate::handlers::webhook::queue_event;
+use crate::models::{
+    rank_after, rank_between, Issue, IssueChanges, IssueFilter, LabelChanges, ProjectRole,
+    Workflow, EVENT_ISSUE_UPDATED,
+};
+use crate::routes::router::AppState;
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use diesel::prelude::*;
+use diesel::result::Error as DieselError;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+#[derive(Debug, Deserialize)]
+pub struct BoardParams {
+    pub sprint_id: Option<i32>,
+    pub milestone_id: Option<i32>,
+    pub assigned_to: Option<i32>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BoardColumn {
+    pub status: String,
+    pub category: String,
+    /// Issues in this status across the whole project, which is what the
+    /// WIP limit counts even when the board is filtered.
+    pub issue_count: i64,
+    pub wip_limit: Option<i32>,
+    pub over_limit: bool,
+    /// Cards in rank order.
+    pub issues: Vec<IssueResponse>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct BoardResponse {
+    /// One column per workflow state, in workflow order.
+    pub columns: Vec<BoardColumn>,
+}
+
+/// Where to put a card. Without `after_id` or `before_id` it goes to the
+/// bottom of its column.
+#[derive(Debug, Deserialize)]
+pub struct MoveIssueRequest {
+    /// Column to move to; defaults to the current status.
+    pub status: Option<String>,
+    /// Card that should end up directly above this one.
+    pub after_id: Option<i32>,
+    /// Card that should end up directly below this one.
+    pub before_id: Option<i32>,
+}
+
+fn bad_request(error: String) -> Rejection<IssueResponse> {
+    (StatusCode::BAD_REQUEST, Json(ApiResponse::Error { error }))
+}
+
+pub async fn get_board(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
+    Path(project_id): Path<i32>,
+    Query(params): Query<BoardParams>,
+) -> impl IntoResponse {
+    let mut conn = match state.pool.get() {
+        Ok(conn) => conn,
+        Err(err) => {
+            return internal_server_error::<BoardResponse>("Database connection failed", &err);
+        }
+    };
+
+    if let Err(err) = require_project_role(&mut conn, &user, project_id, ProjectRole::Viewer) {
+        return err.into_api_response();
+    }
+
+    let filter = IssueFilter {
+        project_id: Some(project_id),
+        sprint_id: params.sprint_id,
+        milestone_id: params.milestone_id,
+        assigned_to_id: params.assigned_to,
+        ..IssueFilter::default()
+    };
+    let loaded = Workflow::load(&mut conn, project_id).and_then(|workflow| {
+        let counts = Issue::status_counts(&mut conn, project_id)?;
+        let issues = Issue::board(&mut conn, &filter)?;
+        Ok((
+            workflow,
+            counts,
+            IssueResponse::load_many(&mut conn, issues)?,
+        ))
+    });
+
+    match loaded {
+        Ok((workflow, counts, issues)) => {
+            let mut count_by_status: HashMap<String, i64> = HashMap::new();
+            for (status, _, count) in counts {
+                *count_by_status.entry(status).or_insert(0) += count;
+            }
+            let mut by_status: HashMap<String, Vec<IssueResponse>> = HashMap::new();
+            for issue in issues {
+                by_status
+                    .entry(issue.status.clone())
+                    .or_default()
+                    .push(issue);
+            }
+            let columns = workflow
+                .states
+                .into_iter()
+                .map(|state| {
+                    let issue_count = count_by_status.get(&state.name).copied().unwrap_or(0);
+                    BoardColumn {
+                        issues: by_status.remove(&state.name).unwrap_or_default(),
+                        issue_count,
+                        wip_limit: state.wip_limit,
+                        over_limit: state
+                            .wip_limit
+                            .is_some_and(|limit| issue_count > i64::from(limit)),
+                        status: state.name,
+                        category: state.category,
+                    }
+                })
+                .collect();
+            let response = BoardResponse { columns };
+            (StatusCode::OK, Json(ApiResponse::Success(response)))
+        }
+        Err(err) => internal_server_error::<BoardResponse>("Failed to load board", &err),
+    }
+}
+
+/// Moves a card within or across columns, changing its status and rank in
+/// one update. Status changes follow the same rules as `update_issue`.
+pub async fn move_issue(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
+    Path((project_id, issue_id)): Path<(i32, i32)>,
+    Query(params): Query<UpdateIssueParams>,
+    Json(payload): Json<MoveIssueRequest>,
+) -> impl IntoResponse {
+    let mut conn = match state.pool.get() {
+        Ok(conn) => conn,
+        Err(err) => {
+            return internal_server_error::<IssueResponse>("Database connection failed", &err);
+        }
+    };
+
+    let role = match require_project_role(&mut conn, &user, project_id, ProjectRole::Reporter) {
+        Ok(role) => role,
+        Err(err) => return err.into_api_response(),
+    };
+    let issue = match find_issue(&mut conn, project_id, issue_id) {
+        Ok(issue) => issue,
+        Err(err) => return err.into_api_response(),
+    };
+    if role < ProjectRole::Developer && issue.created_by_id != user.id {
+        return AppError::Forbidden("Reporters can only edit their own issues".to_string())
+            .into_api_response();
+    }
+
+    if [payload.after_id, payload.before_id].contains(&Some(issue_id)) {
+        return bad_request("An issue cannot be placed next to itself".to_string());
+    }
+
+    let status = payload.status.unwrap_or_else(|| issue.status.clone());
+    let mut is_open = None;
+    if status != issue.status {
+        match check_status_change(&mut conn, &issue, &status, params.force) {
+            Ok(Ok(open)) => is_open = Some(open),
+            Ok(Err(response)) => return response,
+            Err(err) => {
+                return internal_server_error::<IssueResponse>(
+                    "Failed to check status change",
+                    &err,
+                )
+            }
+        }
+    }
+
+    let moved = conn.transaction(|conn| {
+        Issue::lock_board(conn, project_id)?;
+        if is_open.is_some() {
+            if let Err(response) = check_wip_limit(conn, project_id, &status, role)? {
+                return Ok(Err(response));
+            }
+        }
+
+        // Anchors are read under the lock so their ranks cannot change
+        let mut anchors = Vec::new();
+        for anchor_id in [payload.after_id, payload.before_id] {
+            let Some(anchor_id) = anchor_id else {
+                anchors.push(None);
+                continue;
+            };
+            match Issue::get_by_id(conn, anchor_id).optional()? {
+                Some(anchor) if anchor.project_id == project_id && anchor.status == status => {
+                    anchors.push(Some(anchor.rank));
+                }
+                Some(anchor) if anchor.project_id == project_id => {
+                    return Ok(Err(bad_request(format!(
+                        "Issue {} is not in the '{}' column",
+                        anchor_id, status
+                    ))));
+                }
+                _ => {
+                    return Ok(Err(bad_request(format!(
+                        "Issue {} is not in this project",
+                        anchor_id
+                    ))));
+                }
+            }
+        }
+
+        let rank = match (&anchors[0], &anchors[1]) {
+            (Some(after), Some(before)) if after >= before => {
+                return Ok(Err(bad_request(format!(
+                    "Issue {} is below issue {}",
+                    payload.after_id.unwrap_or_default(),
+                    payload.before_id.unwrap_or_default()
+                ))));
+            }
+            (Some(after), _) => {
+                let (_, next) = Issue::rank_neighbours(conn, project_id, after, issue_id)?;
+                rank_between(Some(after), next.as_deref())
+            }
+            (None, Some(before)) => {
+                let (prev, _) = Issue::rank_neighbours(conn, project_id, before, issue_id)?;
+                rank_between(prev.as_deref(), Some(before))
+            }
+            (None, None) => Some(rank_after(Issue::last_rank(conn, project_id)?.as_deref())),
+        };
+        // Ranks are unique within a project, so neighbours always leave room
+        let Some(rank) = rank else {
+            return Ok(Err((
+                StatusCode::CONFLICT,
+                Json(ApiResponse::Error {
+                    error: "The board changed; reload it and retry the move".to_string(),
+                }),
+            )));
+        };
+
+        let changes = IssueChanges {
+            status: is_open.map(|_| status.clone()),
+            is_open,
+            rank: Some(rank),
+            ..IssueChanges::default()
+        };
+        let updated = issue.update(conn, changes, &LabelChanges::default(), user.id)?;
+        let response = IssueResponse::load(conn, updated)?;
+        queue_event(conn, project_id, EVENT_ISSUE_UPDATED, user.id, &response)?;
+        Ok::<_, DieselError>(Ok(response))
+    });
+
+    match moved {
+        Ok(Err(response)) => response,
+        Ok(Ok(response)) => {
+            state
+                .events
+                .publish(project_id, EVENT_ISSUE_UPDATED, user.id, &response);
+            (StatusCode::OK, Json(ApiResponse::Success(response)))
+        }
+        Err(err) => internal_server_error::<IssueResponse>("Failed to move issue", &err),
+    }
+}
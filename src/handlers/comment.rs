@@ -0,0 +1,380 @@
+use crate::auth::{require_project_role, AuthUser};
+use crate::error::{internal_server_error, AppError};
+use crate::event_bus::{EVENT_COMMENT_CREATED, EVENT_COMMENT_DELETED, EVENT_COMMENT_UPDATED};
+use crate::handlers::api_response::ApiResponse;
+use crate::handlers::user::UserSummary;
+use crate::models::{Comment, Issue, NewComment, ProjectRole, User};
+use crate::routes::router::AppState;
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use diesel::result::Error as DieselError;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+#[derive(Debug, Deserialize)]
+pub struct CreateCommentRequest {
+    pub body: String,
+    pub parent_id: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateCommentRequest {
+    pub body: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CommentResponse {
+    pub id: i32,
+    pub issue_id: i32,
+    pub parent_id: Option<i32>,
+    pub author: UserSummary,
+    /// `None` once the comment is deleted.
+    pub body: Option<String>,
+    pub is_deleted: bool,
+    pub created_at: DateTime<Utc>,
+    pub edited_at: Option<DateTime<Utc>>,
+    pub replies: Vec<CommentResponse>,
+}
+
+/// Comments of one issue arranged as threads, with their authors loaded.
+struct CommentThreads {
+    by_parent: HashMap<Option<i32>, Vec<Comment>>,
+    users: HashMap<i32, User>,
+}
+
+impl CommentThreads {
+    fn load(conn: &mut PgConnection, issue_id: i32) -> QueryResult<Self> {
+        let comments = Comment::list_for_issue(conn, issue_id)?;
+        let mut author_ids: Vec<i32> = comments.iter().map(|c| c.author_id).collect();
+        author_ids.sort_unstable();
+        author_ids.dedup();
+        let users = User::find_many(conn, &author_ids)?;
+
+        let mut by_parent: HashMap<Option<i32>, Vec<Comment>> = HashMap::new();
+        for comment in comments {
+            by_parent
+                .entry(comment.parent_id)
+                .or_default()
+                .push(comment);
+        }
+
+        Ok(CommentThreads { by_parent, users })
+    }
+
+    fn respond(&self, comment: &Comment) -> QueryResult<CommentResponse> {
+        let author = self
+            .users
+            .get(&comment.author_id)
+            .map(UserSummary::from)
+            .ok_or(DieselError::NotFound)?;
+
+        Ok(CommentResponse {
+            id: comment.id,
+            issue_id: comment.issue_id,
+            parent_id: comment.parent_id,
+            author,
+            body: (!comment.is_deleted()).then(|| comment.body.clone()),
+            is_deleted: comment.is_deleted(),
+            created_at: comment.created_at,
+            edited_at: comment.edited_at,
+            replies: self.replies_to(Some(comment.id))?,
+        })
+    }
+
+    fn replies_to(&self, parent_id: Option<i32>) -> QueryResult<Vec<CommentResponse>> {
+        self.by_parent
+            .get(&parent_id)
+            .map(|replies| replies.iter().map(|c| self.respond(c)).collect())
+            .unwrap_or_else(|| Ok(Vec::new()))
+    }
+}
+
+pub(crate) fn find_issue(
+    conn: &mut PgConnection,
+    project_id: i32,
+    issue_id: i32,
+) -> Result<Issue, AppError> {
+    match Issue::get_by_id(conn, issue_id) {
+        Ok(issue) if issue.project_id == project_id => Ok(issue),
+        Ok(_) | Err(DieselError::NotFound) => Err(AppError::IssueNotFound(issue_id)),
+        Err(err) => Err(AppError::Database(err)),
+    }
+}
+
+fn find_comment(
+    conn: &mut PgConnection,
+    issue_id: i32,
+    comment_id: i32,
+) -> Result<Comment, AppError> {
+    match Comment::find_by_id(conn, comment_id)? {
+        Some(comment) if comment.issue_id == issue_id => Ok(comment),
+        _ => Err(AppError::CommentNotFound(comment_id)),
+    }
+}
+
+/// The comment as it appears in its thread, replies included.
+fn comment_response(
+    conn: &mut PgConnection,
+    comment: &Comment,
+) -> (StatusCode, Json<ApiResponse<CommentResponse>>) {
+    match CommentThreads::load(conn, comment.issue_id).and_then(|t| t.respond(comment)) {
+        Ok(response) => (StatusCode::OK, Json(ApiResponse::Success(response))),
+        Err(err) => internal_server_error::<CommentResponse>("Failed to load comment", &err),
+    }
+}
+
+pub async fn get_comments(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
+    Path((project_id, issue_id)): Path<(i32, i32)>,
+) -> impl IntoResponse {
+    let mut conn = match state.pool.get() {
+        Ok(conn) => conn,
+        Err(err) => {
+            return internal_server_error::<Vec<CommentResponse>>(
+                "Database connection failed",
+                &err,
+            );
+        }
+    };
+
+    if let Err(err) = require_project_role(&mut conn, &user, project_id, ProjectRole::Viewer) {
+        return err.into_api_response();
+    }
+    if let Err(err) = find_issue(&mut conn, project_id, issue_id) {
+        return err.into_api_response();
+    }
+
+    match CommentThreads::load(&mut conn, issue_id).and_then(|t| t.replies_to(None)) {
+        Ok(response) => (StatusCode::OK, Json(ApiResponse::Success(response))),
+        Err(err) => internal_server_error::<Vec<CommentResponse>>("Failed to load comments", &err),
+    }
+}
+
+pub async fn get_comment(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
+    Path((project_id, issue_id, comment_id)): Path<(i32, i32, i32)>,
+) -> impl IntoResponse {
+    let mut conn = match state.pool.get() {
+        Ok(conn) => conn,
+        Err(err) => {
+            return internal_server_error::<CommentResponse>("Database connection failed", &err);
+        }
+    };
+
+    if let Err(err) = require_project_role(&mut conn, &user, project_id, ProjectRole::Viewer) {
+        return err.into_api_response();
+    }
+    let comment = match find_issue(&mut conn, project_id, issue_id)
+        .and_then(|_| find_comment(&mut conn, issue_id, comment_id))
+    {
+        Ok(comment) => comment,
+        Err(err) => return err.into_api_response(),
+    };
+
+    comment_response(&mut conn, &comment)
+}
+
+/// Publishes a successfully rendered comment to the project's event stream.
+fn publish(
+    state: &AppState,
+    project_id: i32,
+    event: &str,
+    actor_id: i32,
+    response: &Json<ApiResponse<CommentResponse>>,
+) {
+    if let ApiResponse::Success(comment) = &response.0 {
+        state.events.publish(project_id, event, actor_id, comment);
+    }
+}
+
+pub async fn create_comment(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
+    Path((project_id, issue_id)): Path<(i32, i32)>,
+    Json(payload): Json<CreateCommentRequest>,
+) -> impl IntoResponse {
+    let mut conn = match state.pool.get() {
+        Ok(conn) => conn,
+        Err(err) => {
+            return internal_server_error::<CommentResponse>("Database connection failed", &err);
+        }
+    };
+
+    if let Err(err) = require_project_role(&mut conn, &user, project_id, ProjectRole::Reporter) {
+        return err.into_api_response();
+    }
+    if let Err(err) = find_issue(&mut conn, project_id, issue_id) {
+        return err.into_api_response();
+    }
+
+    let body = payload.body.trim();
+    if body.is_empty() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::Error {
+                error: "Comment body is required.".to_string(),
+            }),
+        );
+    }
+
+    if let Some(parent_id) = payload.parent_id {
+        match find_comment(&mut conn, issue_id, parent_id) {
+            Ok(parent) if parent.is_deleted() => {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Json(ApiResponse::Error {
+                        error: format!("Comment {} has been deleted", parent_id),
+                    }),
+                )
+            }
+            Ok(_) => {}
+            Err(AppError::CommentNotFound(_)) => {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Json(ApiResponse::Error {
+                        error: format!("Parent comment {} is not on this issue", parent_id),
+                    }),
+                )
+            }
+            Err(err) => return err.into_api_response(),
+        }
+    }
+
+    let new_comment = NewComment {
+        issue_id,
+        author_id: user.id,
+        parent_id: payload.parent_id,
+        body,
+    };
+
+    match Comment::create(&mut conn, &new_comment) {
+        Ok(comment) => {
+            let (_, response) = comment_response(&mut conn, &comment);
+            publish(
+                &state,
+                project_id,
+                EVENT_COMMENT_CREATED,
+                user.id,
+                &response,
+            );
+            (StatusCode::CREATED, response)
+        }
+        Err(err) => internal_server_error::<CommentResponse>("Failed to create comment", &err),
+    }
+}
+
+pub async fn update_comment(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
+    Path((project_id, issue_id, comment_id)): Path<(i32, i32, i32)>,
+    Json(payload): Json<UpdateCommentRequest>,
+) -> impl IntoResponse {
+    let mut conn = match state.pool.get() {
+        Ok(conn) => conn,
+        Err(err) => {
+            return internal_server_error::<CommentResponse>("Database connection failed", &err);
+        }
+    };
+
+    if let Err(err) = require_project_role(&mut conn, &user, project_id, ProjectRole::Reporter) {
+        return err.into_api_response();
+    }
+    let comment = match find_issue(&mut conn, project_id, issue_id)
+        .and_then(|_| find_comment(&mut conn, issue_id, comment_id))
+    {
+        Ok(comment) if comment.is_deleted() => {
+            return AppError::CommentNotFound(comment_id).into_api_response()
+        }
+        Ok(comment) => comment,
+        Err(err) => return err.into_api_response(),
+    };
+
+    if comment.author_id != user.id {
+        return AppError::Forbidden("Only the author can edit a comment".to_string())
+            .into_api_response();
+    }
+
+    let body = payload.body.trim();
+    if body.is_empty() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::Error {
+                error: "Comment body is required.".to_string(),
+            }),
+        );
+    }
+
+    match comment.edit(&mut conn, body) {
+        Ok(updated) => {
+            let (status, response) = comment_response(&mut conn, &updated);
+            publish(
+                &state,
+                project_id,
+                EVENT_COMMENT_UPDATED,
+                user.id,
+                &response,
+            );
+            (status, response)
+        }
+        Err(err) => internal_server_error::<CommentResponse>("Failed to update comment", &err),
+    }
+}
+
+pub async fn delete_comment(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
+    Path((project_id, issue_id, comment_id)): Path<(i32, i32, i32)>,
+) -> impl IntoResponse {
+    let mut conn = match state.pool.get() {
+        Ok(conn) => conn,
+        Err(err) => {
+            return internal_server_error::<CommentResponse>("Database connection failed", &err);
+        }
+    };
+
+    let role = match require_project_role(&mut conn, &user, project_id, ProjectRole::Reporter) {
+        Ok(role) => role,
+        Err(err) => return err.into_api_response(),
+    };
+    let comment = match find_issue(&mut conn, project_id, issue_id)
+        .and_then(|_| find_comment(&mut conn, issue_id, comment_id))
+    {
+        Ok(comment) if comment.is_deleted() => {
+            return AppError::CommentNotFound(comment_id).into_api_response()
+        }
+        Ok(comment) => comment,
+        Err(err) => return err.into_api_response(),
+    };
+
+    // Maintainers may remove other people's comments for moderation
+    if comment.author_id != user.id && role < ProjectRole::Maintainer {
+        return AppError::Forbidden("Only the author can delete a comment".to_string())
+            .into_api_response();
+    }
+
+    match comment.soft_delete(&mut conn) {
+        Ok(deleted) => {
+            tracing::info!("Comment {} deleted by user {}", comment_id, user.id);
+            let (status, response) = comment_response(&mut conn, &deleted);
+            publish(
+                &state,
+                project_id,
+                EVENT_COMMENT_DELETED,
+                user.id,
+                &response,
+            );
+            (status, response)
+        }
+        Err(err) => internal_server_error::<CommentResponse>("Failed to delete comment", &err),
+    }
+}
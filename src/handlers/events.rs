@@ -0,0 +1,119 @@
+use crate::auth::{require_project_role, AuthUser};
+use crate::error::{internal_server_error, AppError};
+use crate::event_bus::{ProjectEvent, Subscription};
+use crate::models::{Project, ProjectRole};
+use crate::routes::router::AppState;
+
+use axum::{
+    extract::{Path, State},
+    http::HeaderMap,
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Response,
+    },
+};
+use serde_json::{json, Value};
+use std::convert::Infallible;
+use std::sync::Arc;
+use tokio_stream::wrappers::{errors::BroadcastStreamRecvError, BroadcastStream};
+use tokio_stream::StreamExt;
+
+pub const LAST_EVENT_ID_HEADER: &str = "Last-Event-ID";
+/// Sent instead of events the client can no longer be given; it should
+/// reload what it shows and carry on from there.
+pub const RESET_EVENT: &str = "reset";
+
+fn sse_event(event: &ProjectEvent) -> Event {
+    Event::default()
+        .id(event.id.to_string())
+        .event(&event.event)
+        .data(&event.data)
+}
+
+/// `last_id` becomes the client's `Last-Event-ID`, so a reconnect after the
+/// reset resumes from there instead of resetting again.
+fn reset_event(last_id: Option<u64>) -> Event {
+    let event = Event::default()
+        .event(RESET_EVENT)
+        .data(json!({ "reason": "missed_events" }).to_string());
+    match last_id {
+        Some(id) => event.id(id.to_string()),
+        None => event,
+    }
+}
+
+/// Streams the project's issue and comment changes as server-sent events.
+/// Each event's `id` can be sent back in `Last-Event-ID` to resume after it;
+/// events that are no longer buffered are replaced by a single `reset`.
+pub async fn stream_events(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
+    Path(project_id): Path<i32>,
+    headers: HeaderMap,
+) -> Response {
+    // The stream outlives the request, so it must not hold a connection
+    {
+        let mut conn = match state.pool.get() {
+            Ok(conn) => conn,
+            Err(err) => {
+                return internal_server_error::<Value>("Database connection failed", &err)
+                    .into_response();
+            }
+        };
+
+        if let Err(err) = require_project_role(&mut conn, &user, project_id, ProjectRole::Viewer) {
+            return err.into_api_response::<Value>().into_response();
+        }
+        match Project::find_by_id(&mut conn, project_id) {
+            Ok(Some(_)) => {}
+            Ok(None) => {
+                return AppError::ProjectNotFound(project_id.to_string())
+                    .into_api_response::<Value>()
+                    .into_response();
+            }
+            Err(err) => {
+                return internal_server_error::<Value>("Database error", &err).into_response()
+            }
+        }
+    }
+
+    // An ID this process did not issue cannot be resumed from
+    let last_event_id = headers.get(LAST_EVENT_ID_HEADER).map(|value| {
+        value
+            .to_str()
+            .ok()
+            .and_then(|value| value.trim().parse::<u64>().ok())
+            .unwrap_or(u64::MAX)
+    });
+    let Subscription {
+        replay,
+        missed,
+        last_id,
+        receiver,
+    } = state.events.subscribe(project_id, last_event_id);
+
+    let backlog: Vec<Event> = missed
+        .then(|| reset_event(Some(last_id)))
+        .into_iter()
+        .chain(replay.iter().map(|event| sse_event(event)))
+        .collect();
+    let live = BroadcastStream::new(receiver).filter_map(move |received| match received {
+        Ok(event) if event.project_id == project_id => Some(sse_event(&event)),
+        Ok(_) => None,
+        Err(BroadcastStreamRecvError::Lagged(skipped)) => {
+            tracing::warn!(
+                "Event subscriber for project {} fell {} events behind",
+                project_id,
+                skipped
+            );
+            Some(reset_event(None))
+        }
+    });
+    let stream = tokio_stream::iter(backlog)
+        .chain(live)
+        .map(Ok::<_, Infallible>);
+
+    Sse::new(stream)
+        .keep_alive(KeepAlive::default())
+        .into_response()
+}
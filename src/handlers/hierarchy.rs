@@ -0,0 +1,136 @@
+use crate::auth::{require_project_role, AuthUser};
+use crate::error::internal_server_error;
+use crate::handlers::api_response::ApiResponse;
+use crate::handlers::comment::find_issue;
+use crate::models::{Issue, Project, ProjectRole, Worklog};
+use crate::routes::router::AppState;
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use diesel::prelude::*;
+use diesel::result::Error as DieselError;
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// Totals over every issue below a node, not just its direct children.
+#[derive(Debug, Default, Serialize)]
+pub struct Rollup {
+    pub total: i64,
+    /// Sub-issues in a done state.
+    pub done: i64,
+    pub estimate_minutes: i64,
+    /// Estimates of the sub-issues that are still open, less the time
+    /// already logged on each.
+    pub remaining_estimate_minutes: i64,
+    pub logged_minutes: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SubtreeNode {
+    pub id: i32,
+    pub key: String,
+    pub title: String,
+    pub status: String,
+    pub is_open: bool,
+    pub original_estimate_minutes: Option<i32>,
+    pub logged_minutes: i64,
+    pub rollup: Rollup,
+    pub children: Vec<SubtreeNode>,
+}
+
+impl SubtreeNode {
+    fn build(
+        issue: Issue,
+        project_key: &str,
+        by_parent: &mut HashMap<i32, Vec<Issue>>,
+        logged: &HashMap<i32, i64>,
+    ) -> Self {
+        let children: Vec<SubtreeNode> = by_parent
+            .remove(&issue.id)
+            .unwrap_or_default()
+            .into_iter()
+            .map(|child| SubtreeNode::build(child, project_key, by_parent, logged))
+            .collect();
+
+        let mut rollup = Rollup::default();
+        for child in &children {
+            let estimate = i64::from(child.original_estimate_minutes.unwrap_or(0));
+            rollup.total += 1 + child.rollup.total;
+            rollup.done += i64::from(!child.is_open) + child.rollup.done;
+            rollup.estimate_minutes += estimate + child.rollup.estimate_minutes;
+            let remaining = if child.is_open {
+                (estimate - child.logged_minutes).max(0)
+            } else {
+                0
+            };
+            rollup.remaining_estimate_minutes +=
+                remaining + child.rollup.remaining_estimate_minutes;
+            rollup.logged_minutes += child.logged_minutes + child.rollup.logged_minutes;
+        }
+
+        SubtreeNode {
+            id: issue.id,
+            key: format!("{}-{}", project_key, issue.number),
+            title: issue.title,
+            status: issue.status,
+            is_open: issue.is_open,
+            original_estimate_minutes: issue.original_estimate_minutes,
+            logged_minutes: logged.get(&issue.id).copied().unwrap_or(0),
+            rollup,
+            children,
+        }
+    }
+}
+
+fn load_subtree(conn: &mut PgConnection, issue: Issue) -> QueryResult<SubtreeNode> {
+    let project = Project::find_by_id(conn, issue.project_id)?.ok_or(DieselError::NotFound)?;
+    let descendants = Issue::descendants(conn, issue.id)?;
+    let issue_ids: Vec<i32> = std::iter::once(issue.id)
+        .chain(descendants.iter().map(|d| d.id))
+        .collect();
+    let logged = Worklog::minutes_by_issue(conn, &issue_ids)?;
+    let mut by_parent: HashMap<i32, Vec<Issue>> = HashMap::new();
+    for descendant in descendants {
+        if let Some(parent_id) = descendant.parent_id {
+            by_parent.entry(parent_id).or_default().push(descendant);
+        }
+    }
+
+    Ok(SubtreeNode::build(
+        issue,
+        &project.key,
+        &mut by_parent,
+        &logged,
+    ))
+}
+
+pub async fn get_subtree(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
+    Path((project_id, issue_id)): Path<(i32, i32)>,
+) -> impl IntoResponse {
+    let mut conn = match state.pool.get() {
+        Ok(conn) => conn,
+        Err(err) => {
+            return internal_server_error::<SubtreeNode>("Database connection failed", &err);
+        }
+    };
+
+    if let Err(err) = require_project_role(&mut conn, &user, project_id, ProjectRole::Viewer) {
+        return err.into_api_response();
+    }
+    let issue = match find_issue(&mut conn, project_id, issue_id) {
+        Ok(issue) => issue,
+        Err(err) => return err.into_api_response(),
+    };
+
+    match load_subtree(&mut conn, issue) {
+        Ok(tree) => (StatusCode::OK, Json(ApiResponse::Success(tree))),
+        Err(err) => internal_server_error::<SubtreeNode>("Failed to load sub-issues", &err),
+    }
+}
@@ -0,0 +1,123 @@
+use crate::auth::{require_project_role, AuthUser};
+use crate::error::{internal_server_error, AppError};
+use crate::handlers::api_response::ApiResponse;
+use crate::handlers::user::UserSummary;
+use crate::models::{HistoryEntry, Issue, ProjectRole, User, ENTITY_ISSUE, ENTITY_PROJECT};
+use crate::routes::router::AppState;
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use diesel::result::Error as DieselError;
+use serde::Serialize;
+use std::sync::Arc;
+
+#[derive(Debug, Serialize)]
+pub struct HistoryEntryResponse {
+    pub id: i64,
+    pub field: String,
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+    pub actor: UserSummary,
+    pub created_at: DateTime<Utc>,
+}
+
+fn load_history(
+    conn: &mut PgConnection,
+    entity_type: &str,
+    entity_id: i32,
+) -> QueryResult<Vec<HistoryEntryResponse>> {
+    let entries = HistoryEntry::for_entity(conn, entity_type, entity_id)?;
+    let mut actor_ids: Vec<i32> = entries.iter().map(|e| e.actor_id).collect();
+    actor_ids.sort_unstable();
+    actor_ids.dedup();
+    let users = User::find_many(conn, &actor_ids)?;
+
+    entries
+        .into_iter()
+        .map(|entry| {
+            let actor = users
+                .get(&entry.actor_id)
+                .map(UserSummary::from)
+                .ok_or(DieselError::NotFound)?;
+
+            Ok(HistoryEntryResponse {
+                id: entry.id,
+                field: entry.field,
+                old_value: entry.old_value,
+                new_value: entry.new_value,
+                actor,
+                created_at: entry.created_at,
+            })
+        })
+        .collect()
+}
+
+pub async fn get_issue_history(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
+    Path((project_id, issue_id)): Path<(i32, i32)>,
+) -> impl IntoResponse {
+    let mut conn = match state.pool.get() {
+        Ok(conn) => conn,
+        Err(err) => {
+            return internal_server_error::<Vec<HistoryEntryResponse>>(
+                "Database connection failed",
+                &err,
+            );
+        }
+    };
+
+    if let Err(err) = require_project_role(&mut conn, &user, project_id, ProjectRole::Viewer) {
+        return err.into_api_response();
+    }
+
+    match Issue::get_by_id(&mut conn, issue_id) {
+        Ok(issue) if issue.project_id == project_id => {}
+        Ok(_) | Err(DieselError::NotFound) => {
+            return AppError::IssueNotFound(issue_id).into_api_response()
+        }
+        Err(err) => {
+            return internal_server_error::<Vec<HistoryEntryResponse>>("Database error", &err)
+        }
+    }
+
+    match load_history(&mut conn, ENTITY_ISSUE, issue_id) {
+        Ok(response) => (StatusCode::OK, Json(ApiResponse::Success(response))),
+        Err(err) => {
+            internal_server_error::<Vec<HistoryEntryResponse>>("Failed to load history", &err)
+        }
+    }
+}
+
+pub async fn get_project_history(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
+    Path(project_id): Path<i32>,
+) -> impl IntoResponse {
+    let mut conn = match state.pool.get() {
+        Ok(conn) => conn,
+        Err(err) => {
+            return internal_server_error::<Vec<HistoryEntryResponse>>(
+                "Database connection failed",
+                &err,
+            );
+        }
+    };
+
+    if let Err(err) = require_project_role(&mut conn, &user, project_id, ProjectRole::Viewer) {
+        return err.into_api_response();
+    }
+
+    match load_history(&mut conn, ENTITY_PROJECT, project_id) {
+        Ok(response) => (StatusCode::OK, Json(ApiResponse::Success(response))),
+        Err(err) => {
+            internal_server_error::<Vec<HistoryEntryResponse>>("Failed to load history", &err)
+        }
+    }
+}
@@ -1,79 +1,412 @@
+use crate::auth::{require_project_role, AuthUser};
+use crate::error::AppError;
 use crate::handlers::api_response::ApiResponse;
-use crate::models::{Issue, IssueChanges, Project};
+use crate::handlers::label::LabelSummary;
+use crate::handlers::pagination::{Cursor, Page, PageRequest, SortOrder};
+use crate::handlers::user::UserSummary;
+use crate::handlers::webhook::queue_event;
+use crate::models::{
+    Issue, IssueChanges, IssueFilter, IssueKeyset, IssueListQuery, IssueSortField, Label,
+    LabelChanges, Milestone, NewIssue, Priority, Project, ProjectRole, Severity, Sprint,
+    SprintState, User, Workflow, EVENT_ISSUE_CREATED, EVENT_ISSUE_DELETED, EVENT_ISSUE_UPDATED,
+};
 use crate::routes::router::AppState;
 
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
-    response::IntoResponse,
+    response::{IntoResponse, Redirect, Response},
     Json,
 };
-use serde::{Deserialize, Serialize};
+use chrono::{DateTime, NaiveDate, Utc};
+use diesel::prelude::*;
+use diesel::result::Error as DieselError;
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::json;
+use std::collections::HashMap;
 use std::sync::Arc;
 
 #[derive(Debug, Deserialize)]
 pub struct CreateIssueRequest {
     pub title: String,
     pub description: String,
-    pub created_by: String,
-    pub assigned_to: Option<String>,
+    /// Defaults to the authenticated user.
+    pub created_by: Option<i32>,
+    pub assigned_to: Option<i32>,
     pub status: Option<String>,
+    /// `P0`-`P4`, defaults to `P2`.
+    pub priority: Option<String>,
+    /// Defaults to `major`.
+    pub severity: Option<String>,
+    pub start_date: Option<NaiveDate>,
+    pub due_date: Option<NaiveDate>,
+    /// Issue in the same project to nest this one under.
+    pub parent_id: Option<i32>,
+    pub original_estimate_minutes: Option<i32>,
+    /// Story points.
+    pub estimate_points: Option<i32>,
+    /// Unreleased milestone of the same project.
+    pub milestone_id: Option<i32>,
+    /// Planned or active sprint of the same project; none puts the issue in
+    /// the backlog.
+    pub sprint_id: Option<i32>,
 }
 
 #[derive(Debug, Serialize)]
 pub struct IssueResponse {
     pub id: i32,
     pub project_id: i32,
+    pub number: i32,
+    /// Project key and number, e.g. `PROJ-123`.
+    pub key: String,
     pub title: String,
     pub description: String,
-    pub created_by: String,
-    pub assigned_to: Option<String>,
+    pub created_by: UserSummary,
+    pub assigned_to: Option<UserSummary>,
+    pub labels: Vec<LabelSummary>,
     pub status: String,
     pub is_open: bool,
+    pub priority: Priority,
+    pub severity: Severity,
+    pub start_date: Option<NaiveDate>,
+    pub due_date: Option<NaiveDate>,
+    pub parent_id: Option<i32>,
+    pub original_estimate_minutes: Option<i32>,
+    pub estimate_points: Option<i32>,
+    pub milestone_id: Option<i32>,
+    pub sprint_id: Option<i32>,
+    /// Sorts the issue on the project's board.
+    pub rank: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl IssueResponse {
+    /// Builds responses for `issues`, loading the users, labels and project
+    /// keys they reference in one query each.
+    pub fn load_many(conn: &mut PgConnection, issues: Vec<Issue>) -> QueryResult<Vec<Self>> {
+        let mut user_ids: Vec<i32> = issues
+            .iter()
+            .flat_map(|issue| std::iter::once(issue.created_by_id).chain(issue.assigned_to_id))
+            .collect();
+        user_ids.sort_unstable();
+        user_ids.dedup();
+        let users = User::find_many(conn, &user_ids)?;
+        let issue_ids: Vec<i32> = issues.iter().map(|issue| issue.id).collect();
+        let mut labels = Label::for_issues(conn, &issue_ids)?;
+        let mut project_ids: Vec<i32> = issues.iter().map(|issue| issue.project_id).collect();
+        project_ids.sort_unstable();
+        project_ids.dedup();
+        let keys = Project::keys_for(conn, &project_ids)?;
+
+        issues
+            .into_iter()
+            .map(|issue| {
+                let created_by = users
+                    .get(&issue.created_by_id)
+                    .map(UserSummary::from)
+                    .ok_or(DieselError::NotFound)?;
+                let assigned_to = issue
+                    .assigned_to_id
+                    .and_then(|id| users.get(&id))
+                    .map(UserSummary::from);
+                let labels = labels
+                    .remove(&issue.id)
+                    .unwrap_or_default()
+                    .iter()
+                    .map(LabelSummary::from)
+                    .collect();
+                let key = keys
+                    .get(&issue.project_id)
+                    .map(|key| format!("{}-{}", key, issue.number))
+                    .ok_or(DieselError::NotFound)?;
+
+                Ok(IssueResponse {
+                    id: issue.id,
+                    project_id: issue.project_id,
+                    number: issue.number,
+                    key,
+                    title: issue.title,
+                    description: issue.description,
+                    created_by,
+                    assigned_to,
+                    labels,
+                    status: issue.status,
+                    is_open: issue.is_open,
+                    priority: Priority::from_level(issue.priority).unwrap_or_default(),
+                    severity: Severity::parse(&issue.severity).unwrap_or_default(),
+                    start_date: issue.start_date,
+                    due_date: issue.due_date,
+                    parent_id: issue.parent_id,
+                    original_estimate_minutes: issue.original_estimate_minutes,
+                    estimate_points: issue.estimate_points,
+                    milestone_id: issue.milestone_id,
+                    sprint_id: issue.sprint_id,
+                    rank: issue.rank,
+                    created_at: issue.created_at,
+                    updated_at: issue.updated_at,
+                })
+            })
+            .collect()
+    }
+
+    pub fn load(conn: &mut PgConnection, issue: Issue) -> QueryResult<Self> {
+        Self::load_many(conn, vec![issue]).map(|mut responses| responses.remove(0))
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct IssueListParams {
+    pub limit: Option<i64>,
+    pub after: Option<String>,
+    pub sort: Option<IssueSortField>,
+    pub order: Option<SortOrder>,
+    /// Comma-separated list, e.g. `open,in_progress`.
+    pub status: Option<String>,
+    pub is_open: Option<bool>,
+    pub assigned_to: Option<i32>,
+    pub created_by: Option<i32>,
+    /// Direct children of this issue.
+    pub parent_id: Option<i32>,
+    pub milestone_id: Option<i32>,
+    pub sprint_id: Option<i32>,
+    /// Only issues in no sprint.
+    #[serde(default)]
+    pub backlog: bool,
+    pub created_after: Option<DateTime<Utc>>,
+    pub created_before: Option<DateTime<Utc>>,
+    pub updated_after: Option<DateTime<Utc>>,
+    pub updated_before: Option<DateTime<Utc>>,
+    pub due_after: Option<NaiveDate>,
+    pub due_before: Option<NaiveDate>,
+    pub q: Option<String>,
+    /// Comma-separated label names.
+    pub label: Option<String>,
+    pub label_match: Option<LabelMatch>,
+}
+
+/// Whether `?label=` requires every listed label or any one of them.
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LabelMatch {
+    #[default]
+    All,
+    Any,
+}
+
+fn split_list(value: Option<&str>) -> Vec<String> {
+    value
+        .unwrap_or_default()
+        .split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+impl IssueListParams {
+    pub(crate) fn filter(&self, project_id: i32) -> IssueFilter {
+        IssueFilter {
+            project_id: Some(project_id),
+            member_id: None,
+            statuses: split_list(self.status.as_deref()),
+            is_open: self.is_open,
+            assigned_to_id: self.assigned_to,
+            created_by_id: self.created_by,
+            parent_id: self.parent_id,
+            milestone_id: self.milestone_id,
+            sprint_id: self.sprint_id,
+            in_backlog: self.backlog,
+            created_after: self.created_after,
+            created_before: self.created_before,
+            updated_after: self.updated_after,
+            updated_before: self.updated_before,
+            due_after: self.due_after,
+            due_before: self.due_before,
+            title_contains: self.q.clone().filter(|q| !q.trim().is_empty()),
+            labels: split_list(self.label.as_deref()),
+            match_all_labels: matches!(self.label_match.unwrap_or_default(), LabelMatch::All),
+        }
+    }
+}
+
+pub(crate) fn issue_cursor(sort: IssueSortField, issue: &IssueResponse) -> Cursor {
+    match sort {
+        IssueSortField::CreatedAt => Cursor::new(issue.created_at.to_rfc3339(), issue.id),
+        IssueSortField::UpdatedAt => Cursor::new(issue.updated_at.to_rfc3339(), issue.id),
+        IssueSortField::Title => Cursor::new(issue.title.clone(), issue.id),
+        IssueSortField::Priority => Cursor::new(
+            format!(
+                "{}|{}",
+                issue.priority.level(),
+                issue.created_at.to_rfc3339()
+            ),
+            issue.id,
+        ),
+    }
 }
 
-const VALID_STATUSES: &[&str] = &["open", "in_progress", "resolved", "closed"];
+pub(crate) fn issue_keyset(sort: IssueSortField, cursor: &Cursor) -> Option<IssueKeyset> {
+    let timestamp = || {
+        DateTime::parse_from_rfc3339(&cursor.value)
+            .ok()
+            .map(|ts| ts.with_timezone(&Utc))
+    };
+
+    match sort {
+        IssueSortField::CreatedAt => timestamp().map(|ts| IssueKeyset::CreatedAt(ts, cursor.id)),
+        IssueSortField::UpdatedAt => timestamp().map(|ts| IssueKeyset::UpdatedAt(ts, cursor.id)),
+        IssueSortField::Title => Some(IssueKeyset::Title(cursor.value.clone(), cursor.id)),
+        IssueSortField::Priority => {
+            let (level, created) = cursor.value.split_once('|')?;
+            let created = DateTime::parse_from_rfc3339(created).ok()?;
+            Some(IssueKeyset::Priority(
+                level.parse().ok()?,
+                created.with_timezone(&Utc),
+                cursor.id,
+            ))
+        }
+    }
+}
 
 pub async fn get_project_issues(
     State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
     Path(project_id): Path<i32>,
+    Query(params): Query<IssueListParams>,
 ) -> impl IntoResponse {
     let mut conn = match state.pool.get() {
         Ok(conn) => conn,
         Err(err) => {
             return (
                 StatusCode::INTERNAL_SERVER_ERROR,
-                Json(ApiResponse::<Vec<IssueResponse>>::Error {
+                Json(ApiResponse::<Page<IssueResponse>>::Error {
                     error: format!("DB pool error: {err}"),
                 }),
             )
         }
     };
 
+    if let Err(err) = require_project_role(&mut conn, &user, project_id, ProjectRole::Viewer) {
+        return err.into_api_response();
+    }
+
+    let page = match PageRequest::from_query(params.limit, params.after.as_deref()) {
+        Ok(page) => page,
+        Err(message) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::<Page<IssueResponse>>::Error { error: message }),
+            )
+        }
+    };
+
+    let filter = params.filter(project_id);
+
+    let sort = params.sort.unwrap_or_default();
+    let after = match page.after.as_ref().map(|cursor| issue_keyset(sort, cursor)) {
+        Some(None) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::<Page<IssueResponse>>::Error {
+                    error: "Invalid cursor".to_string(),
+                }),
+            )
+        }
+        Some(keyset) => keyset,
+        None => None,
+    };
+
     // First check if project exists
     match Project::find_by_id(&mut conn, project_id) {
         Ok(Some(_)) => {
-            // Project exists, get its issues
-            match Issue::get_by_project(&mut conn, project_id) {
-                Ok(issues) => {
-                    let response: Vec<IssueResponse> = issues
-                        .into_iter()
-                        .map(|issue| IssueResponse {
-                            id: issue.id,
-                            project_id: issue.project_id,
-                            title: issue.title,
-                            description: issue.description,
-                            created_by: issue.created_by,
-                            assigned_to: issue.assigned_to,
-                            status: issue.status,
-                            is_open: issue.is_open,
-                        })
-                        .collect();
-                    (StatusCode::OK, Json(ApiResponse::Success(response)))
+            let workflow = match Workflow::load(&mut conn, project_id) {
+                Ok(workflow) => workflow,
+                Err(err) => {
+                    return (
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        Json(ApiResponse::<Page<IssueResponse>>::Error {
+                            error: format!("Failed to load workflow: {err}"),
+                        }),
+                    )
+                }
+            };
+
+            if let Some(invalid) = filter.statuses.iter().find(|s| workflow.state(s).is_none()) {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Json(ApiResponse::<Page<IssueResponse>>::Error {
+                        error: format!(
+                            "Invalid status value '{}'. Allowed values: {:?}",
+                            invalid,
+                            workflow.state_names()
+                        ),
+                    }),
+                );
+            }
+
+            if !filter.labels.is_empty() {
+                let catalog = match Label::list(&mut conn, project_id) {
+                    Ok(catalog) => catalog,
+                    Err(err) => {
+                        return (
+                            StatusCode::INTERNAL_SERVER_ERROR,
+                            Json(ApiResponse::<Page<IssueResponse>>::Error {
+                                error: format!("Failed to load labels: {err}"),
+                            }),
+                        )
+                    }
+                };
+                if let Err(error) = resolve_labels(&catalog, &filter.labels) {
+                    return (
+                        StatusCode::BAD_REQUEST,
+                        Json(ApiResponse::<Page<IssueResponse>>::Error { error }),
+                    );
+                }
+            }
+
+            let total = match Issue::count(&mut conn, &filter) {
+                Ok(total) => total,
+                Err(err) => {
+                    return (
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        Json(ApiResponse::<Page<IssueResponse>>::Error {
+                            error: format!("Failed to count issues: {err}"),
+                        }),
+                    )
                 }
+            };
+
+            let query = IssueListQuery {
+                filter,
+                sort,
+                descending: params.order.unwrap_or_default().is_descending(),
+                after,
+                limit: page.fetch_limit(),
+            };
+
+            // Project exists, get its issues
+            match Issue::list(&mut conn, &query) {
+                Ok(issues) => match IssueResponse::load_many(&mut conn, issues) {
+                    Ok(issues) => {
+                        let response = Page::from_rows(
+                            issues,
+                            &page,
+                            total,
+                            |issue| issue_cursor(sort, issue),
+                            |issue| issue,
+                        );
+                        (StatusCode::OK, Json(ApiResponse::Success(response)))
+                    }
+                    Err(err) => (
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        Json(ApiResponse::<Page<IssueResponse>>::Error {
+                            error: format!("Failed to load issue users: {err}"),
+                        }),
+                    ),
+                },
                 Err(err) => (
                     StatusCode::INTERNAL_SERVER_ERROR,
-                    Json(ApiResponse::<Vec<IssueResponse>>::Error {
+                    Json(ApiResponse::<Page<IssueResponse>>::Error {
                         error: format!("Failed to fetch issues: {err}"),
                     }),
                 ),
@@ -81,21 +414,375 @@ pub async fn get_project_issues(
         }
         Ok(None) => (
             StatusCode::NOT_FOUND,
-            Json(ApiResponse::<Vec<IssueResponse>>::Error {
+            Json(ApiResponse::<Page<IssueResponse>>::Error {
                 error: format!("Project with ID {} not found", project_id),
             }),
         ),
         Err(err) => (
             StatusCode::INTERNAL_SERVER_ERROR,
-            Json(ApiResponse::<Vec<IssueResponse>>::Error {
+            Json(ApiResponse::<Page<IssueResponse>>::Error {
                 error: format!("Database error: {err}"),
             }),
         ),
     }
 }
 
+pub async fn get_issue(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
+    Path((project_id, issue_id)): Path<(i32, i32)>,
+) -> impl IntoResponse {
+    let mut conn = match state.pool.get() {
+        Ok(conn) => conn,
+        Err(err) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<IssueResponse>::Error {
+                    error: format!("DB pool error: {err}"),
+                }),
+            )
+        }
+    };
+
+    if let Err(err) = require_project_role(&mut conn, &user, project_id, ProjectRole::Viewer) {
+        return err.into_api_response();
+    }
+
+    match Project::find_by_id(&mut conn, project_id) {
+        Ok(Some(_)) => match Issue::get_by_id(&mut conn, issue_id) {
+            Ok(issue) if issue.project_id == project_id => {
+                match IssueResponse::load(&mut conn, issue) {
+                    Ok(response) => (StatusCode::OK, Json(ApiResponse::Success(response))),
+                    Err(err) => (
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        Json(ApiResponse::<IssueResponse>::Error {
+                            error: format!("Failed to load issue users: {err}"),
+                        }),
+                    ),
+                }
+            }
+            Ok(_) => (
+                StatusCode::NOT_FOUND,
+                Json(ApiResponse::<IssueResponse>::Error {
+                    error: "Issue does not belong to this project".to_string(),
+                }),
+            ),
+            Err(DieselError::NotFound) => (
+                StatusCode::NOT_FOUND,
+                Json(ApiResponse::<IssueResponse>::Error {
+                    error: format!("Issue {} not found", issue_id),
+                }),
+            ),
+            Err(err) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<IssueResponse>::Error {
+                    error: format!("Database error: {err}"),
+                }),
+            ),
+        },
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse::<IssueResponse>::Error {
+                error: format!("Project with ID {} not found", project_id),
+            }),
+        ),
+        Err(err) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::<IssueResponse>::Error {
+                error: format!("Database error: {err}"),
+            }),
+        ),
+    }
+}
+
+/// Splits `PROJ-123` into an uppercased project key and issue number.
+fn parse_issue_key(issue_key: &str) -> Option<(String, i32)> {
+    let (key, number) = issue_key.rsplit_once('-')?;
+    let number = number.parse().ok().filter(|number| *number > 0)?;
+    Some((key.to_ascii_uppercase(), number))
+}
+
+/// Resolves `PROJ-123` to an issue. Keys the project has since been renamed
+/// from redirect permanently to the current key.
+pub async fn get_issue_by_key(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
+    Path(issue_key): Path<String>,
+) -> Response {
+    let mut conn = match state.pool.get() {
+        Ok(conn) => conn,
+        Err(err) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<IssueResponse>::Error {
+                    error: format!("DB pool error: {err}"),
+                }),
+            )
+                .into_response()
+        }
+    };
+
+    let not_found = || {
+        (
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse::<IssueResponse>::Error {
+                error: format!("Issue {} not found", issue_key),
+            }),
+        )
+            .into_response()
+    };
+
+    let Some((key, number)) = parse_issue_key(&issue_key) else {
+        return not_found();
+    };
+
+    let project = match Project::find_by_key(&mut conn, &key) {
+        Ok(Some(project)) => project,
+        Ok(None) => return not_found(),
+        Err(err) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<IssueResponse>::Error {
+                    error: format!("Database error: {err}"),
+                }),
+            )
+                .into_response()
+        }
+    };
+
+    if let Err(err) = require_project_role(&mut conn, &user, project.id, ProjectRole::Viewer) {
+        return err.into_api_response::<IssueResponse>().into_response();
+    }
+
+    if project.key != key {
+        return Redirect::permanent(&format!("/api/issues/{}-{}", project.key, number))
+            .into_response();
+    }
+
+    match Issue::find_by_number(&mut conn, project.id, number) {
+        Ok(Some(issue)) => match IssueResponse::load(&mut conn, issue) {
+            Ok(response) => (StatusCode::OK, Json(ApiResponse::Success(response))).into_response(),
+            Err(err) => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<IssueResponse>::Error {
+                    error: format!("Failed to load issue users: {err}"),
+                }),
+            )
+                .into_response(),
+        },
+        Ok(None) => not_found(),
+        Err(err) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::<IssueResponse>::Error {
+                error: format!("Database error: {err}"),
+            }),
+        )
+            .into_response(),
+    }
+}
+
+/// Ids of the named labels in the project's catalog, or an error naming the
+/// first unknown one.
+pub(crate) fn resolve_labels(catalog: &[Label], names: &[String]) -> Result<Vec<i32>, String> {
+    names
+        .iter()
+        .map(|name| {
+            catalog
+                .iter()
+                .find(|label| label.name == *name)
+                .map(|label| label.id)
+                .ok_or_else(|| format!("Unknown label '{}'", name))
+        })
+        .collect()
+}
+
+pub(crate) fn parse_priority(value: &str) -> Result<Priority, String> {
+    Priority::parse(value).ok_or_else(|| {
+        format!(
+            "Invalid priority '{}'. Allowed values: {:?}",
+            value,
+            Priority::ALL.map(Priority::as_str)
+        )
+    })
+}
+
+pub(crate) fn parse_severity(value: &str) -> Result<Severity, String> {
+    Severity::parse(value).ok_or_else(|| {
+        format!(
+            "Invalid severity '{}'. Allowed values: {:?}",
+            value,
+            Severity::ALL.map(Severity::as_str)
+        )
+    })
+}
+
+pub(crate) fn check_dates(
+    start_date: Option<NaiveDate>,
+    due_date: Option<NaiveDate>,
+) -> Result<(), String> {
+    match (start_date, due_date) {
+        (Some(start), Some(due)) if start > due => {
+            Err(format!("start_date {} is after due_date {}", start, due))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// An error response ready to return from a handler.
+pub(crate) type Rejection<T> = (StatusCode, Json<ApiResponse<T>>);
+
+/// Checks that `issue_id` (`None` for a new issue) may be nested under
+/// `parent_id`: the parent must be in the same project, must not be the issue
+/// or one of its descendants, and the issue's subtree must stay within the
+/// project's depth limit.
+fn check_parent<T>(
+    conn: &mut PgConnection,
+    project: &Project,
+    issue_id: Option<i32>,
+    parent_id: i32,
+) -> QueryResult<Result<(), Rejection<T>>> {
+    match Issue::get_by_id(conn, parent_id) {
+        Ok(parent) if parent.project_id == project.id => {}
+        Ok(_) | Err(DieselError::NotFound) => {
+            return Ok(Err((
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::Error {
+                    error: format!("Parent issue {} is not in this project", parent_id),
+                }),
+            )))
+        }
+        Err(err) => return Err(err),
+    }
+
+    // Levels the issue's own subtree adds below it
+    let mut height = 0;
+    if let Some(issue_id) = issue_id {
+        let descendants = Issue::descendants(conn, issue_id)?;
+        if issue_id == parent_id || descendants.iter().any(|d| d.id == parent_id) {
+            return Ok(Err((
+                StatusCode::UNPROCESSABLE_ENTITY,
+                Json(ApiResponse::DetailedError {
+                    error: format!(
+                        "Issue {} cannot be nested under itself or one of its sub-issues",
+                        issue_id
+                    ),
+                    code: "hierarchy_cycle".to_string(),
+                    details: json!({ "issue_id": issue_id, "parent_id": parent_id }),
+                }),
+            )));
+        }
+        let mut levels = HashMap::from([(issue_id, 0)]);
+        for descendant in &descendants {
+            let level = descendant
+                .parent_id
+                .and_then(|id| levels.get(&id))
+                .map_or(1, |level| level + 1);
+            levels.insert(descendant.id, level);
+            height = height.max(level);
+        }
+    }
+
+    let depth = Issue::ancestor_ids(conn, parent_id)?.len() as i64 + 1 + height;
+    if depth > i64::from(project.max_issue_depth) {
+        return Ok(Err((
+            StatusCode::UNPROCESSABLE_ENTITY,
+            Json(ApiResponse::DetailedError {
+                error: format!(
+                    "Issues in this project can be nested at most {} levels deep",
+                    project.max_issue_depth
+                ),
+                code: "depth_limit".to_string(),
+                details: json!({ "max_depth": project.max_issue_depth, "depth": depth }),
+            }),
+        )));
+    }
+
+    Ok(Ok(()))
+}
+
+pub(crate) fn check_estimate(minutes: Option<i32>, points: Option<i32>) -> Result<(), String> {
+    match (minutes, points) {
+        (Some(minutes), _) if minutes < 0 => {
+            Err("original_estimate_minutes cannot be negative".to_string())
+        }
+        (_, Some(points)) if points < 0 => Err("estimate_points cannot be negative".to_string()),
+        _ => Ok(()),
+    }
+}
+
+/// Issues may only be scheduled into unreleased milestones of their own
+/// project.
+fn ensure_open_milestone(
+    conn: &mut PgConnection,
+    project_id: i32,
+    milestone_id: i32,
+) -> Result<(), (StatusCode, String)> {
+    match Milestone::find_by_id(conn, milestone_id) {
+        Ok(Some(milestone)) if milestone.project_id == project_id && !milestone.released => Ok(()),
+        Ok(Some(milestone)) if milestone.project_id == project_id => Err((
+            StatusCode::BAD_REQUEST,
+            format!("Milestone {} is already released", milestone_id),
+        )),
+        Ok(_) => Err((
+            StatusCode::BAD_REQUEST,
+            format!("Milestone {} is not in this project", milestone_id),
+        )),
+        Err(err) => Err((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Database error: {err}"),
+        )),
+    }
+}
+
+/// Issues may only join planned or active sprints of their own project.
+fn ensure_open_sprint(
+    conn: &mut PgConnection,
+    project_id: i32,
+    sprint_id: i32,
+) -> Result<(), (StatusCode, String)> {
+    match Sprint::find_by_id(conn, sprint_id) {
+        Ok(Some(sprint)) if sprint.project_id == project_id => {
+            if sprint.state() == SprintState::Closed {
+                Err((
+                    StatusCode::BAD_REQUEST,
+                    format!("Sprint {} is already closed", sprint_id),
+                ))
+            } else {
+                Ok(())
+            }
+        }
+        Ok(_) => Err((
+            StatusCode::BAD_REQUEST,
+            format!("Sprint {} is not in this project", sprint_id),
+        )),
+        Err(err) => Err((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Database error: {err}"),
+        )),
+    }
+}
+
+/// Issues may only reference users that exist and are active.
+pub(crate) fn ensure_active_user(
+    conn: &mut PgConnection,
+    user_id: i32,
+) -> Result<(), (StatusCode, String)> {
+    match User::find_active(conn, user_id) {
+        Ok(Some(_)) => Ok(()),
+        Ok(None) => Err((
+            StatusCode::BAD_REQUEST,
+            format!("User {} does not exist or is inactive", user_id),
+        )),
+        Err(err) => Err((
+            StatusCode::INTERNAL_SERVER_ERROR,
+            format!("Database error: {err}"),
+        )),
+    }
+}
+
 pub async fn create_issue(
     State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
     Path(project_id): Path<i32>,
     Json(payload): Json<CreateIssueRequest>,
 ) -> impl IntoResponse {
@@ -111,44 +798,121 @@ pub async fn create_issue(
         }
     };
 
-    let status = payload
-        .status
-        .as_deref()
-        .unwrap_or("open")
-        .to_lowercase();
+    let role = match require_project_role(&mut conn, &user, project_id, ProjectRole::Reporter) {
+        Ok(role) => role,
+        Err(err) => return err.into_api_response(),
+    };
 
-    if !VALID_STATUSES.contains(&status.as_str()) {
-        return (
-            StatusCode::BAD_REQUEST,
-            Json(ApiResponse::<IssueResponse>::Error {
-                error: format!("Invalid status value. Allowed values: {:?}", VALID_STATUSES),
-            }),
-        );
+    let created_by_id = payload.created_by.unwrap_or(user.id);
+    if created_by_id != user.id && role < ProjectRole::Developer {
+        return AppError::Forbidden("Reporters can only file issues as themselves".to_string())
+            .into_api_response();
+    }
+    let priority = payload.priority.as_deref().map(parse_priority).transpose();
+    let severity = payload.severity.as_deref().map(parse_severity).transpose();
+    let (priority, severity) = match (priority, severity) {
+        (Ok(priority), Ok(severity)) => {
+            (priority.unwrap_or_default(), severity.unwrap_or_default())
+        }
+        (Err(error), _) | (_, Err(error)) => {
+            return (StatusCode::BAD_REQUEST, Json(ApiResponse::Error { error }))
+        }
+    };
+
+    if let Err(error) = check_dates(payload.start_date, payload.due_date)
+        .and_then(|_| check_estimate(payload.original_estimate_minutes, payload.estimate_points))
+    {
+        return (StatusCode::BAD_REQUEST, Json(ApiResponse::Error { error }));
+    }
+
+    for user_id in std::iter::once(created_by_id).chain(payload.assigned_to) {
+        if let Err((status, error)) = ensure_active_user(&mut conn, user_id) {
+            return (status, Json(ApiResponse::Error { error }));
+        }
+    }
+    if let Some(milestone_id) = payload.milestone_id {
+        if let Err((status, error)) = ensure_open_milestone(&mut conn, project_id, milestone_id) {
+            return (status, Json(ApiResponse::Error { error }));
+        }
+    }
+    if let Some(sprint_id) = payload.sprint_id {
+        if let Err((status, error)) = ensure_open_sprint(&mut conn, project_id, sprint_id) {
+            return (status, Json(ApiResponse::Error { error }));
+        }
     }
 
     match Project::find_by_id(&mut conn, project_id) {
-        Ok(Some(_)) => {
-            match Issue::create(
-                &mut conn,
+        Ok(Some(project)) => {
+            let workflow = match Workflow::load(&mut conn, project_id) {
+                Ok(workflow) => workflow,
+                Err(err) => {
+                    return (
+                        StatusCode::INTERNAL_SERVER_ERROR,
+                        Json(ApiResponse::<IssueResponse>::Error {
+                            error: format!("Failed to load workflow: {err}"),
+                        }),
+                    )
+                }
+            };
+
+            let initial = match payload.status.as_deref() {
+                Some(status) => workflow.state(status),
+                None => workflow.initial_state(),
+            };
+            let Some(initial) = initial else {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Json(ApiResponse::<IssueResponse>::Error {
+                        error: format!(
+                            "Invalid status value. Allowed values: {:?}",
+                            workflow.state_names()
+                        ),
+                    }),
+                );
+            };
+
+            let new_issue = NewIssue {
                 project_id,
-                &payload.title,
-                &payload.description,
-                &payload.created_by,
-                payload.assigned_to.as_deref(),
-                &status,
-            ) {
-                Ok(issue) => {
-                    let response = ApiResponse::Success(IssueResponse {
-                        id: issue.id,
-                        project_id: issue.project_id,
-                        title: issue.title,
-                        description: issue.description,
-                        created_by: issue.created_by,
-                        assigned_to: issue.assigned_to,
-                        status: issue.status,
-                        is_open: issue.is_open,
-                    });
-                    (StatusCode::CREATED, Json(response))
+                title: &payload.title,
+                description: &payload.description,
+                created_by_id,
+                assigned_to_id: payload.assigned_to,
+                status: &initial.name,
+                is_open: initial.is_open(),
+                priority: priority.level(),
+                severity: severity.as_str(),
+                start_date: payload.start_date,
+                due_date: payload.due_date,
+                parent_id: payload.parent_id,
+                original_estimate_minutes: payload.original_estimate_minutes,
+                milestone_id: payload.milestone_id,
+                sprint_id: payload.sprint_id,
+                estimate_points: payload.estimate_points,
+            };
+
+            let created = conn.transaction(|conn| {
+                if let Some(parent_id) = payload.parent_id {
+                    Issue::lock_hierarchy(conn, project_id)?;
+                    if let Err(response) = check_parent(conn, &project, None, parent_id)? {
+                        return Ok(Err(response));
+                    }
+                }
+                if let Err(response) = check_wip_limit(conn, project_id, new_issue.status, role)? {
+                    return Ok(Err(response));
+                }
+                let issue = Issue::create(conn, &new_issue)?;
+                let response = IssueResponse::load(conn, issue)?;
+                queue_event(conn, project_id, EVENT_ISSUE_CREATED, user.id, &response)?;
+                Ok::<_, DieselError>(Ok(response))
+            });
+
+            match created {
+                Ok(Err(response)) => response,
+                Ok(Ok(response)) => {
+                    state
+                        .events
+                        .publish(project_id, EVENT_ISSUE_CREATED, user.id, &response);
+                    (StatusCode::CREATED, Json(ApiResponse::Success(response)))
                 }
                 Err(err) => (
                     StatusCode::INTERNAL_SERVER_ERROR,
@@ -177,14 +941,164 @@ pub async fn create_issue(
 pub struct UpdateIssueRequest {
     pub title: Option<String>,
     pub description: Option<String>,
-    pub assigned_to: Option<Option<String>>,
+    /// `null` unassigns the issue.
+    #[serde(default, deserialize_with = "nullable")]
+    pub assigned_to: Option<Option<i32>>,
     pub status: Option<String>,
-    pub is_open: Option<bool>,
+    /// Label names to attach.
+    #[serde(default)]
+    pub add_labels: Vec<String>,
+    /// Label names to detach.
+    #[serde(default)]
+    pub remove_labels: Vec<String>,
+    pub priority: Option<String>,
+    pub severity: Option<String>,
+    /// `null` clears the date.
+    #[serde(default, deserialize_with = "nullable")]
+    pub start_date: Option<Option<NaiveDate>>,
+    /// `null` clears the date.
+    #[serde(default, deserialize_with = "nullable")]
+    pub due_date: Option<Option<NaiveDate>>,
+    /// `null` makes the issue top-level.
+    #[serde(default, deserialize_with = "nullable")]
+    pub parent_id: Option<Option<i32>>,
+    /// `null` clears the estimate.
+    #[serde(default, deserialize_with = "nullable")]
+    pub original_estimate_minutes: Option<Option<i32>>,
+    /// `null` clears the estimate.
+    #[serde(default, deserialize_with = "nullable")]
+    pub estimate_points: Option<Option<i32>>,
+    /// `null` removes the issue from its milestone.
+    #[serde(default, deserialize_with = "nullable")]
+    pub milestone_id: Option<Option<i32>>,
+    /// `null` moves the issue to the backlog.
+    #[serde(default, deserialize_with = "nullable")]
+    pub sprint_id: Option<Option<i32>>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct UpdateIssueParams {
+    /// Close the issue even if it has open sub-issues.
+    #[serde(default)]
+    pub force: bool,
+}
+
+/// Distinguishes an explicit `null` (`Some(None)`) from an absent field
+/// (`None`).
+pub(crate) fn nullable<'de, T, D>(deserializer: D) -> Result<Option<Option<T>>, D::Error>
+where
+    T: Deserialize<'de>,
+    D: Deserializer<'de>,
+{
+    Option::deserialize(deserializer).map(Some)
+}
+
+/// Checks moving `issue` to the `target` status against the project's
+/// workflow and, when that closes the issue, against its open sub-issues
+/// unless `force` is set. Returns whether the issue is open afterwards.
+pub(crate) fn check_status_change<T>(
+    conn: &mut PgConnection,
+    issue: &Issue,
+    target: &str,
+    force: bool,
+) -> QueryResult<Result<bool, Rejection<T>>> {
+    let workflow = Workflow::load(conn, issue.project_id)?;
+
+    let Some(state) = workflow.state(target) else {
+        return Ok(Err((
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::Error {
+                error: format!(
+                    "Invalid status value. Allowed values: {:?}",
+                    workflow.state_names()
+                ),
+            }),
+        )));
+    };
+
+    if !workflow.can_transition(&issue.status, target) {
+        let allowed = workflow.allowed_targets(&issue.status);
+        return Ok(Err((
+            StatusCode::UNPROCESSABLE_ENTITY,
+            Json(ApiResponse::DetailedError {
+                error: format!("Cannot move issue from '{}' to '{}'", issue.status, target),
+                code: "invalid_transition".to_string(),
+                details: json!({
+                    "from": issue.status,
+                    "to": target,
+                    "allowed": allowed,
+                }),
+            }),
+        )));
+    }
+
+    if issue.is_open && !state.is_open() && !force {
+        let open = Issue::open_children(conn, issue.id)?;
+        if !open.is_empty() {
+            return Ok(Err((
+                StatusCode::UNPROCESSABLE_ENTITY,
+                Json(ApiResponse::DetailedError {
+                    error: format!(
+                        "Issue {} has {} open sub-issues; pass ?force=true to close it anyway",
+                        issue.id,
+                        open.len()
+                    ),
+                    code: "open_children".to_string(),
+                    details: json!({ "open_children": open }),
+                }),
+            )));
+        }
+    }
+
+    Ok(Ok(state.is_open()))
+}
+
+/// Rejects putting one more issue into `status` while it is at its WIP
+/// limit; maintainers may go over limits. Locks the project's board when the
+/// status is limited, so must run inside the transaction that makes the
+/// change.
+pub(crate) fn check_wip_limit<T>(
+    conn: &mut PgConnection,
+    project_id: i32,
+    status: &str,
+    role: ProjectRole,
+) -> QueryResult<Result<(), Rejection<T>>> {
+    if role >= ProjectRole::Maintainer {
+        return Ok(Ok(()));
+    }
+    let Some(limit) = Workflow::wip_limit(conn, project_id, status)? else {
+        return Ok(Ok(()));
+    };
+
+    Issue::lock_board(conn, project_id)?;
+    let filter = IssueFilter {
+        project_id: Some(project_id),
+        statuses: vec![status.to_string()],
+        ..IssueFilter::default()
+    };
+    let count = Issue::count(conn, &filter)?;
+    if count >= i64::from(limit) {
+        return Ok(Err((
+            StatusCode::UNPROCESSABLE_ENTITY,
+            Json(ApiResponse::DetailedError {
+                error: format!(
+                    "Status '{}' is at its WIP limit of {} issues",
+                    status, limit
+                ),
+                code: "wip_limit_reached".to_string(),
+                details: json!({ "status": status, "wip_limit": limit, "count": count }),
+            }),
+        )));
+    }
+
+    Ok(Ok(()))
 }
 
 pub async fn update_issue(
     State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
     Path((project_id, issue_id)): Path<(i32, i32)>,
+    Query(params): Query<UpdateIssueParams>,
     Json(payload): Json<UpdateIssueRequest>,
 ) -> impl IntoResponse {
     let mut conn = match state.pool.get() {
@@ -199,8 +1113,13 @@ pub async fn update_issue(
         }
     };
 
+    let role = match require_project_role(&mut conn, &user, project_id, ProjectRole::Reporter) {
+        Ok(role) => role,
+        Err(err) => return err.into_api_response(),
+    };
+
     match Project::find_by_id(&mut conn, project_id) {
-        Ok(Some(_)) => match Issue::get_by_id(&mut conn, issue_id) {
+        Ok(Some(project)) => match Issue::get_by_id(&mut conn, issue_id) {
             Ok(issue) => {
                 if issue.project_id != project_id {
                     return (
@@ -211,27 +1130,171 @@ pub async fn update_issue(
                     );
                 }
 
+                if role < ProjectRole::Developer && issue.created_by_id != user.id {
+                    return AppError::Forbidden(
+                        "Reporters can only edit their own issues".to_string(),
+                    )
+                    .into_api_response();
+                }
+
+                if let Some(Some(assignee)) = payload.assigned_to {
+                    if let Err((status, error)) = ensure_active_user(&mut conn, assignee) {
+                        return (status, Json(ApiResponse::Error { error }));
+                    }
+                }
+                if let Some(milestone_id) = payload
+                    .milestone_id
+                    .flatten()
+                    .filter(|id| issue.milestone_id != Some(*id))
+                {
+                    if let Err((status, error)) =
+                        ensure_open_milestone(&mut conn, project_id, milestone_id)
+                    {
+                        return (status, Json(ApiResponse::Error { error }));
+                    }
+                }
+                if let Some(sprint_id) = payload
+                    .sprint_id
+                    .flatten()
+                    .filter(|id| issue.sprint_id != Some(*id))
+                {
+                    if let Err((status, error)) =
+                        ensure_open_sprint(&mut conn, project_id, sprint_id)
+                    {
+                        return (status, Json(ApiResponse::Error { error }));
+                    }
+                }
+
+                let mut is_open = None;
+                if let Some(target) = payload.status.as_deref().filter(|s| *s != issue.status) {
+                    match check_status_change(&mut conn, &issue, target, params.force) {
+                        Ok(Ok(open)) => is_open = Some(open),
+                        Ok(Err(response)) => return response,
+                        Err(err) => {
+                            return (
+                                StatusCode::INTERNAL_SERVER_ERROR,
+                                Json(ApiResponse::<IssueResponse>::Error {
+                                    error: format!("Failed to check status change: {err}"),
+                                }),
+                            )
+                        }
+                    }
+                }
+
+                let mut label_changes = LabelChanges::default();
+                if !payload.add_labels.is_empty() || !payload.remove_labels.is_empty() {
+                    let catalog = match Label::list(&mut conn, project_id) {
+                        Ok(catalog) => catalog,
+                        Err(err) => {
+                            return (
+                                StatusCode::INTERNAL_SERVER_ERROR,
+                                Json(ApiResponse::<IssueResponse>::Error {
+                                    error: format!("Failed to load labels: {err}"),
+                                }),
+                            )
+                        }
+                    };
+                    let resolved =
+                        resolve_labels(&catalog, &payload.add_labels).and_then(|attach| {
+                            let detach = resolve_labels(&catalog, &payload.remove_labels)?;
+                            Ok(LabelChanges { attach, detach })
+                        });
+                    label_changes = match resolved {
+                        Ok(changes) => changes,
+                        Err(error) => {
+                            return (
+                                StatusCode::BAD_REQUEST,
+                                Json(ApiResponse::<IssueResponse>::Error { error }),
+                            )
+                        }
+                    };
+                }
+
+                let priority = payload.priority.as_deref().map(parse_priority).transpose();
+                let severity = payload.severity.as_deref().map(parse_severity).transpose();
+                let (priority, severity) = match (priority, severity) {
+                    (Ok(priority), Ok(severity)) => (priority, severity),
+                    (Err(error), _) | (_, Err(error)) => {
+                        return (
+                            StatusCode::BAD_REQUEST,
+                            Json(ApiResponse::<IssueResponse>::Error { error }),
+                        )
+                    }
+                };
+
+                if let Err(error) = check_dates(
+                    payload.start_date.unwrap_or(issue.start_date),
+                    payload.due_date.unwrap_or(issue.due_date),
+                )
+                .and_then(|_| {
+                    check_estimate(
+                        payload.original_estimate_minutes.flatten(),
+                        payload.estimate_points.flatten(),
+                    )
+                }) {
+                    return (
+                        StatusCode::BAD_REQUEST,
+                        Json(ApiResponse::<IssueResponse>::Error { error }),
+                    );
+                }
+
                 let changes = IssueChanges {
                     title: payload.title,
                     description: payload.description,
-                    assigned_to: payload.assigned_to,
+                    assigned_to_id: payload.assigned_to,
                     status: payload.status,
-                    is_open: payload.is_open,
+                    is_open,
+                    priority: priority.map(Priority::level),
+                    severity: severity.map(|s| s.as_str().to_string()),
+                    start_date: payload.start_date,
+                    due_date: payload.due_date,
+                    parent_id: payload.parent_id,
+                    original_estimate_minutes: payload.original_estimate_minutes,
+                    milestone_id: payload.milestone_id,
+                    sprint_id: payload.sprint_id,
+                    estimate_points: payload.estimate_points,
+                    rank: None,
                 };
+                if changes.is_empty() && label_changes.is_empty() {
+                    return (
+                        StatusCode::BAD_REQUEST,
+                        Json(ApiResponse::<IssueResponse>::Error {
+                            error: "At least one field must be provided for update.".to_string(),
+                        }),
+                    );
+                }
 
-                match issue.update(&mut conn, changes) {
-                    Ok(updated_issue) => {
-                        let response = ApiResponse::Success(IssueResponse {
-                            id: updated_issue.id,
-                            project_id: updated_issue.project_id,
-                            title: updated_issue.title,
-                            description: updated_issue.description,
-                            created_by: updated_issue.created_by,
-                            assigned_to: updated_issue.assigned_to,
-                            status: updated_issue.status,
-                            is_open: updated_issue.is_open,
-                        });
-                        (StatusCode::OK, Json(response))
+                let new_parent = changes
+                    .parent_id
+                    .flatten()
+                    .filter(|parent_id| issue.parent_id != Some(*parent_id));
+                let updated = conn.transaction(|conn| {
+                    if let Some(parent_id) = new_parent {
+                        Issue::lock_hierarchy(conn, project_id)?;
+                        if let Err(response) =
+                            check_parent(conn, &project, Some(issue.id), parent_id)?
+                        {
+                            return Ok(Err(response));
+                        }
+                    }
+                    if let Some(status) = changes.status.as_deref().filter(|_| is_open.is_some()) {
+                        if let Err(response) = check_wip_limit(conn, project_id, status, role)? {
+                            return Ok(Err(response));
+                        }
+                    }
+                    let updated_issue = issue.update(conn, changes, &label_changes, user.id)?;
+                    let response = IssueResponse::load(conn, updated_issue)?;
+                    queue_event(conn, project_id, EVENT_ISSUE_UPDATED, user.id, &response)?;
+                    Ok::<_, DieselError>(Ok(response))
+                });
+
+                match updated {
+                    Ok(Err(response)) => response,
+                    Ok(Ok(response)) => {
+                        state
+                            .events
+                            .publish(project_id, EVENT_ISSUE_UPDATED, user.id, &response);
+                        (StatusCode::OK, Json(ApiResponse::Success(response)))
                     }
                     Err(err) => (
                         StatusCode::INTERNAL_SERVER_ERROR,
@@ -263,6 +1326,54 @@ pub async fn update_issue(
     }
 }
 
+#[derive(Debug, Deserialize)]
+pub struct OverdueParams {
+    pub assigned_to: Option<i32>,
+    pub due_after: Option<NaiveDate>,
+    pub due_before: Option<NaiveDate>,
+}
+
+/// Open issues past their due date across every project the caller can see,
+/// most overdue first.
+pub async fn get_overdue_issues(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
+    Query(params): Query<OverdueParams>,
+) -> impl IntoResponse {
+    let mut conn = match state.pool.get() {
+        Ok(conn) => conn,
+        Err(err) => {
+            return (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                Json(ApiResponse::<Vec<IssueResponse>>::Error {
+                    error: format!("DB pool error: {err}"),
+                }),
+            )
+        }
+    };
+
+    let filter = IssueFilter {
+        member_id: (!user.is_admin).then_some(user.id),
+        assigned_to_id: params.assigned_to,
+        due_after: params.due_after,
+        due_before: params.due_before,
+        ..IssueFilter::default()
+    };
+    let today = Utc::now().date_naive();
+
+    match Issue::overdue(&mut conn, &filter, today)
+        .and_then(|issues| IssueResponse::load_many(&mut conn, issues))
+    {
+        Ok(issues) => (StatusCode::OK, Json(ApiResponse::Success(issues))),
+        Err(err) => (
+            StatusCode::INTERNAL_SERVER_ERROR,
+            Json(ApiResponse::<Vec<IssueResponse>>::Error {
+                error: format!("Failed to fetch overdue issues: {err}"),
+            }),
+        ),
+    }
+}
+
 #[derive(Debug, Serialize)]
 pub struct DeleteResponse {
     pub success: bool,
@@ -271,6 +1382,7 @@ pub struct DeleteResponse {
 
 pub async fn delete_issue(
     State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
     Path((project_id, issue_id)): Path<(i32, i32)>,
 ) -> impl IntoResponse {
     let mut conn = match state.pool.get() {
@@ -286,6 +1398,10 @@ pub async fn delete_issue(
         }
     };
 
+    if let Err(err) = require_project_role(&mut conn, &user, project_id, ProjectRole::Maintainer) {
+        return err.into_api_response();
+    }
+
     match Project::find_by_id(&mut conn, project_id) {
         Ok(Some(_)) => match Issue::get_by_id(&mut conn, issue_id) {
             Ok(issue) => {
@@ -298,16 +1414,29 @@ pub async fn delete_issue(
                     );
                 }
 
-                match Issue::delete(&mut conn, issue_id) {
-                    Ok(true) => {
+                // The event carries the issue as it was before deletion
+                let deleted = conn.transaction(|conn| {
+                    let snapshot = IssueResponse::load(conn, issue)?;
+                    if !Issue::delete(conn, issue_id)? {
+                        return Ok(None);
+                    }
+                    queue_event(conn, project_id, EVENT_ISSUE_DELETED, user.id, &snapshot)?;
+                    Ok::<_, DieselError>(Some(snapshot))
+                });
+
+                match deleted {
+                    Ok(Some(snapshot)) => {
                         tracing::info!("Deleted issue {} successfully", issue_id);
+                        state
+                            .events
+                            .publish(project_id, EVENT_ISSUE_DELETED, user.id, &snapshot);
                         let response = ApiResponse::Success(DeleteResponse {
                             success: true,
                             message: format!("Issue {} successfully deleted", issue_id),
                         });
                         (StatusCode::OK, Json(response))
                     }
-                    Ok(false) => {
+                    Ok(None) => {
                         tracing::warn!("Issue {} not found", issue_id);
                         (
                             StatusCode::NOT_FOUND,
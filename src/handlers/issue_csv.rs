@@ -0,0 +1,879 @@
+//! Spreadsheet exchange of a project's issues.
+//!
+//! Exports stream the filtered issue list a page at a time, so large
+//! projects never sit in memory at once. Imports are all or nothing: every
+//! row is checked, and issues are only created when none has an error.
+
+use crate::auth::{require_project_role, AuthUser};
+use crate::error::{internal_server_error, AppError};
+use crate::handlers::api_response::ApiResponse;
+use crate::handlers::issue::{
+    check_dates, check_estimate, check_wip_limit, issue_cursor, issue_keyset, parse_priority,
+    parse_severity, resolve_labels, IssueListParams, IssueResponse, Rejection,
+};
+use crate::handlers::webhook::queue_event;
+use crate::models::{
+    Issue, IssueListQuery, Label, NewIssue, Priority, Project, ProjectRole, Severity, User,
+    Workflow, EVENT_ISSUE_CREATED,
+};
+use crate::routes::router::AppState;
+
+use axum::{
+    body::{Body, Bytes},
+    extract::{Path, Query, State},
+    http::{header, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use chrono::NaiveDate;
+use diesel::prelude::*;
+use diesel::result::Error as DieselError;
+use futures_util::stream::{self, StreamExt};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::{HashMap, HashSet};
+use std::sync::Arc;
+
+/// Issues fetched per query while streaming an export.
+pub const EXPORT_PAGE_SIZE: i64 = 500;
+pub const MAX_IMPORT_ROWS: usize = 1000;
+pub const MAX_TITLE_LENGTH: usize = 200;
+pub const MAX_USERNAME_LENGTH: usize = 100;
+
+/// A CSV column. Exports may use any of them; imports only those that map
+/// to a field of a new issue.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Column {
+    Id,
+    Key,
+    Number,
+    Title,
+    Description,
+    Status,
+    IsOpen,
+    Priority,
+    Severity,
+    CreatedBy,
+    AssignedTo,
+    Labels,
+    StartDate,
+    DueDate,
+    ParentId,
+    OriginalEstimateMinutes,
+    EstimatePoints,
+    MilestoneId,
+    SprintId,
+    CreatedAt,
+    UpdatedAt,
+}
+
+impl Column {
+    pub const ALL: [Column; 21] = [
+        Column::Id,
+        Column::Key,
+        Column::Number,
+        Column::Title,
+        Column::Description,
+        Column::Status,
+        Column::IsOpen,
+        Column::Priority,
+        Column::Severity,
+        Column::CreatedBy,
+        Column::AssignedTo,
+        Column::Labels,
+        Column::StartDate,
+        Column::DueDate,
+        Column::ParentId,
+        Column::OriginalEstimateMinutes,
+        Column::EstimatePoints,
+        Column::MilestoneId,
+        Column::SprintId,
+        Column::CreatedAt,
+        Column::UpdatedAt,
+    ];
+
+    /// Exported when `?columns=` is not given.
+    pub const DEFAULT: [Column; 10] = [
+        Column::Key,
+        Column::Title,
+        Column::Status,
+        Column::Priority,
+        Column::Severity,
+        Column::AssignedTo,
+        Column::CreatedBy,
+        Column::Labels,
+        Column::DueDate,
+        Column::CreatedAt,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Column::Id => "id",
+            Column::Key => "key",
+            Column::Number => "number",
+            Column::Title => "title",
+            Column::Description => "description",
+            Column::Status => "status",
+            Column::IsOpen => "is_open",
+            Column::Priority => "priority",
+            Column::Severity => "severity",
+            Column::CreatedBy => "created_by",
+            Column::AssignedTo => "assigned_to",
+            Column::Labels => "labels",
+            Column::StartDate => "start_date",
+            Column::DueDate => "due_date",
+            Column::ParentId => "parent_id",
+            Column::OriginalEstimateMinutes => "original_estimate_minutes",
+            Column::EstimatePoints => "estimate_points",
+            Column::MilestoneId => "milestone_id",
+            Column::SprintId => "sprint_id",
+            Column::CreatedAt => "created_at",
+            Column::UpdatedAt => "updated_at",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Column> {
+        Column::ALL
+            .into_iter()
+            .find(|column| column.as_str().eq_ignore_ascii_case(value))
+    }
+
+    pub fn is_importable(self) -> bool {
+        matches!(
+            self,
+            Column::Title
+                | Column::Description
+                | Column::Status
+                | Column::Priority
+                | Column::Severity
+                | Column::CreatedBy
+                | Column::AssignedTo
+                | Column::StartDate
+                | Column::DueDate
+                | Column::OriginalEstimateMinutes
+                | Column::EstimatePoints
+        )
+    }
+
+    /// The issue's value for this column; users appear by username, so an
+    /// export can be imported again. Text is passed through `escape_formula`.
+    fn value(self, issue: &IssueResponse) -> String {
+        fn optional<T: ToString>(value: Option<T>) -> String {
+            value.map(|value| value.to_string()).unwrap_or_default()
+        }
+
+        match self {
+            Column::Id => issue.id.to_string(),
+            Column::Key => escape_formula(&issue.key),
+            Column::Number => issue.number.to_string(),
+            Column::Title => escape_formula(&issue.title),
+            Column::Description => escape_formula(&issue.description),
+            Column::Status => escape_formula(&issue.status),
+            Column::IsOpen => issue.is_open.to_string(),
+            Column::Priority => issue.priority.as_str().to_string(),
+            Column::Severity => issue.severity.as_str().to_string(),
+            Column::CreatedBy => escape_formula(&issue.created_by.username),
+            Column::AssignedTo => optional(
+                issue
+                    .assigned_to
+                    .as_ref()
+                    .map(|u| escape_formula(&u.username)),
+            ),
+            Column::Labels => escape_formula(
+                &issue
+                    .labels
+                    .iter()
+                    .map(|label| label.name.as_str())
+                    .collect::<Vec<_>>()
+                    .join(","),
+            ),
+            Column::StartDate => optional(issue.start_date),
+            Column::DueDate => optional(issue.due_date),
+            Column::ParentId => optional(issue.parent_id),
+            Column::OriginalEstimateMinutes => optional(issue.original_estimate_minutes),
+            Column::EstimatePoints => optional(issue.estimate_points),
+            Column::MilestoneId => optional(issue.milestone_id),
+            Column::SprintId => optional(issue.sprint_id),
+            Column::CreatedAt => issue.created_at.to_rfc3339(),
+            Column::UpdatedAt => issue.updated_at.to_rfc3339(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ExportParams {
+    /// Comma-separated column names, in the order they should appear.
+    pub columns: Option<String>,
+}
+
+fn parse_columns(value: Option<&str>) -> Result<Vec<Column>, AppError> {
+    let Some(value) = value.filter(|value| !value.trim().is_empty()) else {
+        return Ok(Column::DEFAULT.to_vec());
+    };
+    value
+        .split(',')
+        .map(str::trim)
+        .map(|name| {
+            Column::parse(name).ok_or_else(|| {
+                AppError::Validation(format!(
+                    "Unknown column '{}'. Allowed values: {:?}",
+                    name,
+                    Column::ALL.map(Column::as_str)
+                ))
+            })
+        })
+        .collect()
+}
+
+/// Leading characters that make a spreadsheet read a cell as a formula.
+const FORMULA_PREFIXES: [char; 6] = ['=', '+', '-', '@', '\t', '\r'];
+
+/// Prefixes text a spreadsheet would run as a formula with `'`, which makes
+/// it show the text instead.
+fn escape_formula(text: &str) -> String {
+    if text.starts_with(FORMULA_PREFIXES) {
+        format!("'{}", text)
+    } else {
+        text.to_string()
+    }
+}
+
+/// Undoes `escape_formula` on imported cells.
+fn unescape_formula(text: &str) -> &str {
+    match text.strip_prefix('\'') {
+        Some(rest) if rest.starts_with(FORMULA_PREFIXES) => rest,
+        _ => text,
+    }
+}
+
+fn csv_line<I, S>(fields: I) -> Result<Bytes, csv::Error>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<[u8]>,
+{
+    let mut writer = csv::Writer::from_writer(Vec::new());
+    writer.write_record(fields)?;
+    let buffer = writer.into_inner().map_err(|err| err.into_error())?;
+    Ok(Bytes::from(buffer))
+}
+
+/// Checks the export request and builds the query for its first page.
+fn prepare_export(
+    state: &AppState,
+    user: &User,
+    project_id: i32,
+    params: &IssueListParams,
+) -> Result<(Project, IssueListQuery), AppError> {
+    let mut conn = state.pool.get()?;
+    require_project_role(&mut conn, user, project_id, ProjectRole::Viewer)?;
+    let project = Project::find_by_id(&mut conn, project_id)?
+        .ok_or_else(|| AppError::ProjectNotFound(project_id.to_string()))?;
+
+    let filter = params.filter(project_id);
+    let workflow = Workflow::load(&mut conn, project_id)?;
+    if let Some(invalid) = filter.statuses.iter().find(|s| workflow.state(s).is_none()) {
+        return Err(AppError::Validation(format!(
+            "Invalid status value '{}'. Allowed values: {:?}",
+            invalid,
+            workflow.state_names()
+        )));
+    }
+    if !filter.labels.is_empty() {
+        let catalog = Label::list(&mut conn, project_id)?;
+        resolve_labels(&catalog, &filter.labels).map_err(AppError::Validation)?;
+    }
+
+    let query = IssueListQuery {
+        filter,
+        sort: params.sort.unwrap_or_default(),
+        descending: params.order.unwrap_or_default().is_descending(),
+        after: None,
+        limit: EXPORT_PAGE_SIZE,
+    };
+    Ok((project, query))
+}
+
+/// One page of CSV rows, and the query for the page after it unless this
+/// was the last.
+fn export_page(
+    state: &AppState,
+    mut query: IssueListQuery,
+    columns: &[Column],
+) -> Result<(Bytes, Option<IssueListQuery>), AppError> {
+    let mut conn = state.pool.get()?;
+    let issues = Issue::list(&mut conn, &query)?;
+    let full = issues.len() as i64 == query.limit;
+    let issues = IssueResponse::load_many(&mut conn, issues)?;
+
+    let mut chunk = Vec::new();
+    for issue in &issues {
+        let line = csv_line(columns.iter().map(|column| column.value(issue)))
+            .map_err(|err| AppError::Validation(format!("Failed to write CSV: {err}")))?;
+        chunk.extend_from_slice(&line);
+    }
+
+    let next = match issues.last() {
+        Some(last) if full => {
+            query.after = issue_keyset(query.sort, &issue_cursor(query.sort, last));
+            query.after.is_some().then_some(query)
+        }
+        _ => None,
+    };
+    Ok((Bytes::from(chunk), next))
+}
+
+/// Streams the project's issues as CSV. Accepts the listing's filters and
+/// sort order, and `?columns=` to choose the columns; `limit` and `after`
+/// are ignored, as every matching issue is exported.
+pub async fn export_issues(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
+    Path(project_id): Path<i32>,
+    Query(params): Query<IssueListParams>,
+    Query(export): Query<ExportParams>,
+) -> Response {
+    let columns = match parse_columns(export.columns.as_deref()) {
+        Ok(columns) => columns,
+        Err(err) => return err.into_api_response::<()>().into_response(),
+    };
+    // Pages are fetched as the client reads, each on a connection of its own
+    let (project, query) = match prepare_export(&state, &user, project_id, &params) {
+        Ok(prepared) => prepared,
+        Err(err) => return err.into_api_response::<()>().into_response(),
+    };
+    let header = match csv_line(columns.iter().map(|column| column.as_str())) {
+        Ok(header) => header,
+        Err(err) => {
+            return internal_server_error::<()>("Failed to write CSV", &err).into_response()
+        }
+    };
+
+    let rows = stream::try_unfold(Some(query), move |query| {
+        let state = state.clone();
+        let columns = columns.clone();
+        async move {
+            let Some(query) = query else {
+                return Ok(None);
+            };
+            // The response has started, so failures can only cut it short
+            export_page(&state, query, &columns)
+                .map(Some)
+                .map_err(|err| {
+                    tracing::error!("Failed to export issues of project {}: {}", project_id, err);
+                    std::io::Error::other(err)
+                })
+        }
+    });
+    let body = stream::once(async { Ok::<_, std::io::Error>(header) }).chain(rows);
+
+    (
+        [
+            (header::CONTENT_TYPE, "text/csv; charset=utf-8".to_string()),
+            (
+                header::CONTENT_DISPOSITION,
+                format!("attachment; filename=\"{}-issues.csv\"", project.key),
+            ),
+        ],
+        Body::from_stream(body),
+    )
+        .into_response()
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ImportRequest {
+    /// CSV text whose first record names the columns.
+    pub csv: String,
+    /// CSV header to column name, e.g. `{"Summary": "title"}`. Headers not
+    /// listed are imported into the column of the same name, if there is
+    /// one, and ignored otherwise.
+    #[serde(default)]
+    pub mapping: HashMap<String, String>,
+    /// Check every row without creating anything.
+    #[serde(default)]
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RowError {
+    /// Line of the CSV text the row starts on; the header is line 1.
+    pub line: u64,
+    /// Header of the offending column, if the error is about one.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub column: Option<String>,
+    pub message: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ImportResponse {
+    pub dry_run: bool,
+    /// Data rows in the CSV text.
+    pub rows: usize,
+    /// The created issues, in row order; empty for a dry run.
+    pub issues: Vec<IssueResponse>,
+    /// Headers that were not imported.
+    pub ignored_columns: Vec<String>,
+}
+
+/// A row that passed validation, ready to insert.
+struct ImportRow {
+    line: u64,
+    title: String,
+    description: String,
+    status: String,
+    is_open: bool,
+    priority: Priority,
+    severity: Severity,
+    created_by_id: i32,
+    assigned_to_id: Option<i32>,
+    start_date: Option<NaiveDate>,
+    due_date: Option<NaiveDate>,
+    original_estimate_minutes: Option<i32>,
+    estimate_points: Option<i32>,
+}
+
+/// Which column each CSV field is imported into.
+fn map_headers(
+    headers: &csv::StringRecord,
+    mapping: &HashMap<String, String>,
+) -> Result<Vec<Option<Column>>, AppError> {
+    if let Some(missing) = mapping
+        .keys()
+        .find(|name| !headers.iter().any(|header| header == name.as_str()))
+    {
+        return Err(AppError::Validation(format!(
+            "Mapped header '{}' is not in the CSV",
+            missing
+        )));
+    }
+
+    let mut seen = HashSet::new();
+    let mut columns = Vec::with_capacity(headers.len());
+    for header in headers {
+        let column = match mapping.get(header) {
+            Some(name) => {
+                let column = Column::parse(name.trim())
+                    .filter(|column| column.is_importable())
+                    .ok_or_else(|| {
+                        AppError::Validation(format!(
+                            "Header '{}' is mapped to '{}', which cannot be imported",
+                            header, name
+                        ))
+                    })?;
+                Some(column)
+            }
+            None => Column::parse(header.trim()).filter(|column| column.is_importable()),
+        };
+        if let Some(column) = column {
+            if !seen.insert(column) {
+                return Err(AppError::Validation(format!(
+                    "More than one header maps to '{}'",
+                    column.as_str()
+                )));
+            }
+        }
+        columns.push(column);
+    }
+
+    if !seen.contains(&Column::Title) {
+        return Err(AppError::Validation(
+            "No header maps to 'title'".to_string(),
+        ));
+    }
+    Ok(columns)
+}
+
+/// Everything rows are checked against, loaded once per import.
+struct RowContext<'a> {
+    user: &'a User,
+    role: ProjectRole,
+    workflow: &'a Workflow,
+    /// Active users named anywhere in the file.
+    users: HashMap<String, User>,
+}
+
+impl RowContext<'_> {
+    fn user_id(&self, username: &str) -> Result<i32, String> {
+        if username.chars().count() > MAX_USERNAME_LENGTH {
+            return Err(format!(
+                "Usernames are at most {} characters",
+                MAX_USERNAME_LENGTH
+            ));
+        }
+        self.users
+            .get(username)
+            .filter(|user| user.is_active)
+            .map(|user| user.id)
+            .ok_or_else(|| format!("User '{}' does not exist or is inactive", username))
+    }
+
+    /// Validates one record, collecting an error for every bad field.
+    fn check(
+        &self,
+        line: u64,
+        headers: &csv::StringRecord,
+        columns: &[Option<Column>],
+        record: &csv::StringRecord,
+    ) -> Result<ImportRow, Vec<RowError>> {
+        let mut values: HashMap<Column, (&str, &str)> = HashMap::new();
+        for ((column, header), value) in columns.iter().zip(headers).zip(record) {
+            if let Some(column) = column {
+                values.insert(*column, (header, unescape_formula(value.trim())));
+            }
+        }
+        let mut errors = Vec::new();
+        let field = |column: Column| -> Option<&str> {
+            values
+                .get(&column)
+                .map(|(_, value)| *value)
+                .filter(|value| !value.is_empty())
+        };
+        let header = |column: Column| -> Option<String> {
+            values.get(&column).map(|(header, _)| header.to_string())
+        };
+        let mut fail = |column: Column, message: String| {
+            errors.push(RowError {
+                line,
+                column: header(column),
+                message,
+            });
+        };
+
+        let title = field(Column::Title).unwrap_or_default().to_string();
+        if title.is_empty() {
+            fail(Column::Title, "Title is required".to_string());
+        } else if title.chars().count() > MAX_TITLE_LENGTH {
+            fail(
+                Column::Title,
+                format!("Titles are at most {} characters", MAX_TITLE_LENGTH),
+            );
+        }
+
+        let state = match field(Column::Status) {
+            Some(status) => self.workflow.state(status),
+            None => self.workflow.initial_state(),
+        };
+        if state.is_none() {
+            fail(
+                Column::Status,
+                format!(
+                    "Invalid status value '{}'. Allowed values: {:?}",
+                    field(Column::Status).unwrap_or_default(),
+                    self.workflow.state_names()
+                ),
+            );
+        }
+
+        let priority = field(Column::Priority)
+            .map(parse_priority)
+            .transpose()
+            .unwrap_or_else(|message| {
+                fail(Column::Priority, message);
+                None
+            });
+        let severity = field(Column::Severity)
+            .map(parse_severity)
+            .transpose()
+            .unwrap_or_else(|message| {
+                fail(Column::Severity, message);
+                None
+            });
+
+        let created_by_id = match field(Column::CreatedBy) {
+            Some(username) => match self.user_id(username) {
+                Ok(id) if id != self.user.id && self.role < ProjectRole::Developer => {
+                    fail(
+                        Column::CreatedBy,
+                        "Reporters can only file issues as themselves".to_string(),
+                    );
+                    None
+                }
+                Ok(id) => Some(id),
+                Err(message) => {
+                    fail(Column::CreatedBy, message);
+                    None
+                }
+            },
+            None => Some(self.user.id),
+        };
+        let assigned_to_id = field(Column::AssignedTo)
+            .map(|username| self.user_id(username))
+            .transpose()
+            .unwrap_or_else(|message| {
+                fail(Column::AssignedTo, message);
+                None
+            });
+
+        let mut date = |column: Column| {
+            field(column)
+                .map(|value| NaiveDate::parse_from_str(value, "%Y-%m-%d"))
+                .transpose()
+                .unwrap_or_else(|_| {
+                    fail(
+                        column,
+                        format!("{} must be a YYYY-MM-DD date", column.as_str()),
+                    );
+                    None
+                })
+        };
+        let start_date = date(Column::StartDate);
+        let due_date = date(Column::DueDate);
+        if let Err(message) = check_dates(start_date, due_date) {
+            fail(Column::DueDate, message);
+        }
+
+        let mut number = |column: Column| {
+            field(column)
+                .map(str::parse::<i32>)
+                .transpose()
+                .unwrap_or_else(|_| {
+                    fail(
+                        column,
+                        format!("{} must be a whole number", column.as_str()),
+                    );
+                    None
+                })
+        };
+        let original_estimate_minutes = number(Column::OriginalEstimateMinutes);
+        let estimate_points = number(Column::EstimatePoints);
+        if let Err(message) = check_estimate(original_estimate_minutes, estimate_points) {
+            let column = if original_estimate_minutes.is_some_and(|m| m < 0) {
+                Column::OriginalEstimateMinutes
+            } else {
+                Column::EstimatePoints
+            };
+            fail(column, message);
+        }
+
+        match (state, created_by_id) {
+            (Some(state), Some(created_by_id)) if errors.is_empty() => Ok(ImportRow {
+                line,
+                title,
+                description: field(Column::Description).unwrap_or_default().to_string(),
+                status: state.name.clone(),
+                is_open: state.is_open(),
+                priority: priority.unwrap_or_default(),
+                severity: severity.unwrap_or_default(),
+                created_by_id,
+                assigned_to_id,
+                start_date,
+                due_date,
+                original_estimate_minutes,
+                estimate_points,
+            }),
+            _ => Err(errors),
+        }
+    }
+}
+
+/// Why the import transaction was rolled back.
+enum Abort {
+    Database(DieselError),
+    Rejected(Vec<RowError>),
+    DryRun,
+}
+
+impl From<DieselError> for Abort {
+    fn from(err: DieselError) -> Self {
+        Abort::Database(err)
+    }
+}
+
+fn rejection_message<T>((_, Json(body)): Rejection<T>) -> String {
+    match body {
+        ApiResponse::Error { error } | ApiResponse::DetailedError { error, .. } => error,
+        ApiResponse::Success(_) => String::new(),
+    }
+}
+
+fn invalid_rows<T>(dry_run: bool, rows: usize, errors: Vec<RowError>) -> Rejection<T> {
+    let mut lines: Vec<u64> = errors.iter().map(|error| error.line).collect();
+    lines.dedup();
+    (
+        StatusCode::UNPROCESSABLE_ENTITY,
+        Json(ApiResponse::DetailedError {
+            error: format!(
+                "{} of {} rows are invalid; nothing was imported",
+                lines.len(),
+                rows
+            ),
+            code: "invalid_rows".to_string(),
+            details: json!({ "dry_run": dry_run, "rows": rows, "errors": errors }),
+        }),
+    )
+}
+
+/// Creates an issue for every row of a CSV file in one transaction, or none
+/// if any row is invalid. A dry run reports the same errors without
+/// creating anything.
+pub async fn import_issues(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
+    Path(project_id): Path<i32>,
+    Json(payload): Json<ImportRequest>,
+) -> impl IntoResponse {
+    let mut conn = match state.pool.get() {
+        Ok(conn) => conn,
+        Err(err) => return AppError::from(err).into_api_response(),
+    };
+
+    let role = match require_project_role(&mut conn, &user, project_id, ProjectRole::Reporter) {
+        Ok(role) => role,
+        Err(err) => return err.into_api_response(),
+    };
+    let workflow = match Project::find_by_id(&mut conn, project_id) {
+        Ok(Some(_)) => match Workflow::load(&mut conn, project_id) {
+            Ok(workflow) => workflow,
+            Err(err) => return AppError::from(err).into_api_response(),
+        },
+        Ok(None) => return AppError::ProjectNotFound(project_id.to_string()).into_api_response(),
+        Err(err) => return AppError::from(err).into_api_response(),
+    };
+
+    let mut reader = csv::ReaderBuilder::new().from_reader(payload.csv.as_bytes());
+    let headers = match reader.headers() {
+        Ok(headers) if !headers.is_empty() => headers.clone(),
+        Ok(_) => {
+            return AppError::Validation("The CSV has no header".to_string()).into_api_response()
+        }
+        Err(err) => {
+            return AppError::Validation(format!("Invalid CSV header: {err}")).into_api_response()
+        }
+    };
+    let columns = match map_headers(&headers, &payload.mapping) {
+        Ok(columns) => columns,
+        Err(err) => return err.into_api_response(),
+    };
+    let ignored_columns = headers
+        .iter()
+        .zip(&columns)
+        .filter(|(_, column)| column.is_none())
+        .map(|(header, _)| header.to_string())
+        .collect();
+
+    let mut records = Vec::new();
+    let mut errors = Vec::new();
+    for record in reader.records() {
+        if records.len() + errors.len() >= MAX_IMPORT_ROWS {
+            let error = format!("At most {} rows can be imported at once", MAX_IMPORT_ROWS);
+            return AppError::Validation(error).into_api_response();
+        }
+        match record {
+            Ok(record) => records.push(record),
+            Err(err) => errors.push(RowError {
+                line: err.position().map_or(0, |position| position.line()),
+                column: None,
+                message: format!("Invalid CSV row: {err}"),
+            }),
+        }
+    }
+    let rows = records.len() + errors.len();
+
+    let mut usernames: Vec<String> = [Column::CreatedBy, Column::AssignedTo]
+        .iter()
+        .filter_map(|wanted| columns.iter().position(|column| *column == Some(*wanted)))
+        .flat_map(|index| records.iter().filter_map(move |record| record.get(index)))
+        .map(|username| username.trim().to_string())
+        .filter(|username| !username.is_empty())
+        .collect();
+    usernames.sort_unstable();
+    usernames.dedup();
+    let users = match User::find_by_usernames(&mut conn, &usernames) {
+        Ok(users) => users,
+        Err(err) => return AppError::from(err).into_api_response(),
+    };
+    let context = RowContext {
+        user: &user,
+        role,
+        workflow: &workflow,
+        users,
+    };
+
+    let mut valid = Vec::with_capacity(records.len());
+    for record in &records {
+        let line = record.position().map_or(0, |position| position.line());
+        match context.check(line, &headers, &columns, record) {
+            Ok(row) => valid.push(row),
+            Err(row_errors) => errors.extend(row_errors),
+        }
+    }
+    if !errors.is_empty() {
+        errors.sort_by_key(|error| error.line);
+        return invalid_rows(payload.dry_run, rows, errors);
+    }
+
+    // WIP limits depend on the rows inserted before, so even a dry run
+    // inserts and then rolls back
+    let imported = conn.transaction(|conn| {
+        let mut created = Vec::with_capacity(valid.len());
+        let mut rejected = Vec::new();
+        for row in &valid {
+            if let Err(rejection) = check_wip_limit::<()>(conn, project_id, &row.status, role)? {
+                rejected.push(RowError {
+                    line: row.line,
+                    column: None,
+                    message: rejection_message(rejection),
+                });
+                continue;
+            }
+            let issue = Issue::create(
+                conn,
+                &NewIssue {
+                    project_id,
+                    title: &row.title,
+                    description: &row.description,
+                    created_by_id: row.created_by_id,
+                    assigned_to_id: row.assigned_to_id,
+                    status: &row.status,
+                    is_open: row.is_open,
+                    priority: row.priority.level(),
+                    severity: row.severity.as_str(),
+                    start_date: row.start_date,
+                    due_date: row.due_date,
+                    parent_id: None,
+                    original_estimate_minutes: row.original_estimate_minutes,
+                    milestone_id: None,
+                    sprint_id: None,
+                    estimate_points: row.estimate_points,
+                },
+            )?;
+            let response = IssueResponse::load(conn, issue)?;
+            queue_event(conn, project_id, EVENT_ISSUE_CREATED, user.id, &response)?;
+            created.push(response);
+        }
+
+        if !rejected.is_empty() {
+            return Err(Abort::Rejected(rejected));
+        }
+        if payload.dry_run {
+            return Err(Abort::DryRun);
+        }
+        Ok(created)
+    });
+
+    let issues = match imported {
+        Ok(issues) => issues,
+        Err(Abort::DryRun) => Vec::new(),
+        Err(Abort::Rejected(errors)) => return invalid_rows(payload.dry_run, rows, errors),
+        Err(Abort::Database(err)) => {
+            return internal_server_error("Failed to import issues", &err);
+        }
+    };
+    for issue in &issues {
+        state
+            .events
+            .publish(project_id, EVENT_ISSUE_CREATED, user.id, issue);
+    }
+
+    let status = if payload.dry_run {
+        StatusCode::OK
+    } else {
+        StatusCode::CREATED
+    };
+    let response = ImportResponse {
+        dry_run: payload.dry_run,
+        rows,
+        issues,
+        ignored_columns,
+    };
+    (status, Json(ApiResponse::Success(response)))
+}
@@ -0,0 +1,276 @@
+use crate::auth::{require_project_role, AuthUser};
+use crate::error::internal_server_error;
+use crate::handlers::api_response::ApiResponse;
+use crate::models::{is_valid_color, Label, NewLabel, Project, ProjectRole, UpdateLabel};
+use crate::routes::router::AppState;
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use chrono::{DateTime, Utc};
+use diesel::result::{DatabaseErrorKind, Error as DieselError};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+#[derive(Debug, Deserialize)]
+pub struct CreateLabelRequest {
+    pub name: String,
+    pub color: Option<String>,
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct LabelResponse {
+    pub id: i32,
+    pub project_id: i32,
+    pub name: String,
+    pub color: String,
+    pub description: String,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<Label> for LabelResponse {
+    fn from(label: Label) -> Self {
+        LabelResponse {
+            id: label.id,
+            project_id: label.project_id,
+            name: label.name,
+            color: label.color,
+            description: label.description,
+            created_at: label.created_at,
+        }
+    }
+}
+
+/// Compact form embedded in issue responses.
+#[derive(Debug, Clone, Serialize)]
+pub struct LabelSummary {
+    pub id: i32,
+    pub name: String,
+    pub color: String,
+}
+
+impl From<&Label> for LabelSummary {
+    fn from(label: &Label) -> Self {
+        LabelSummary {
+            id: label.id,
+            name: label.name.clone(),
+            color: label.color.clone(),
+        }
+    }
+}
+
+fn validate_label(name: Option<&str>, color: Option<&str>) -> Result<(), String> {
+    if let Some(name) = name {
+        if name.is_empty() || name.len() > 50 || name.contains(',') {
+            return Err(
+                "Label names must be 1 to 50 characters and cannot contain commas".to_string(),
+            );
+        }
+    }
+    if let Some(color) = color {
+        if !is_valid_color(color) {
+            return Err(format!("Invalid color '{}'. Expected #rrggbb", color));
+        }
+    }
+    Ok(())
+}
+
+fn label_conflict<T>(project_id: i32) -> (StatusCode, Json<ApiResponse<T>>) {
+    (
+        StatusCode::CONFLICT,
+        Json(ApiResponse::Error {
+            error: format!("Project {} already has a label with that name", project_id),
+        }),
+    )
+}
+
+pub async fn get_labels(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
+    Path(project_id): Path<i32>,
+) -> impl IntoResponse {
+    let mut conn = match state.pool.get() {
+        Ok(conn) => conn,
+        Err(err) => {
+            return internal_server_error::<Vec<LabelResponse>>("Database connection failed", &err);
+        }
+    };
+
+    if let Err(err) = require_project_role(&mut conn, &user, project_id, ProjectRole::Viewer) {
+        return err.into_api_response();
+    }
+
+    match Label::list(&mut conn, project_id) {
+        Ok(labels) => {
+            let response: Vec<LabelResponse> =
+                labels.into_iter().map(LabelResponse::from).collect();
+            (StatusCode::OK, Json(ApiResponse::Success(response)))
+        }
+        Err(err) => internal_server_error::<Vec<LabelResponse>>("Failed to load labels", &err),
+    }
+}
+
+pub async fn create_label(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
+    Path(project_id): Path<i32>,
+    Json(payload): Json<CreateLabelRequest>,
+) -> impl IntoResponse {
+    let mut conn = match state.pool.get() {
+        Ok(conn) => conn,
+        Err(err) => {
+            return internal_server_error::<LabelResponse>("Database connection failed", &err);
+        }
+    };
+
+    if let Err(err) = require_project_role(&mut conn, &user, project_id, ProjectRole::Maintainer) {
+        return err.into_api_response();
+    }
+
+    let name = payload.name.trim();
+    if let Err(message) = validate_label(Some(name), payload.color.as_deref()) {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::Error { error: message }),
+        );
+    }
+
+    match Project::find_by_id(&mut conn, project_id) {
+        Ok(Some(_)) => {}
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ApiResponse::Error {
+                    error: format!("Project with ID {} not found", project_id),
+                }),
+            )
+        }
+        Err(err) => return internal_server_error::<LabelResponse>("Database error", &err),
+    }
+
+    let new_label = NewLabel {
+        project_id,
+        name,
+        color: payload.color.as_deref(),
+        description: payload.description.as_deref().unwrap_or_default(),
+    };
+
+    match Label::create(&mut conn, &new_label) {
+        Ok(label) => (
+            StatusCode::CREATED,
+            Json(ApiResponse::Success(LabelResponse::from(label))),
+        ),
+        Err(DieselError::DatabaseError(DatabaseErrorKind::UniqueViolation, _)) => {
+            label_conflict(project_id)
+        }
+        Err(err) => internal_server_error::<LabelResponse>("Failed to create label", &err),
+    }
+}
+
+pub async fn update_label(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
+    Path((project_id, label_id)): Path<(i32, i32)>,
+    Json(mut payload): Json<UpdateLabel>,
+) -> impl IntoResponse {
+    let mut conn = match state.pool.get() {
+        Ok(conn) => conn,
+        Err(err) => {
+            return internal_server_error::<LabelResponse>("Database connection failed", &err);
+        }
+    };
+
+    if let Err(err) = require_project_role(&mut conn, &user, project_id, ProjectRole::Maintainer) {
+        return err.into_api_response();
+    }
+
+    payload.name = payload.name.map(|name| name.trim().to_string());
+    if payload.name.is_none() && payload.color.is_none() && payload.description.is_none() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::Error {
+                error: "At least one field must be provided for update.".to_string(),
+            }),
+        );
+    }
+    if let Err(message) = validate_label(payload.name.as_deref(), payload.color.as_deref()) {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::Error { error: message }),
+        );
+    }
+
+    match Label::find_by_id(&mut conn, label_id) {
+        Ok(Some(label)) if label.project_id == project_id => {
+            match Label::update(&mut conn, label_id, &payload) {
+                Ok(label) => (
+                    StatusCode::OK,
+                    Json(ApiResponse::Success(LabelResponse::from(label))),
+                ),
+                Err(DieselError::DatabaseError(DatabaseErrorKind::UniqueViolation, _)) => {
+                    label_conflict(project_id)
+                }
+                Err(err) => internal_server_error::<LabelResponse>("Failed to update label", &err),
+            }
+        }
+        Ok(_) => (
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse::Error {
+                error: format!("Label {} not found in project {}", label_id, project_id),
+            }),
+        ),
+        Err(err) => internal_server_error::<LabelResponse>("Database error", &err),
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct DeleteResponse {
+    success: bool,
+    message: String,
+}
+
+pub async fn delete_label(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
+    Path((project_id, label_id)): Path<(i32, i32)>,
+) -> impl IntoResponse {
+    let mut conn = match state.pool.get() {
+        Ok(conn) => conn,
+        Err(err) => {
+            return internal_server_error::<DeleteResponse>("Database connection failed", &err);
+        }
+    };
+
+    if let Err(err) = require_project_role(&mut conn, &user, project_id, ProjectRole::Maintainer) {
+        return err.into_api_response();
+    }
+
+    match Label::find_by_id(&mut conn, label_id) {
+        Ok(Some(label)) if label.project_id == project_id => {
+            match Label::delete(&mut conn, label_id) {
+                Ok(_) => {
+                    tracing::info!("Label {} deleted from project {}", label_id, project_id);
+                    (
+                        StatusCode::OK,
+                        Json(ApiResponse::Success(DeleteResponse {
+                            success: true,
+                            message: format!("Label {} successfully deleted", label_id),
+                        })),
+                    )
+                }
+                Err(err) => internal_server_error::<DeleteResponse>("Failed to delete label", &err),
+            }
+        }
+        Ok(_) => (
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse::Error {
+                error: format!("Label {} not found in project {}", label_id, project_id),
+            }),
+        ),
+        Err(err) => internal_server_error::<DeleteResponse>("Database error", &err),
+    }
+}
@@ -0,0 +1,378 @@
+use crate::auth::{require_project_role, AuthUser};
+use crate::error::{internal_server_error, AppError};
+use crate::handlers::api_response::ApiResponse;
+use crate::handlers::comment::find_issue;
+use crate::models::{Issue, IssueLink, LinkType, NewIssueLink, Project, ProjectRole};
+use crate::routes::router::AppState;
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use diesel::result::{DatabaseErrorKind, Error as DieselError};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::HashMap;
+use std::sync::Arc;
+
+#[derive(Debug, Deserialize)]
+pub struct CreateLinkRequest {
+    /// Relation from this issue to the target, e.g. `blocks` or `blocked_by`.
+    pub link_type: LinkType,
+    pub target_id: i32,
+}
+
+/// The issue at the other end of a link.
+#[derive(Debug, Serialize)]
+pub struct LinkedIssue {
+    pub id: i32,
+    pub key: String,
+    pub title: String,
+    pub status: String,
+    pub is_open: bool,
+}
+
+impl LinkedIssue {
+    fn load_many(conn: &mut PgConnection, issue_ids: &[i32]) -> QueryResult<HashMap<i32, Self>> {
+        let issues = Issue::find_many(conn, issue_ids)?;
+        let mut project_ids: Vec<i32> = issues.iter().map(|issue| issue.project_id).collect();
+        project_ids.sort_unstable();
+        project_ids.dedup();
+        let keys = Project::keys_for(conn, &project_ids)?;
+
+        issues
+            .into_iter()
+            .map(|issue| {
+                let key = keys
+                    .get(&issue.project_id)
+                    .map(|key| format!("{}-{}", key, issue.number))
+                    .ok_or(DieselError::NotFound)?;
+                let linked = LinkedIssue {
+                    id: issue.id,
+                    key,
+                    title: issue.title,
+                    status: issue.status,
+                    is_open: issue.is_open,
+                };
+                Ok((linked.id, linked))
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct LinkResponse {
+    pub id: i32,
+    /// Relation from the requested issue to `issue`.
+    pub link_type: LinkType,
+    pub issue: LinkedIssue,
+    pub created_at: DateTime<Utc>,
+}
+
+fn link_responses(
+    conn: &mut PgConnection,
+    issue_id: i32,
+    links: Vec<IssueLink>,
+) -> QueryResult<Vec<LinkResponse>> {
+    let other_ids: Vec<i32> = links
+        .iter()
+        .map(|link| link.view_from(issue_id).1)
+        .collect();
+    let mut issues = LinkedIssue::load_many(conn, &other_ids)?;
+
+    links
+        .into_iter()
+        .map(|link| {
+            let (link_type, other_id) = link.view_from(issue_id);
+            let issue = issues.remove(&other_id).ok_or(DieselError::NotFound)?;
+            Ok(LinkResponse {
+                id: link.id,
+                link_type,
+                issue,
+                created_at: link.created_at,
+            })
+        })
+        .collect()
+}
+
+#[derive(Debug, Serialize)]
+pub struct DependencyEdge {
+    pub blocker: i32,
+    pub blocked: i32,
+}
+
+/// Everything an issue transitively waits on and holds up.
+#[derive(Debug, Serialize)]
+pub struct DependencyGraph {
+    pub issue_id: i32,
+    /// Issues that block this one, directly or through other issues.
+    pub blocked_by: Vec<LinkedIssue>,
+    /// Issues this one blocks, directly or through other issues.
+    pub blocking: Vec<LinkedIssue>,
+    pub edges: Vec<DependencyEdge>,
+}
+
+/// Loads `issue_id` and checks the user may change its links: reporters
+/// only on issues they filed.
+fn editable_issue(
+    conn: &mut PgConnection,
+    user_id: i32,
+    role: ProjectRole,
+    project_id: i32,
+    issue_id: i32,
+) -> Result<Issue, AppError> {
+    let issue = find_issue(conn, project_id, issue_id)?;
+    if role < ProjectRole::Developer && issue.created_by_id != user_id {
+        return Err(AppError::Forbidden(
+            "Reporters can only link their own issues".to_string(),
+        ));
+    }
+    Ok(issue)
+}
+
+pub async fn get_links(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
+    Path((project_id, issue_id)): Path<(i32, i32)>,
+) -> impl IntoResponse {
+    let mut conn = match state.pool.get() {
+        Ok(conn) => conn,
+        Err(err) => {
+            return internal_server_error::<Vec<LinkResponse>>("Database connection failed", &err);
+        }
+    };
+
+    if let Err(err) = require_project_role(&mut conn, &user, project_id, ProjectRole::Viewer) {
+        return err.into_api_response();
+    }
+    if let Err(err) = find_issue(&mut conn, project_id, issue_id) {
+        return err.into_api_response();
+    }
+
+    match IssueLink::for_issue(&mut conn, issue_id)
+        .and_then(|links| link_responses(&mut conn, issue_id, links))
+    {
+        Ok(links) => (StatusCode::OK, Json(ApiResponse::Success(links))),
+        Err(err) => internal_server_error::<Vec<LinkResponse>>("Failed to load links", &err),
+    }
+}
+
+pub async fn create_link(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
+    Path((project_id, issue_id)): Path<(i32, i32)>,
+    Json(payload): Json<CreateLinkRequest>,
+) -> impl IntoResponse {
+    let mut conn = match state.pool.get() {
+        Ok(conn) => conn,
+        Err(err) => {
+            return internal_server_error::<LinkResponse>("Database connection failed", &err);
+        }
+    };
+
+    let role = match require_project_role(&mut conn, &user, project_id, ProjectRole::Reporter) {
+        Ok(role) => role,
+        Err(err) => return err.into_api_response(),
+    };
+    if let Err(err) = editable_issue(&mut conn, user.id, role, project_id, issue_id) {
+        return err.into_api_response();
+    }
+
+    let target_id = payload.target_id;
+    if target_id == issue_id {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::Error {
+                error: "An issue cannot be linked to itself".to_string(),
+            }),
+        );
+    }
+    match find_issue(&mut conn, project_id, target_id) {
+        Ok(_) => {}
+        Err(AppError::IssueNotFound(_)) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::Error {
+                    error: format!("Issue {} is not in this project", target_id),
+                }),
+            )
+        }
+        Err(err) => return err.into_api_response(),
+    }
+
+    // Store the forward direction of inverse relations
+    let (source_id, target_id, link_type) = if payload.link_type.is_stored() {
+        (issue_id, target_id, payload.link_type)
+    } else {
+        (target_id, issue_id, payload.link_type.inverse())
+    };
+
+    let existing = match IssueLink::between(&mut conn, source_id, target_id) {
+        Ok(existing) => existing,
+        Err(err) => return internal_server_error::<LinkResponse>("Failed to load links", &err),
+    };
+    // `relates_to` reads the same from both ends, so a reverse link counts
+    if existing
+        .iter()
+        .any(|link| link.view_from(source_id).0 == link_type)
+    {
+        return (
+            StatusCode::CONFLICT,
+            Json(ApiResponse::Error {
+                error: "The issues are already linked that way".to_string(),
+            }),
+        );
+    }
+
+    let new_link = NewIssueLink {
+        source_id,
+        target_id,
+        link_type: link_type.as_str(),
+        created_by_id: user.id,
+    };
+    let created = conn.transaction(|conn| {
+        if link_type == LinkType::Blocks {
+            IssueLink::lock_blocking_graph(conn)?;
+            // The target already blocking the source, however indirectly,
+            // would close a cycle
+            if let Some(path) = IssueLink::blocking_path(conn, target_id, source_id)? {
+                return Ok(Err(path));
+            }
+        }
+        IssueLink::create(conn, &new_link).map(Ok)
+    });
+
+    match created {
+        Ok(Ok(link)) => match link_responses(&mut conn, issue_id, vec![link]) {
+            Ok(mut links) => (
+                StatusCode::CREATED,
+                Json(ApiResponse::Success(links.remove(0))),
+            ),
+            Err(err) => internal_server_error::<LinkResponse>("Failed to load link", &err),
+        },
+        Ok(Err(path)) => (
+            StatusCode::UNPROCESSABLE_ENTITY,
+            Json(ApiResponse::DetailedError {
+                error: format!(
+                    "Issue {} already depends on issue {}; the link would create a cycle",
+                    source_id, target_id
+                ),
+                code: "dependency_cycle".to_string(),
+                details: json!({ "path": path }),
+            }),
+        ),
+        Err(DieselError::DatabaseError(DatabaseErrorKind::UniqueViolation, _)) => (
+            StatusCode::CONFLICT,
+            Json(ApiResponse::Error {
+                error: "The issues are already linked that way".to_string(),
+            }),
+        ),
+        Err(err) => internal_server_error::<LinkResponse>("Failed to create link", &err),
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct DeleteLinkResponse {
+    pub success: bool,
+    pub message: String,
+}
+
+pub async fn delete_link(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
+    Path((project_id, issue_id, link_id)): Path<(i32, i32, i32)>,
+) -> impl IntoResponse {
+    let mut conn = match state.pool.get() {
+        Ok(conn) => conn,
+        Err(err) => {
+            return internal_server_error::<DeleteLinkResponse>("Database connection failed", &err);
+        }
+    };
+
+    let role = match require_project_role(&mut conn, &user, project_id, ProjectRole::Reporter) {
+        Ok(role) => role,
+        Err(err) => return err.into_api_response(),
+    };
+    if let Err(err) = editable_issue(&mut conn, user.id, role, project_id, issue_id) {
+        return err.into_api_response();
+    }
+
+    match IssueLink::find_by_id(&mut conn, link_id) {
+        Ok(Some(link)) if link.source_id == issue_id || link.target_id == issue_id => {}
+        Ok(_) => return AppError::LinkNotFound(link_id).into_api_response(),
+        Err(err) => return internal_server_error::<DeleteLinkResponse>("Database error", &err),
+    }
+
+    match IssueLink::delete(&mut conn, link_id) {
+        Ok(_) => (
+            StatusCode::OK,
+            Json(ApiResponse::Success(DeleteLinkResponse {
+                success: true,
+                message: format!("Link {} successfully deleted", link_id),
+            })),
+        ),
+        Err(err) => internal_server_error::<DeleteLinkResponse>("Failed to delete link", &err),
+    }
+}
+
+pub async fn get_dependencies(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
+    Path((project_id, issue_id)): Path<(i32, i32)>,
+) -> impl IntoResponse {
+    let mut conn = match state.pool.get() {
+        Ok(conn) => conn,
+        Err(err) => {
+            return internal_server_error::<DependencyGraph>("Database connection failed", &err);
+        }
+    };
+
+    if let Err(err) = require_project_role(&mut conn, &user, project_id, ProjectRole::Viewer) {
+        return err.into_api_response();
+    }
+    if let Err(err) = find_issue(&mut conn, project_id, issue_id) {
+        return err.into_api_response();
+    }
+
+    let graph = IssueLink::blocking_graph(&mut conn, issue_id, false).and_then(|upstream| {
+        let downstream = IssueLink::blocking_graph(&mut conn, issue_id, true)?;
+        let blocker_ids: Vec<i32> = upstream.iter().map(|link| link.source_id).collect();
+        let blocked_ids: Vec<i32> = downstream.iter().map(|link| link.target_id).collect();
+        let mut issues = LinkedIssue::load_many(
+            &mut conn,
+            &[blocker_ids.as_slice(), blocked_ids.as_slice()].concat(),
+        )?;
+
+        // Each issue is listed once, nearest first
+        let mut take = |ids: Vec<i32>| -> Vec<LinkedIssue> {
+            ids.into_iter()
+                .filter_map(|id| issues.remove(&id))
+                .collect()
+        };
+        let blocked_by = take(blocker_ids);
+        let blocking = take(blocked_ids);
+
+        Ok(DependencyGraph {
+            issue_id,
+            blocked_by,
+            blocking,
+            edges: upstream
+                .iter()
+                .chain(downstream.iter())
+                .map(|link| DependencyEdge {
+                    blocker: link.source_id,
+                    blocked: link.target_id,
+                })
+                .collect(),
+        })
+    });
+
+    match graph {
+        Ok(graph) => (StatusCode::OK, Json(ApiResponse::Success(graph))),
+        Err(err) => internal_server_error::<DependencyGraph>("Failed to load dependencies", &err),
+    }
+}
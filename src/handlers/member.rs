@@ -0,0 +1,175 @@
+use crate::auth::{require_project_role, AuthUser};
+use crate::error::internal_server_error;
+use crate::handlers::api_response::ApiResponse;
+use crate::handlers::user::UserSummary;
+use crate::models::{Project, ProjectMember, ProjectRole, User};
+use crate::routes::router::AppState;
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+#[derive(Debug, Deserialize)]
+pub struct SetMemberRequest {
+    pub role: ProjectRole,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MemberResponse {
+    pub user: UserSummary,
+    pub role: ProjectRole,
+    pub created_at: DateTime<Utc>,
+}
+
+impl MemberResponse {
+    fn new(member: ProjectMember, user: &User) -> Self {
+        MemberResponse {
+            user: UserSummary::from(user),
+            role: member.role(),
+            created_at: member.created_at,
+        }
+    }
+}
+
+pub async fn get_members(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
+    Path(project_id): Path<i32>,
+) -> impl IntoResponse {
+    let mut conn = match state.pool.get() {
+        Ok(conn) => conn,
+        Err(err) => {
+            return internal_server_error::<Vec<MemberResponse>>(
+                "Database connection failed",
+                &err,
+            );
+        }
+    };
+
+    if let Err(err) = require_project_role(&mut conn, &user, project_id, ProjectRole::Viewer) {
+        return err.into_api_response();
+    }
+
+    match ProjectMember::list(&mut conn, project_id) {
+        Ok(members) => {
+            let response: Vec<MemberResponse> = members
+                .into_iter()
+                .map(|(member, user)| MemberResponse::new(member, &user))
+                .collect();
+            (StatusCode::OK, Json(ApiResponse::Success(response)))
+        }
+        Err(err) => internal_server_error::<Vec<MemberResponse>>("Failed to load members", &err),
+    }
+}
+
+pub async fn set_member(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
+    Path((project_id, member_id)): Path<(i32, i32)>,
+    Json(payload): Json<SetMemberRequest>,
+) -> impl IntoResponse {
+    let mut conn = match state.pool.get() {
+        Ok(conn) => conn,
+        Err(err) => {
+            return internal_server_error::<MemberResponse>("Database connection failed", &err);
+        }
+    };
+
+    if let Err(err) = require_project_role(&mut conn, &user, project_id, ProjectRole::Admin) {
+        return err.into_api_response();
+    }
+
+    match Project::find_by_id(&mut conn, project_id) {
+        Ok(Some(_)) => {}
+        Ok(None) => {
+            return (
+                StatusCode::NOT_FOUND,
+                Json(ApiResponse::Error {
+                    error: format!("Project with ID {} not found", project_id),
+                }),
+            )
+        }
+        Err(err) => return internal_server_error::<MemberResponse>("Database error", &err),
+    }
+
+    let member_user = match User::find_active(&mut conn, member_id) {
+        Ok(Some(member_user)) => member_user,
+        Ok(None) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::Error {
+                    error: format!("No active user with ID {}", member_id),
+                }),
+            )
+        }
+        Err(err) => return internal_server_error::<MemberResponse>("Database error", &err),
+    };
+
+    match ProjectMember::upsert(&mut conn, project_id, member_id, payload.role) {
+        Ok(member) => {
+            tracing::info!(
+                "User {} is now {} of project {}",
+                member_id,
+                payload.role,
+                project_id
+            );
+            (
+                StatusCode::OK,
+                Json(ApiResponse::Success(MemberResponse::new(
+                    member,
+                    &member_user,
+                ))),
+            )
+        }
+        Err(err) => internal_server_error::<MemberResponse>("Failed to save member", &err),
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct DeleteResponse {
+    success: bool,
+    message: String,
+}
+
+pub async fn remove_member(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
+    Path((project_id, member_id)): Path<(i32, i32)>,
+) -> impl IntoResponse {
+    let mut conn = match state.pool.get() {
+        Ok(conn) => conn,
+        Err(err) => {
+            return internal_server_error::<DeleteResponse>("Database connection failed", &err);
+        }
+    };
+
+    if let Err(err) = require_project_role(&mut conn, &user, project_id, ProjectRole::Admin) {
+        return err.into_api_response();
+    }
+
+    match ProjectMember::remove(&mut conn, project_id, member_id) {
+        Ok(true) => (
+            StatusCode::OK,
+            Json(ApiResponse::Success(DeleteResponse {
+                success: true,
+                message: format!("User {} removed from project {}", member_id, project_id),
+            })),
+        ),
+        Ok(false) => (
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse::Error {
+                error: format!(
+                    "User {} is not a member of project {}",
+                    member_id, project_id
+                ),
+            }),
+        ),
+        Err(err) => internal_server_error::<DeleteResponse>("Failed to remove member", &err),
+    }
+}
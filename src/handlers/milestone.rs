@@ -0,0 +1,488 @@
+use crate::auth::{require_project_role, AuthUser};
+use crate::error::{internal_server_error, AppError};
+use crate::handlers::api_response::ApiResponse;
+use crate::handlers::issue::nullable;
+use crate::handlers::worklog::{TimeRangeParams, TimeSummary};
+use crate::models::{IssueFilter, Milestone, NewMilestone, Project, ProjectRole, UpdateMilestone};
+use crate::routes::router::AppState;
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use chrono::{DateTime, NaiveDate, Utc};
+use diesel::prelude::*;
+use diesel::result::{DatabaseErrorKind, Error as DieselError};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+#[derive(Debug, Deserialize)]
+pub struct CreateMilestoneRequest {
+    pub name: String,
+    pub description: Option<String>,
+    pub target_date: Option<NaiveDate>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateMilestoneRequest {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    /// `null` clears the date.
+    #[serde(default, deserialize_with = "nullable")]
+    pub target_date: Option<Option<NaiveDate>>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct ReleaseMilestoneRequest {
+    /// Move issues that are still open to another milestone.
+    #[serde(default)]
+    pub move_open_issues: bool,
+    /// Where to move them, implying `move_open_issues`. Defaults to the next
+    /// unreleased milestone by target date.
+    pub move_to: Option<i32>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MilestoneResponse {
+    pub id: i32,
+    pub project_id: i32,
+    pub name: String,
+    pub description: String,
+    pub target_date: Option<NaiveDate>,
+    pub released: bool,
+    pub released_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<Milestone> for MilestoneResponse {
+    fn from(milestone: Milestone) -> Self {
+        MilestoneResponse {
+            id: milestone.id,
+            project_id: milestone.project_id,
+            name: milestone.name,
+            description: milestone.description,
+            target_date: milestone.target_date,
+            released: milestone.released,
+            released_at: milestone.released_at,
+            created_at: milestone.created_at,
+        }
+    }
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct MilestoneProgress {
+    pub total: i64,
+    pub done: i64,
+    pub open: i64,
+    /// Share of the milestone's issues in a done state, 0 when it has none.
+    pub percent_done: f64,
+    pub open_by_status: BTreeMap<String, i64>,
+    /// Days until the target date; negative once it has passed.
+    pub days_remaining: Option<i64>,
+    /// Unreleased with open issues, and either past its target date or
+    /// further through its schedule than through its issues.
+    pub at_risk: bool,
+}
+
+impl MilestoneProgress {
+    fn from_counts(
+        milestone: &Milestone,
+        counts: Vec<(String, bool, i64)>,
+        today: NaiveDate,
+    ) -> Self {
+        let mut progress = MilestoneProgress::default();
+        for (status, is_open, count) in counts {
+            progress.total += count;
+            if is_open {
+                progress.open += count;
+                *progress.open_by_status.entry(status).or_insert(0) += count;
+            } else {
+                progress.done += count;
+            }
+        }
+        let done_share = if progress.total > 0 {
+            progress.done as f64 / progress.total as f64
+        } else {
+            0.0
+        };
+        progress.percent_done = (done_share * 1000.0).round() / 10.0;
+
+        if let (Some(target), false) = (milestone.target_date, milestone.released) {
+            progress.days_remaining = Some((target - today).num_days());
+            if progress.open > 0 {
+                // Schedule runs from the day the milestone was created
+                let start = milestone.created_at.date_naive();
+                let span = (target - start).num_days().max(1) as f64;
+                let elapsed = (today - start).num_days().max(0) as f64;
+                progress.at_risk = today > target || elapsed / span > done_share;
+            }
+        }
+        progress
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct MilestoneDetailResponse {
+    #[serde(flatten)]
+    pub milestone: MilestoneResponse,
+    pub progress: MilestoneProgress,
+    pub time_tracking: TimeSummary,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ReleaseResponse {
+    pub milestone: MilestoneResponse,
+    /// Milestone the open issues were moved to, if they were.
+    pub moved_to: Option<i32>,
+    pub moved_issues: Vec<i32>,
+}
+
+fn validate_name(name: &str) -> Result<(), String> {
+    if name.is_empty() || name.len() > 100 {
+        return Err("Milestone names must be 1 to 100 characters".to_string());
+    }
+    Ok(())
+}
+
+fn milestone_conflict<T>(project_id: i32) -> (StatusCode, Json<ApiResponse<T>>) {
+    (
+        StatusCode::CONFLICT,
+        Json(ApiResponse::Error {
+            error: format!(
+                "Project {} already has a milestone with that name",
+                project_id
+            ),
+        }),
+    )
+}
+
+fn find_milestone(
+    conn: &mut PgConnection,
+    project_id: i32,
+    milestone_id: i32,
+) -> Result<Milestone, AppError> {
+    match Milestone::find_by_id(conn, milestone_id)? {
+        Some(milestone) if milestone.project_id == project_id => Ok(milestone),
+        _ => Err(AppError::MilestoneNotFound(milestone_id)),
+    }
+}
+
+pub async fn get_milestones(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
+    Path(project_id): Path<i32>,
+) -> impl IntoResponse {
+    let mut conn = match state.pool.get() {
+        Ok(conn) => conn,
+        Err(err) => {
+            return internal_server_error::<Vec<MilestoneResponse>>(
+                "Database connection failed",
+                &err,
+            );
+        }
+    };
+
+    if let Err(err) = require_project_role(&mut conn, &user, project_id, ProjectRole::Viewer) {
+        return err.into_api_response();
+    }
+
+    match Milestone::list(&mut conn, project_id) {
+        Ok(milestones) => {
+            let response: Vec<MilestoneResponse> = milestones
+                .into_iter()
+                .map(MilestoneResponse::from)
+                .collect();
+            (StatusCode::OK, Json(ApiResponse::Success(response)))
+        }
+        Err(err) => {
+            internal_server_error::<Vec<MilestoneResponse>>("Failed to load milestones", &err)
+        }
+    }
+}
+
+pub async fn create_milestone(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
+    Path(project_id): Path<i32>,
+    Json(payload): Json<CreateMilestoneRequest>,
+) -> impl IntoResponse {
+    let mut conn = match state.pool.get() {
+        Ok(conn) => conn,
+        Err(err) => {
+            return internal_server_error::<MilestoneResponse>("Database connection failed", &err);
+        }
+    };
+
+    if let Err(err) = require_project_role(&mut conn, &user, project_id, ProjectRole::Maintainer) {
+        return err.into_api_response();
+    }
+
+    let name = payload.name.trim();
+    if let Err(error) = validate_name(name) {
+        return (StatusCode::BAD_REQUEST, Json(ApiResponse::Error { error }));
+    }
+
+    match Project::find_by_id(&mut conn, project_id) {
+        Ok(Some(_)) => {}
+        Ok(None) => {
+            return AppError::ProjectNotFound(project_id.to_string()).into_api_response();
+        }
+        Err(err) => return internal_server_error::<MilestoneResponse>("Database error", &err),
+    }
+
+    let new_milestone = NewMilestone {
+        project_id,
+        name,
+        description: payload.description.as_deref().unwrap_or_default(),
+        target_date: payload.target_date,
+    };
+
+    match Milestone::create(&mut conn, &new_milestone) {
+        Ok(milestone) => (
+            StatusCode::CREATED,
+            Json(ApiResponse::Success(MilestoneResponse::from(milestone))),
+        ),
+        Err(DieselError::DatabaseError(DatabaseErrorKind::UniqueViolation, _)) => {
+            milestone_conflict(project_id)
+        }
+        Err(err) => internal_server_error::<MilestoneResponse>("Failed to create milestone", &err),
+    }
+}
+
+pub async fn get_milestone(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
+    Path((project_id, milestone_id)): Path<(i32, i32)>,
+    Query(range): Query<TimeRangeParams>,
+) -> impl IntoResponse {
+    let mut conn = match state.pool.get() {
+        Ok(conn) => conn,
+        Err(err) => {
+            return internal_server_error::<MilestoneDetailResponse>(
+                "Database connection failed",
+                &err,
+            );
+        }
+    };
+
+    if let Err(err) = require_project_role(&mut conn, &user, project_id, ProjectRole::Viewer) {
+        return err.into_api_response();
+    }
+    if let Err(error) = range.validate() {
+        return (StatusCode::BAD_REQUEST, Json(ApiResponse::Error { error }));
+    }
+    let milestone = match find_milestone(&mut conn, project_id, milestone_id) {
+        Ok(milestone) => milestone,
+        Err(err) => return err.into_api_response(),
+    };
+
+    let filter = IssueFilter {
+        milestone_id: Some(milestone_id),
+        ..IssueFilter::default()
+    };
+    match Milestone::status_counts(&mut conn, milestone_id)
+        .and_then(|counts| Ok((counts, TimeSummary::load(&mut conn, &filter, &range)?)))
+    {
+        Ok((counts, time_tracking)) => {
+            let today = Utc::now().date_naive();
+            let progress = MilestoneProgress::from_counts(&milestone, counts, today);
+            let response = MilestoneDetailResponse {
+                milestone: MilestoneResponse::from(milestone),
+                progress,
+                time_tracking,
+            };
+            (StatusCode::OK, Json(ApiResponse::Success(response)))
+        }
+        Err(err) => internal_server_error::<MilestoneDetailResponse>(
+            "Failed to count milestone issues",
+            &err,
+        ),
+    }
+}
+
+pub async fn update_milestone(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
+    Path((project_id, milestone_id)): Path<(i32, i32)>,
+    Json(payload): Json<UpdateMilestoneRequest>,
+) -> impl IntoResponse {
+    let mut conn = match state.pool.get() {
+        Ok(conn) => conn,
+        Err(err) => {
+            return internal_server_error::<MilestoneResponse>("Database connection failed", &err);
+        }
+    };
+
+    if let Err(err) = require_project_role(&mut conn, &user, project_id, ProjectRole::Maintainer) {
+        return err.into_api_response();
+    }
+
+    let changes = UpdateMilestone {
+        name: payload.name.map(|name| name.trim().to_string()),
+        description: payload.description,
+        target_date: payload.target_date,
+    };
+    if changes.is_empty() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::Error {
+                error: "At least one field must be provided for update.".to_string(),
+            }),
+        );
+    }
+    if let Some(Err(error)) = changes.name.as_deref().map(validate_name) {
+        return (StatusCode::BAD_REQUEST, Json(ApiResponse::Error { error }));
+    }
+
+    if let Err(err) = find_milestone(&mut conn, project_id, milestone_id) {
+        return err.into_api_response();
+    }
+
+    match Milestone::update(&mut conn, milestone_id, &changes) {
+        Ok(milestone) => (
+            StatusCode::OK,
+            Json(ApiResponse::Success(MilestoneResponse::from(milestone))),
+        ),
+        Err(DieselError::DatabaseError(DatabaseErrorKind::UniqueViolation, _)) => {
+            milestone_conflict(project_id)
+        }
+        Err(err) => internal_server_error::<MilestoneResponse>("Failed to update milestone", &err),
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct DeleteResponse {
+    success: bool,
+    message: String,
+}
+
+pub async fn delete_milestone(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
+    Path((project_id, milestone_id)): Path<(i32, i32)>,
+) -> impl IntoResponse {
+    let mut conn = match state.pool.get() {
+        Ok(conn) => conn,
+        Err(err) => {
+            return internal_server_error::<DeleteResponse>("Database connection failed", &err);
+        }
+    };
+
+    if let Err(err) = require_project_role(&mut conn, &user, project_id, ProjectRole::Maintainer) {
+        return err.into_api_response();
+    }
+    if let Err(err) = find_milestone(&mut conn, project_id, milestone_id) {
+        return err.into_api_response();
+    }
+
+    match Milestone::delete(&mut conn, milestone_id) {
+        Ok(_) => {
+            tracing::info!(
+                "Milestone {} deleted from project {}",
+                milestone_id,
+                project_id
+            );
+            (
+                StatusCode::OK,
+                Json(ApiResponse::Success(DeleteResponse {
+                    success: true,
+                    message: format!("Milestone {} successfully deleted", milestone_id),
+                })),
+            )
+        }
+        Err(err) => internal_server_error::<DeleteResponse>("Failed to delete milestone", &err),
+    }
+}
+
+fn already_released<T>(milestone_id: i32) -> (StatusCode, Json<ApiResponse<T>>) {
+    (
+        StatusCode::CONFLICT,
+        Json(ApiResponse::Error {
+            error: format!("Milestone {} is already released", milestone_id),
+        }),
+    )
+}
+
+/// Releases the milestone. The body is optional; without one, open issues
+/// stay where they are.
+pub async fn release_milestone(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
+    Path((project_id, milestone_id)): Path<(i32, i32)>,
+    payload: Option<Json<ReleaseMilestoneRequest>>,
+) -> impl IntoResponse {
+    let mut conn = match state.pool.get() {
+        Ok(conn) => conn,
+        Err(err) => {
+            return internal_server_error::<ReleaseResponse>("Database connection failed", &err);
+        }
+    };
+
+    if let Err(err) = require_project_role(&mut conn, &user, project_id, ProjectRole::Maintainer) {
+        return err.into_api_response();
+    }
+    let milestone = match find_milestone(&mut conn, project_id, milestone_id) {
+        Ok(milestone) => milestone,
+        Err(err) => return err.into_api_response(),
+    };
+    if milestone.released {
+        return already_released(milestone_id);
+    }
+
+    let Json(payload) = payload.unwrap_or_default();
+    let move_to = match payload.move_to {
+        Some(target_id) => match find_milestone(&mut conn, project_id, target_id) {
+            Ok(target) if target.id != milestone_id && !target.released => Some(target.id),
+            Ok(_) | Err(AppError::MilestoneNotFound(_)) => {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Json(ApiResponse::Error {
+                        error: format!(
+                            "Milestone {} is not another unreleased milestone of this project",
+                            target_id
+                        ),
+                    }),
+                )
+            }
+            Err(err) => return err.into_api_response(),
+        },
+        None if payload.move_open_issues => match milestone.next(&mut conn) {
+            Ok(Some(next)) => Some(next.id),
+            Ok(None) => {
+                return (
+                    StatusCode::CONFLICT,
+                    Json(ApiResponse::Error {
+                        error: "There is no unreleased milestone to move open issues to"
+                            .to_string(),
+                    }),
+                )
+            }
+            Err(err) => return internal_server_error::<ReleaseResponse>("Database error", &err),
+        },
+        None => None,
+    };
+
+    match milestone.release(&mut conn, move_to, user.id) {
+        Ok((released, moved_issues)) => {
+            tracing::info!(
+                "Milestone {} released, {} open issues moved",
+                milestone_id,
+                moved_issues.len()
+            );
+            (
+                StatusCode::OK,
+                Json(ApiResponse::Success(ReleaseResponse {
+                    milestone: MilestoneResponse::from(released),
+                    moved_to: move_to,
+                    moved_issues,
+                })),
+            )
+        }
+        // Released concurrently
+        Err(DieselError::NotFound) => already_released(milestone_id),
+        Err(err) => internal_server_error::<ReleaseResponse>("Failed to release milestone", &err),
+    }
+}
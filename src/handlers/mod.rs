@@ -1,3 +1,21 @@
 pub mod api_response;
+pub mod board;
+pub mod comment;
+pub mod events;
+pub mod hierarchy;
+pub mod history;
 pub mod issue;
+pub mod issue_csv;
+pub mod label;
+pub mod link;
+pub mod member;
+pub mod milestone;
+pub mod pagination;
 pub mod project;
+pub mod socket;
+pub mod sprint;
+pub mod token;
+pub mod user;
+pub mod webhook;
+pub mod workflow;
+pub mod worklog;
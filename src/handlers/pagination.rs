@@ -0,0 +1,107 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use serde::{Deserialize, Serialize};
+
+pub const DEFAULT_PAGE_LIMIT: i64 = 20;
+pub const MAX_PAGE_LIMIT: i64 = 100;
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SortOrder {
+    #[default]
+    Asc,
+    Desc,
+}
+
+impl SortOrder {
+    pub fn is_descending(self) -> bool {
+        self == SortOrder::Desc
+    }
+}
+
+/// Position of the last row of a page: the value of the sort column plus the
+/// row id as a tie-breaker. Clients only ever see it as an opaque string.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Cursor {
+    pub value: String,
+    pub id: i32,
+}
+
+impl Cursor {
+    pub fn new(value: impl Into<String>, id: i32) -> Self {
+        Self {
+            value: value.into(),
+            id,
+        }
+    }
+
+    pub fn encode(&self) -> String {
+        let json = serde_json::to_vec(self).expect("cursor serialization cannot fail");
+        URL_SAFE_NO_PAD.encode(json)
+    }
+
+    pub fn decode(raw: &str) -> Option<Self> {
+        let bytes = URL_SAFE_NO_PAD.decode(raw).ok()?;
+        serde_json::from_slice(&bytes).ok()
+    }
+}
+
+#[derive(Debug)]
+pub struct PageRequest {
+    pub limit: i64,
+    pub after: Option<Cursor>,
+}
+
+impl PageRequest {
+    pub fn from_query(limit: Option<i64>, after: Option<&str>) -> Result<Self, String> {
+        let limit = limit.unwrap_or(DEFAULT_PAGE_LIMIT);
+        if !(1..=MAX_PAGE_LIMIT).contains(&limit) {
+            return Err(format!("limit must be between 1 and {}", MAX_PAGE_LIMIT));
+        }
+
+        let after = match after {
+            Some(raw) => Some(Cursor::decode(raw).ok_or_else(|| "Invalid cursor".to_string())?),
+            None => None,
+        };
+
+        Ok(Self { limit, after })
+    }
+
+    /// Rows to ask the database for: one more than the page size, so we can
+    /// tell whether another page follows without a second query.
+    pub fn fetch_limit(&self) -> i64 {
+        self.limit + 1
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    pub next_cursor: Option<String>,
+    pub total: i64,
+}
+
+impl<T> Page<T> {
+    /// Builds a page from rows fetched with `PageRequest::fetch_limit`.
+    pub fn from_rows<R>(
+        mut rows: Vec<R>,
+        request: &PageRequest,
+        total: i64,
+        cursor_of: impl Fn(&R) -> Cursor,
+        to_item: impl FnMut(R) -> T,
+    ) -> Self {
+        let has_more = rows.len() as i64 > request.limit;
+        rows.truncate(request.limit as usize);
+
+        let next_cursor = if has_more {
+            rows.last().map(|row| cursor_of(row).encode())
+        } else {
+            None
+        };
+
+        Self {
+            items: rows.into_iter().map(to_item).collect(),
+            next_cursor,
+            total,
+        }
+    }
+}
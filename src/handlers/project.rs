@@ -1,34 +1,130 @@
+use crate::auth::{require_project_role, AuthUser};
 use crate::error::internal_server_error;
+use crate::event_bus::EVENT_PROJECT_DELETED;
 use crate::handlers::api_response::ApiResponse;
-use crate::models::Project;
-use crate::models::UpdateProject;
+use crate::handlers::pagination::{Cursor, Page, PageRequest, SortOrder};
+use crate::handlers::webhook::queue_event;
+use crate::handlers::worklog::{TimeRangeParams, TimeSummary};
+use crate::models::{Issue, IssueFilter, Priority, Project, ProjectMember, ProjectRole};
+use crate::models::{ProjectKeyset, ProjectListQuery, ProjectSortField};
+use crate::models::{UpdateProject, EVENT_PROJECT_UPDATED};
 use crate::routes::router::AppState;
 
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::StatusCode,
     response::IntoResponse,
     Json,
 };
 
+use chrono::{DateTime, Utc};
+use diesel::result::{DatabaseErrorKind, Error as DieselError};
+use diesel::Connection;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::sync::Arc;
 
 #[derive(Debug, Deserialize)]
 pub struct CreateProject {
     pub name: Option<String>,
     pub description: Option<String>,
+    /// Derived from the name when omitted.
+    pub key: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
 pub struct ProjectResponse {
     pub id: i32,
+    pub key: String,
     pub name: String,
     pub description: String,
+    pub max_issue_depth: i16,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<Project> for ProjectResponse {
+    fn from(project: Project) -> Self {
+        ProjectResponse {
+            id: project.id,
+            key: project.key,
+            name: project.name,
+            description: project.description,
+            max_issue_depth: project.max_issue_depth,
+            created_at: project.created_at,
+            updated_at: project.updated_at,
+        }
+    }
+}
+
+fn normalize_key<T>(key: &str) -> Result<String, (StatusCode, Json<ApiResponse<T>>)> {
+    Project::normalize_key(key).ok_or_else(|| {
+        (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::Error {
+                error: format!(
+                    "Invalid project key '{}'. Keys are 2-10 letters or digits, starting with a letter",
+                    key
+                ),
+            }),
+        )
+    })
+}
+
+fn key_conflict<T>() -> (StatusCode, Json<ApiResponse<T>>) {
+    (
+        StatusCode::CONFLICT,
+        Json(ApiResponse::Error {
+            error: "Project key is already in use".to_string(),
+        }),
+    )
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct IssueCountSummary {
+    pub total: i64,
+    pub open: i64,
+    pub closed: i64,
+    pub by_status: BTreeMap<String, i64>,
+    /// Open issues per priority; every priority is listed.
+    pub open_by_priority: BTreeMap<String, i64>,
+}
+
+impl IssueCountSummary {
+    fn from_counts(counts: Vec<(String, bool, i64)>, priority_counts: Vec<(i16, i64)>) -> Self {
+        let mut summary = IssueCountSummary::default();
+        for priority in Priority::ALL {
+            summary.open_by_priority.insert(priority.to_string(), 0);
+        }
+        for (level, count) in priority_counts {
+            if let Some(priority) = Priority::from_level(level) {
+                summary.open_by_priority.insert(priority.to_string(), count);
+            }
+        }
+        for (status, is_open, count) in counts {
+            summary.total += count;
+            if is_open {
+                summary.open += count;
+            } else {
+                summary.closed += count;
+            }
+            *summary.by_status.entry(status).or_insert(0) += count;
+        }
+        summary
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct ProjectDetailResponse {
+    #[serde(flatten)]
+    pub project: ProjectResponse,
+    pub issue_counts: IssueCountSummary,
+    pub time_tracking: TimeSummary,
 }
 
 pub async fn create_project(
     State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
     Json(payload): Json<CreateProject>,
 ) -> impl IntoResponse {
     let mut conn = match state.pool.get() {
@@ -47,54 +143,197 @@ pub async fn create_project(
         );
     }
 
+    let key = match payload.key.as_deref().map(normalize_key).transpose() {
+        Ok(key) => key,
+        Err(response) => return response,
+    };
     let name = payload.name.unwrap();
     let description = payload.description.unwrap_or_default();
 
-    match Project::create(&mut conn, &name, &description) {
+    // The creator administers the new project
+    let created = conn.transaction(|conn| {
+        let project = Project::create(conn, &name, &description, key.as_deref())?;
+        ProjectMember::upsert(conn, project.id, user.id, ProjectRole::Admin)?;
+        Ok::<_, diesel::result::Error>(project)
+    });
+
+    match created {
         Ok(project) => {
-            let response = ApiResponse::Success(ProjectResponse {
-                id: project.id,
-                name: project.name,
-                description: project.description,
-            });
+            let response = ApiResponse::Success(ProjectResponse::from(project));
             (StatusCode::CREATED, Json(response))
         }
-        Err(err) => {
-            return internal_server_error::<ProjectResponse>("Failed to create project", &err);
-        }
+        Err(DieselError::DatabaseError(DatabaseErrorKind::UniqueViolation, _)) => key_conflict(),
+        Err(err) => internal_server_error::<ProjectResponse>("Failed to create project", &err),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ProjectListParams {
+    pub limit: Option<i64>,
+    pub after: Option<String>,
+    pub sort: Option<ProjectSortField>,
+    pub order: Option<SortOrder>,
+    pub name: Option<String>,
+}
+
+fn project_cursor(sort: ProjectSortField, project: &Project) -> Cursor {
+    match sort {
+        ProjectSortField::Name => Cursor::new(project.name.clone(), project.id),
+        ProjectSortField::CreatedAt => Cursor::new(project.created_at.to_rfc3339(), project.id),
+        ProjectSortField::UpdatedAt => Cursor::new(project.updated_at.to_rfc3339(), project.id),
     }
 }
 
+fn project_keyset(sort: ProjectSortField, cursor: &Cursor) -> Option<ProjectKeyset> {
+    let timestamp = || {
+        DateTime::parse_from_rfc3339(&cursor.value)
+            .ok()
+            .map(|ts| ts.with_timezone(&Utc))
+    };
 
-pub async fn get_projects(State(state): State<Arc<AppState>>) -> impl IntoResponse {
+    match sort {
+        ProjectSortField::Name => Some(ProjectKeyset::Name(cursor.value.clone(), cursor.id)),
+        ProjectSortField::CreatedAt => {
+            timestamp().map(|ts| ProjectKeyset::CreatedAt(ts, cursor.id))
+        }
+        ProjectSortField::UpdatedAt => {
+            timestamp().map(|ts| ProjectKeyset::UpdatedAt(ts, cursor.id))
+        }
+    }
+}
+
+pub async fn get_projects(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
+    Query(params): Query<ProjectListParams>,
+) -> impl IntoResponse {
     let mut conn = match state.pool.get() {
         Ok(conn) => conn,
         Err(err) => {
-            return internal_server_error::<Vec<ProjectResponse>>(
+            return internal_server_error::<Page<ProjectResponse>>(
                 "Database connection failed",
                 &err,
             )
         }
     };
 
-    match Project::get_first_n(&mut conn, 10) {
+    let page = match PageRequest::from_query(params.limit, params.after.as_deref()) {
+        Ok(page) => page,
+        Err(message) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::<Page<ProjectResponse>>::Error { error: message }),
+            )
+        }
+    };
+
+    let sort = params.sort.unwrap_or_default();
+    let after = match page
+        .after
+        .as_ref()
+        .map(|cursor| project_keyset(sort, cursor))
+    {
+        Some(None) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::<Page<ProjectResponse>>::Error {
+                    error: "Invalid cursor".to_string(),
+                }),
+            )
+        }
+        Some(keyset) => keyset,
+        None => None,
+    };
+
+    let name_contains = params.name.filter(|name| !name.trim().is_empty());
+    let query = ProjectListQuery {
+        name_contains,
+        member_id: (!user.is_admin).then_some(user.id),
+        sort,
+        descending: params.order.unwrap_or_default().is_descending(),
+        after,
+        limit: page.fetch_limit(),
+    };
+
+    let total = match Project::count(&mut conn, &query) {
+        Ok(total) => total,
+        Err(err) => {
+            return internal_server_error::<Page<ProjectResponse>>("Failed to count projects", &err)
+        }
+    };
+
+    match Project::list(&mut conn, &query) {
         Ok(projects) => {
-            let response: Vec<ProjectResponse> = projects
-                .into_iter()
-                .map(|p| ProjectResponse {
-                    id: p.id,
-                    name: p.name,
-                    description: p.description,
-                })
-                .collect();
+            let response = Page::from_rows(
+                projects,
+                &page,
+                total,
+                |p| project_cursor(sort, p),
+                ProjectResponse::from,
+            );
             (StatusCode::OK, Json(ApiResponse::Success(response)))
         }
         Err(err) => {
-            return internal_server_error::<Vec<ProjectResponse>>(
-                "Failed to retrieve project list",
+            internal_server_error::<Page<ProjectResponse>>("Failed to retrieve project list", &err)
+        }
+    }
+}
+
+pub async fn get_project(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
+    Path(project_id): Path<i32>,
+    Query(range): Query<TimeRangeParams>,
+) -> impl IntoResponse {
+    let mut conn = match state.pool.get() {
+        Ok(conn) => conn,
+        Err(err) => {
+            return internal_server_error::<ProjectDetailResponse>(
+                "Database connection failed",
                 &err,
             )
         }
+    };
+
+    if let Err(err) = require_project_role(&mut conn, &user, project_id, ProjectRole::Viewer) {
+        return err.into_api_response();
+    }
+    if let Err(error) = range.validate() {
+        return (StatusCode::BAD_REQUEST, Json(ApiResponse::Error { error }));
+    }
+
+    let filter = IssueFilter {
+        project_id: Some(project_id),
+        ..IssueFilter::default()
+    };
+    match Project::find_by_id(&mut conn, project_id) {
+        Ok(Some(project)) => match Issue::status_counts(&mut conn, project_id).and_then(|counts| {
+            Ok((
+                counts,
+                Issue::open_priority_counts(&mut conn, project_id)?,
+                TimeSummary::load(&mut conn, &filter, &range)?,
+            ))
+        }) {
+            Ok((counts, priority_counts, time_tracking)) => {
+                let response = ApiResponse::Success(ProjectDetailResponse {
+                    project: ProjectResponse::from(project),
+                    issue_counts: IssueCountSummary::from_counts(counts, priority_counts),
+                    time_tracking,
+                });
+                (StatusCode::OK, Json(response))
+            }
+            Err(err) => internal_server_error::<ProjectDetailResponse>(
+                "Failed to count project issues",
+                &err,
+            ),
+        },
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse::Error {
+                error: format!("Project with ID {} not found", project_id),
+            }),
+        ),
+        Err(err) => internal_server_error::<ProjectDetailResponse>("Database error", &err),
     }
 }
 
@@ -106,6 +345,7 @@ pub struct DeleteResponse {
 
 pub async fn delete_project(
     State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
     Path(id): Path<i32>,
 ) -> impl IntoResponse {
     let mut conn = match state.pool.get() {
@@ -115,19 +355,25 @@ pub async fn delete_project(
         }
     };
 
+    if let Err(err) = require_project_role(&mut conn, &user, id, ProjectRole::Maintainer) {
+        return err.into_api_response();
+    }
+
     match Project::find_by_id(&mut conn, id) {
-        Ok(Some(_)) => match Project::delete_by_id(&mut conn, id) {
+        Ok(Some(project)) => match Project::delete_by_id(&mut conn, id) {
             Ok(_) => {
                 tracing::info!("Project with ID {} deleted successfully", id);
+                let snapshot = ProjectResponse::from(project);
+                state
+                    .events
+                    .publish(id, EVENT_PROJECT_DELETED, user.id, &snapshot);
                 let response = ApiResponse::Success(DeleteResponse {
                     success: true,
                     message: format!("Project with ID {} successfully deleted", id),
                 });
                 (StatusCode::OK, Json(response))
             }
-            Err(err) => {
-                return internal_server_error::<DeleteResponse>("Failed to delete project", &err)
-            }
+            Err(err) => internal_server_error::<DeleteResponse>("Failed to delete project", &err),
         },
         Ok(None) => {
             tracing::warn!("Attempt to delete non-existent project: {}", id);
@@ -138,14 +384,15 @@ pub async fn delete_project(
                 }),
             )
         }
-        Err(err) => return internal_server_error::<DeleteResponse>("Database error", &err),
+        Err(err) => internal_server_error::<DeleteResponse>("Database error", &err),
     }
 }
 
 pub async fn update_project(
     State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
     Path(project_id): Path<i32>,
-    Json(payload): Json<UpdateProject>,
+    Json(mut payload): Json<UpdateProject>,
 ) -> impl IntoResponse {
     let mut conn = match state.pool.get() {
         Ok(conn) => conn,
@@ -159,9 +406,17 @@ pub async fn update_project(
         }
     };
 
+    if let Err(err) = require_project_role(&mut conn, &user, project_id, ProjectRole::Maintainer) {
+        return err.into_api_response();
+    }
+
     match Project::find_by_id(&mut conn, project_id) {
         Ok(Some(_)) => {
-            if payload.name.is_none() && payload.description.is_none() {
+            if payload.name.is_none()
+                && payload.description.is_none()
+                && payload.key.is_none()
+                && payload.max_issue_depth.is_none()
+            {
                 return (
                     StatusCode::BAD_REQUEST,
                     Json(ApiResponse::<ProjectResponse>::Error {
@@ -170,14 +425,41 @@ pub async fn update_project(
                 );
             }
 
-            match Project::update(&mut conn, project_id, &payload) {
-                Ok(updated_project) => {
-                    let response = ApiResponse::Success(ProjectResponse {
-                        id: updated_project.id,
-                        name: updated_project.name,
-                        description: updated_project.description,
-                    });
-                    (StatusCode::OK, Json(response))
+            if payload
+                .max_issue_depth
+                .is_some_and(|depth| !(1..=10).contains(&depth))
+            {
+                return (
+                    StatusCode::BAD_REQUEST,
+                    Json(ApiResponse::<ProjectResponse>::Error {
+                        error: "max_issue_depth must be between 1 and 10".to_string(),
+                    }),
+                );
+            }
+
+            if let Some(key) = payload.key.as_deref() {
+                payload.key = match normalize_key(key) {
+                    Ok(key) => Some(key),
+                    Err(response) => return response,
+                };
+            }
+
+            let updated = conn.transaction(|conn| {
+                let project = Project::update(conn, project_id, &payload, user.id)?;
+                let response = ProjectResponse::from(project);
+                queue_event(conn, project_id, EVENT_PROJECT_UPDATED, user.id, &response)?;
+                Ok::<_, DieselError>(response)
+            });
+
+            match updated {
+                Ok(response) => {
+                    state
+                        .events
+                        .publish(project_id, EVENT_PROJECT_UPDATED, user.id, &response);
+                    (StatusCode::OK, Json(ApiResponse::Success(response)))
+                }
+                Err(DieselError::DatabaseError(DatabaseErrorKind::UniqueViolation, _)) => {
+                    key_conflict()
                 }
                 Err(err) => (
                     StatusCode::INTERNAL_SERVER_ERROR,
@@ -0,0 +1,329 @@
+use crate::auth::{require_project_role, AuthUser};
+use crate::error::AppError;
+use crate::event_bus::ProjectEvent;
+use crate::handlers::comment::find_issue;
+use crate::handlers::user::UserSummary;
+use crate::models::{Project, ProjectRole, User};
+use crate::presence::PresenceUpdate;
+use crate::routes::router::AppState;
+
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        State,
+    },
+    response::Response,
+};
+use futures_util::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashSet;
+use std::sync::Arc;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::mpsc::{self, error::TrySendError};
+
+/// Messages queued for a connection that is not reading them; past this
+/// the connection is closed rather than buffered without bound.
+pub const OUTBOUND_LIMIT: usize = 256;
+pub const MAX_SUBSCRIPTIONS: usize = 100;
+/// Largest message accepted from clients, in bytes.
+pub const MAX_MESSAGE_SIZE: usize = 16 * 1024;
+
+/// A project, or one issue of it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize, Serialize)]
+pub struct Topic {
+    pub project_id: i32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub issue_id: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClientMessage {
+    Subscribe(Topic),
+    Unsubscribe(Topic),
+    Ping,
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerMessage<'a> {
+    Subscribed(Topic),
+    Unsubscribed(Topic),
+    /// A change event; the fields of its envelope are inlined.
+    Event {
+        id: u64,
+        #[serde(flatten)]
+        envelope: Value,
+    },
+    Presence {
+        project_id: i32,
+        issue_id: i32,
+        viewers: &'a [UserSummary],
+    },
+    Pong,
+    Error {
+        /// HTTP-style status of the failure.
+        status: i32,
+        message: String,
+        /// The subscription the error is about, if any.
+        #[serde(skip_serializing_if = "Option::is_none")]
+        topic: Option<Topic>,
+    },
+}
+
+/// Why the server ends a connection.
+#[derive(Debug)]
+enum Closing {
+    /// The client fell more than `OUTBOUND_LIMIT` messages behind.
+    TooSlow,
+    /// The writer stopped, so the socket is gone.
+    Gone,
+}
+
+/// Issue an event is about, for routing it to issue subscriptions.
+fn event_issue_id(event: &ProjectEvent, envelope: &Value) -> Option<i32> {
+    let data = &envelope["data"];
+    let id = if event.event.starts_with("issue.") {
+        &data["id"]
+    } else if event.event.starts_with("comment.") {
+        &data["issue_id"]
+    } else {
+        return None;
+    };
+    id.as_i64().and_then(|id| i32::try_from(id).ok())
+}
+
+struct Connection {
+    id: u64,
+    state: Arc<AppState>,
+    user: User,
+    outbound: mpsc::Sender<Message>,
+    topics: HashSet<Topic>,
+}
+
+impl Connection {
+    fn send(&self, message: &ServerMessage) -> Result<(), Closing> {
+        let text = match serde_json::to_string(message) {
+            Ok(text) => text,
+            Err(err) => {
+                tracing::error!("Failed to serialize socket message: {:?}", err);
+                return Ok(());
+            }
+        };
+        match self.outbound.try_send(Message::Text(text.into())) {
+            Ok(()) => Ok(()),
+            Err(TrySendError::Full(_)) => Err(Closing::TooSlow),
+            Err(TrySendError::Closed(_)) => Err(Closing::Gone),
+        }
+    }
+
+    fn send_error(&self, error: AppError, topic: Option<Topic>) -> Result<(), Closing> {
+        let log_message = error.to_string();
+        let (status, message) = <(i32, String)>::from(error);
+        if status >= 500 {
+            tracing::error!("{}", log_message);
+        }
+        self.send(&ServerMessage::Error {
+            status,
+            message,
+            topic,
+        })
+    }
+
+    fn handle(&mut self, text: &str) -> Result<(), Closing> {
+        match serde_json::from_str::<ClientMessage>(text) {
+            Ok(ClientMessage::Subscribe(topic)) => self.subscribe(topic),
+            Ok(ClientMessage::Unsubscribe(topic)) => self.unsubscribe(topic),
+            Ok(ClientMessage::Ping) => self.send(&ServerMessage::Pong),
+            Err(err) => self.send_error(
+                AppError::Validation(format!("Invalid message: {}", err)),
+                None,
+            ),
+        }
+    }
+
+    /// Viewers of the project may subscribe to it and to its issues.
+    fn authorize(&self, topic: Topic) -> Result<(), AppError> {
+        let mut conn = self.state.pool.get()?;
+        require_project_role(&mut conn, &self.user, topic.project_id, ProjectRole::Viewer)?;
+        if Project::find_by_id(&mut conn, topic.project_id)?.is_none() {
+            return Err(AppError::ProjectNotFound(topic.project_id.to_string()));
+        }
+        if let Some(issue_id) = topic.issue_id {
+            find_issue(&mut conn, topic.project_id, issue_id)?;
+        }
+        Ok(())
+    }
+
+    fn subscribe(&mut self, topic: Topic) -> Result<(), Closing> {
+        if !self.topics.contains(&topic) {
+            if self.topics.len() >= MAX_SUBSCRIPTIONS {
+                let error = format!("At most {} subscriptions per connection", MAX_SUBSCRIPTIONS);
+                return self.send_error(AppError::Validation(error), Some(topic));
+            }
+            if let Err(err) = self.authorize(topic) {
+                return self.send_error(err, Some(topic));
+            }
+            self.topics.insert(topic);
+        }
+        self.send(&ServerMessage::Subscribed(topic))?;
+
+        // Viewers learn about each other through the broadcast, this one included
+        if let Some(issue_id) = topic.issue_id {
+            let user = UserSummary::from(&self.user);
+            self.state
+                .presence
+                .join(self.id, topic.project_id, issue_id, user);
+        }
+        Ok(())
+    }
+
+    fn unsubscribe(&mut self, topic: Topic) -> Result<(), Closing> {
+        if self.topics.remove(&topic) {
+            if let Some(issue_id) = topic.issue_id {
+                self.state
+                    .presence
+                    .leave(self.id, topic.project_id, issue_id);
+            }
+        }
+        self.send(&ServerMessage::Unsubscribed(topic))
+    }
+
+    fn forward_event(&self, event: &ProjectEvent) -> Result<(), Closing> {
+        if !self.topics.iter().any(|t| t.project_id == event.project_id) {
+            return Ok(());
+        }
+        let Ok(envelope) = serde_json::from_str::<Value>(&event.data) else {
+            return Ok(());
+        };
+
+        let project = Topic {
+            project_id: event.project_id,
+            issue_id: None,
+        };
+        let wanted = self.topics.contains(&project)
+            || event_issue_id(event, &envelope).is_some_and(|issue_id| {
+                self.topics.contains(&Topic {
+                    issue_id: Some(issue_id),
+                    ..project
+                })
+            });
+        if !wanted {
+            return Ok(());
+        }
+        self.send(&ServerMessage::Event {
+            id: event.id,
+            envelope,
+        })
+    }
+
+    fn forward_presence(&self, update: &PresenceUpdate) -> Result<(), Closing> {
+        let topic = Topic {
+            project_id: update.project_id,
+            issue_id: Some(update.issue_id),
+        };
+        if !self.topics.contains(&topic) {
+            return Ok(());
+        }
+        self.send(&ServerMessage::Presence {
+            project_id: update.project_id,
+            issue_id: update.issue_id,
+            viewers: &update.viewers,
+        })
+    }
+}
+
+impl Drop for Connection {
+    fn drop(&mut self) {
+        for topic in &self.topics {
+            if let Some(issue_id) = topic.issue_id {
+                self.state
+                    .presence
+                    .leave(self.id, topic.project_id, issue_id);
+            }
+        }
+    }
+}
+
+/// Opens a WebSocket that multiplexes change events and issue presence for
+/// any number of projects and issues. Browsers, which cannot set headers
+/// on WebSockets, may authenticate with `?access_token=` instead.
+pub async fn connect(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
+    ws: WebSocketUpgrade,
+) -> Response {
+    ws.max_message_size(MAX_MESSAGE_SIZE)
+        .on_upgrade(move |socket| run(socket, state, user))
+}
+
+async fn run(socket: WebSocket, state: Arc<AppState>, user: User) {
+    let (mut sink, mut stream) = socket.split();
+    let (outbound, mut queue) = mpsc::channel::<Message>(OUTBOUND_LIMIT);
+    // Writes happen on their own task so a slow client only fills its queue
+    let writer = tokio::spawn(async move {
+        while let Some(message) = queue.recv().await {
+            if sink.send(message).await.is_err() {
+                return;
+            }
+        }
+        let _ = sink.close().await;
+    });
+
+    let mut events = state.events.receiver();
+    let mut presence = state.presence.subscribe();
+    let mut connection = Connection {
+        id: state.presence.connection_id(),
+        state: state.clone(),
+        user,
+        outbound,
+        topics: HashSet::new(),
+    };
+
+    let closing = loop {
+        // Events already published are sent before replies to later
+        // requests, so a reply never overtakes a change that preceded it
+        let handled = tokio::select! {
+            biased;
+            received = events.recv() => match received {
+                Ok(event) => connection.forward_event(&event),
+                Err(RecvError::Lagged(_)) => Err(Closing::TooSlow),
+                Err(RecvError::Closed) => break None,
+            },
+            received = presence.recv() => match received {
+                Ok(update) => connection.forward_presence(&update),
+                Err(RecvError::Lagged(_)) => Err(Closing::TooSlow),
+                Err(RecvError::Closed) => break None,
+            },
+            incoming = stream.next() => match incoming {
+                Some(Ok(Message::Text(text))) => connection.handle(text.as_str()),
+                Some(Ok(Message::Binary(_))) => connection.send_error(
+                    AppError::Validation("Messages must be JSON text".to_string()),
+                    None,
+                ),
+                Some(Ok(Message::Close(_))) | Some(Err(_)) | None => break None,
+                // Protocol-level pings are answered by the socket itself
+                Some(Ok(_)) => Ok(()),
+            },
+        };
+        if let Err(closing) = handled {
+            break Some(closing);
+        }
+    };
+
+    match closing {
+        Some(Closing::TooSlow) => {
+            tracing::warn!(
+                "Closing socket of user {}: more than {} messages behind",
+                connection.user.id,
+                OUTBOUND_LIMIT
+            );
+            // Its queue is full, so a close frame would wait behind it
+            writer.abort();
+        }
+        Some(Closing::Gone) | None => {}
+    }
+    // Dropping the connection ends the writer and clears its presence
+    drop(connection);
+}
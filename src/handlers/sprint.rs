@@ -0,0 +1,537 @@
+use crate::auth::{require_project_role, AuthUser};
+use crate::error::{internal_server_error, AppError};
+use crate::handlers::api_response::ApiResponse;
+use crate::handlers::issue::nullable;
+use crate::models::{NewSprint, Project, ProjectRole, Sprint, SprintState, UpdateSprint};
+use crate::routes::router::AppState;
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use diesel::prelude::*;
+use diesel::result::{DatabaseErrorKind, Error as DieselError};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+/// Length of a sprint started without an end date.
+const DEFAULT_SPRINT_DAYS: i64 = 14;
+
+#[derive(Debug, Deserialize)]
+pub struct CreateSprintRequest {
+    pub name: String,
+    pub goal: Option<String>,
+    pub start_date: Option<NaiveDate>,
+    pub end_date: Option<NaiveDate>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateSprintRequest {
+    pub name: Option<String>,
+    pub goal: Option<String>,
+    /// `null` clears the date.
+    #[serde(default, deserialize_with = "nullable")]
+    pub start_date: Option<Option<NaiveDate>>,
+    /// `null` clears the date.
+    #[serde(default, deserialize_with = "nullable")]
+    pub end_date: Option<Option<NaiveDate>>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct StartSprintRequest {
+    /// Defaults to the planned start date, else today.
+    pub start_date: Option<NaiveDate>,
+    /// Defaults to the planned end date, else two weeks after the start.
+    pub end_date: Option<NaiveDate>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct CompleteSprintRequest {
+    /// Planned sprint to carry open issues over to; the backlog when absent.
+    pub move_incomplete_to: Option<i32>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SprintResponse {
+    pub id: i32,
+    pub project_id: i32,
+    pub name: String,
+    pub goal: String,
+    pub state: SprintState,
+    pub start_date: Option<NaiveDate>,
+    pub end_date: Option<NaiveDate>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub completed_at: Option<DateTime<Utc>>,
+    /// Set once the sprint is closed.
+    pub completed_issues: Option<i32>,
+    pub incomplete_issues: Option<i32>,
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<Sprint> for SprintResponse {
+    fn from(sprint: Sprint) -> Self {
+        SprintResponse {
+            id: sprint.id,
+            project_id: sprint.project_id,
+            state: sprint.state(),
+            name: sprint.name,
+            goal: sprint.goal,
+            start_date: sprint.start_date,
+            end_date: sprint.end_date,
+            started_at: sprint.started_at,
+            completed_at: sprint.completed_at,
+            completed_issues: sprint.completed_issues,
+            incomplete_issues: sprint.incomplete_issues,
+            created_at: sprint.created_at,
+        }
+    }
+}
+
+/// Issues currently in the sprint.
+#[derive(Debug, Default, Serialize)]
+pub struct SprintIssueCounts {
+    pub total: i64,
+    pub done: i64,
+    pub open: i64,
+    pub by_status: BTreeMap<String, i64>,
+}
+
+impl SprintIssueCounts {
+    fn from_counts(counts: Vec<(String, bool, i64)>) -> Self {
+        let mut summary = SprintIssueCounts::default();
+        for (status, is_open, count) in counts {
+            summary.total += count;
+            if is_open {
+                summary.open += count;
+            } else {
+                summary.done += count;
+            }
+            *summary.by_status.entry(status).or_insert(0) += count;
+        }
+        summary
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct SprintDetailResponse {
+    #[serde(flatten)]
+    pub sprint: SprintResponse,
+    pub issue_counts: SprintIssueCounts,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SprintReportResponse {
+    pub sprint: SprintResponse,
+    pub completed: Vec<i32>,
+    pub incomplete: Vec<i32>,
+    /// Sprint the incomplete issues moved to; `null` for the backlog.
+    pub moved_to: Option<i32>,
+}
+
+fn validate_name(name: &str) -> Result<(), String> {
+    if name.is_empty() || name.len() > 100 {
+        return Err("Sprint names must be 1 to 100 characters".to_string());
+    }
+    Ok(())
+}
+
+fn check_dates(start_date: Option<NaiveDate>, end_date: Option<NaiveDate>) -> Result<(), String> {
+    match (start_date, end_date) {
+        (Some(start), Some(end)) if start > end => {
+            Err(format!("start_date {} is after end_date {}", start, end))
+        }
+        _ => Ok(()),
+    }
+}
+
+fn sprint_conflict<T>(error: String) -> (StatusCode, Json<ApiResponse<T>>) {
+    (StatusCode::CONFLICT, Json(ApiResponse::Error { error }))
+}
+
+fn find_sprint(
+    conn: &mut PgConnection,
+    project_id: i32,
+    sprint_id: i32,
+) -> Result<Sprint, AppError> {
+    match Sprint::find_by_id(conn, sprint_id)? {
+        Some(sprint) if sprint.project_id == project_id => Ok(sprint),
+        _ => Err(AppError::SprintNotFound(sprint_id)),
+    }
+}
+
+pub async fn get_sprints(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
+    Path(project_id): Path<i32>,
+) -> impl IntoResponse {
+    let mut conn = match state.pool.get() {
+        Ok(conn) => conn,
+        Err(err) => {
+            return internal_server_error::<Vec<SprintResponse>>(
+                "Database connection failed",
+                &err,
+            );
+        }
+    };
+
+    if let Err(err) = require_project_role(&mut conn, &user, project_id, ProjectRole::Viewer) {
+        return err.into_api_response();
+    }
+
+    match Sprint::list(&mut conn, project_id) {
+        Ok(sprints) => {
+            let response: Vec<SprintResponse> =
+                sprints.into_iter().map(SprintResponse::from).collect();
+            (StatusCode::OK, Json(ApiResponse::Success(response)))
+        }
+        Err(err) => internal_server_error::<Vec<SprintResponse>>("Failed to load sprints", &err),
+    }
+}
+
+pub async fn create_sprint(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
+    Path(project_id): Path<i32>,
+    Json(payload): Json<CreateSprintRequest>,
+) -> impl IntoResponse {
+    let mut conn = match state.pool.get() {
+        Ok(conn) => conn,
+        Err(err) => {
+            return internal_server_error::<SprintResponse>("Database connection failed", &err);
+        }
+    };
+
+    if let Err(err) = require_project_role(&mut conn, &user, project_id, ProjectRole::Developer) {
+        return err.into_api_response();
+    }
+
+    let name = payload.name.trim();
+    if let Err(error) =
+        validate_name(name).and_then(|_| check_dates(payload.start_date, payload.end_date))
+    {
+        return (StatusCode::BAD_REQUEST, Json(ApiResponse::Error { error }));
+    }
+
+    match Project::find_by_id(&mut conn, project_id) {
+        Ok(Some(_)) => {}
+        Ok(None) => {
+            return AppError::ProjectNotFound(project_id.to_string()).into_api_response();
+        }
+        Err(err) => return internal_server_error::<SprintResponse>("Database error", &err),
+    }
+
+    let new_sprint = NewSprint {
+        project_id,
+        name,
+        goal: payload.goal.as_deref().unwrap_or_default(),
+        start_date: payload.start_date,
+        end_date: payload.end_date,
+    };
+
+    match Sprint::create(&mut conn, &new_sprint) {
+        Ok(sprint) => (
+            StatusCode::CREATED,
+            Json(ApiResponse::Success(SprintResponse::from(sprint))),
+        ),
+        Err(DieselError::DatabaseError(DatabaseErrorKind::UniqueViolation, _)) => sprint_conflict(
+            format!("Project {} already has a sprint with that name", project_id),
+        ),
+        Err(err) => internal_server_error::<SprintResponse>("Failed to create sprint", &err),
+    }
+}
+
+pub async fn get_sprint(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
+    Path((project_id, sprint_id)): Path<(i32, i32)>,
+) -> impl IntoResponse {
+    let mut conn = match state.pool.get() {
+        Ok(conn) => conn,
+        Err(err) => {
+            return internal_server_error::<SprintDetailResponse>(
+                "Database connection failed",
+                &err,
+            );
+        }
+    };
+
+    if let Err(err) = require_project_role(&mut conn, &user, project_id, ProjectRole::Viewer) {
+        return err.into_api_response();
+    }
+    let sprint = match find_sprint(&mut conn, project_id, sprint_id) {
+        Ok(sprint) => sprint,
+        Err(err) => return err.into_api_response(),
+    };
+
+    match Sprint::status_counts(&mut conn, sprint_id) {
+        Ok(counts) => {
+            let response = SprintDetailResponse {
+                sprint: SprintResponse::from(sprint),
+                issue_counts: SprintIssueCounts::from_counts(counts),
+            };
+            (StatusCode::OK, Json(ApiResponse::Success(response)))
+        }
+        Err(err) => {
+            internal_server_error::<SprintDetailResponse>("Failed to count sprint issues", &err)
+        }
+    }
+}
+
+pub async fn update_sprint(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
+    Path((project_id, sprint_id)): Path<(i32, i32)>,
+    Json(payload): Json<UpdateSprintRequest>,
+) -> impl IntoResponse {
+    let mut conn = match state.pool.get() {
+        Ok(conn) => conn,
+        Err(err) => {
+            return internal_server_error::<SprintResponse>("Database connection failed", &err);
+        }
+    };
+
+    if let Err(err) = require_project_role(&mut conn, &user, project_id, ProjectRole::Developer) {
+        return err.into_api_response();
+    }
+
+    let changes = UpdateSprint {
+        name: payload.name.map(|name| name.trim().to_string()),
+        goal: payload.goal,
+        start_date: payload.start_date,
+        end_date: payload.end_date,
+    };
+    if changes.is_empty() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::Error {
+                error: "At least one field must be provided for update.".to_string(),
+            }),
+        );
+    }
+    if let Some(Err(error)) = changes.name.as_deref().map(validate_name) {
+        return (StatusCode::BAD_REQUEST, Json(ApiResponse::Error { error }));
+    }
+
+    let sprint = match find_sprint(&mut conn, project_id, sprint_id) {
+        Ok(sprint) => sprint,
+        Err(err) => return err.into_api_response(),
+    };
+    if sprint.state() == SprintState::Closed {
+        return sprint_conflict(format!("Sprint {} is closed", sprint_id));
+    }
+    if let Err(error) = check_dates(
+        changes.start_date.unwrap_or(sprint.start_date),
+        changes.end_date.unwrap_or(sprint.end_date),
+    ) {
+        return (StatusCode::BAD_REQUEST, Json(ApiResponse::Error { error }));
+    }
+
+    match Sprint::update(&mut conn, sprint_id, &changes) {
+        Ok(sprint) => (
+            StatusCode::OK,
+            Json(ApiResponse::Success(SprintResponse::from(sprint))),
+        ),
+        Err(DieselError::DatabaseError(DatabaseErrorKind::UniqueViolation, _)) => sprint_conflict(
+            format!("Project {} already has a sprint with that name", project_id),
+        ),
+        Err(err) => internal_server_error::<SprintResponse>("Failed to update sprint", &err),
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct DeleteResponse {
+    success: bool,
+    message: String,
+}
+
+pub async fn delete_sprint(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
+    Path((project_id, sprint_id)): Path<(i32, i32)>,
+) -> impl IntoResponse {
+    let mut conn = match state.pool.get() {
+        Ok(conn) => conn,
+        Err(err) => {
+            return internal_server_error::<DeleteResponse>("Database connection failed", &err);
+        }
+    };
+
+    if let Err(err) = require_project_role(&mut conn, &user, project_id, ProjectRole::Developer) {
+        return err.into_api_response();
+    }
+    match find_sprint(&mut conn, project_id, sprint_id) {
+        Ok(sprint) if sprint.state() == SprintState::Active => {
+            return sprint_conflict(format!(
+                "Sprint {} is active; complete it instead",
+                sprint_id
+            ));
+        }
+        Ok(_) => {}
+        Err(err) => return err.into_api_response(),
+    }
+
+    match Sprint::delete(&mut conn, sprint_id) {
+        Ok(_) => {
+            tracing::info!("Sprint {} deleted from project {}", sprint_id, project_id);
+            (
+                StatusCode::OK,
+                Json(ApiResponse::Success(DeleteResponse {
+                    success: true,
+                    message: format!("Sprint {} successfully deleted", sprint_id),
+                })),
+            )
+        }
+        Err(err) => internal_server_error::<DeleteResponse>("Failed to delete sprint", &err),
+    }
+}
+
+/// Starts a planned sprint. The body is optional.
+pub async fn start_sprint(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
+    Path((project_id, sprint_id)): Path<(i32, i32)>,
+    payload: Option<Json<StartSprintRequest>>,
+) -> impl IntoResponse {
+    let mut conn = match state.pool.get() {
+        Ok(conn) => conn,
+        Err(err) => {
+            return internal_server_error::<SprintResponse>("Database connection failed", &err);
+        }
+    };
+
+    if let Err(err) = require_project_role(&mut conn, &user, project_id, ProjectRole::Developer) {
+        return err.into_api_response();
+    }
+    if let Err(err) = find_sprint(&mut conn, project_id, sprint_id) {
+        return err.into_api_response();
+    }
+
+    let Json(payload) = payload.unwrap_or_default();
+    let started = conn.transaction(|conn| {
+        let sprint = Sprint::lock(conn, sprint_id)?.ok_or(DieselError::NotFound)?;
+        if sprint.state() != SprintState::Planned {
+            return Ok(Err(sprint_conflict(format!(
+                "Sprint {} is {}; only planned sprints can start",
+                sprint_id,
+                sprint.state()
+            ))));
+        }
+
+        let start_date = payload
+            .start_date
+            .or(sprint.start_date)
+            .unwrap_or_else(|| Utc::now().date_naive());
+        let end_date = payload
+            .end_date
+            .or(sprint.end_date)
+            .unwrap_or(start_date + Duration::days(DEFAULT_SPRINT_DAYS));
+        if let Err(error) = check_dates(Some(start_date), Some(end_date)) {
+            return Ok(Err((
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::Error { error }),
+            )));
+        }
+
+        Sprint::start(conn, sprint_id, start_date, end_date).map(Ok)
+    });
+
+    match started {
+        Ok(Err(response)) => response,
+        Ok(Ok(sprint)) => (
+            StatusCode::OK,
+            Json(ApiResponse::Success(SprintResponse::from(sprint))),
+        ),
+        Err(DieselError::DatabaseError(DatabaseErrorKind::UniqueViolation, _)) => sprint_conflict(
+            format!("Project {} already has an active sprint", project_id),
+        ),
+        Err(err) => internal_server_error::<SprintResponse>("Failed to start sprint", &err),
+    }
+}
+
+/// Closes the active sprint, carrying its open issues over to a planned
+/// sprint or back to the backlog in the same transaction. The body is
+/// optional.
+pub async fn complete_sprint(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
+    Path((project_id, sprint_id)): Path<(i32, i32)>,
+    payload: Option<Json<CompleteSprintRequest>>,
+) -> impl IntoResponse {
+    let mut conn = match state.pool.get() {
+        Ok(conn) => conn,
+        Err(err) => {
+            return internal_server_error::<SprintReportResponse>(
+                "Database connection failed",
+                &err,
+            );
+        }
+    };
+
+    if let Err(err) = require_project_role(&mut conn, &user, project_id, ProjectRole::Developer) {
+        return err.into_api_response();
+    }
+    if let Err(err) = find_sprint(&mut conn, project_id, sprint_id) {
+        return err.into_api_response();
+    }
+
+    let Json(payload) = payload.unwrap_or_default();
+    let move_to = payload.move_incomplete_to;
+    let completed = conn.transaction(|conn| {
+        let sprint = Sprint::lock(conn, sprint_id)?.ok_or(DieselError::NotFound)?;
+        if sprint.state() != SprintState::Active {
+            return Ok(Err(sprint_conflict(format!(
+                "Sprint {} is {}; only the active sprint can be completed",
+                sprint_id,
+                sprint.state()
+            ))));
+        }
+        if let Some(target_id) = move_to {
+            match Sprint::lock(conn, target_id)? {
+                Some(target)
+                    if target.project_id == project_id
+                        && target.state() == SprintState::Planned => {}
+                _ => {
+                    return Ok(Err((
+                        StatusCode::BAD_REQUEST,
+                        Json(ApiResponse::Error {
+                            error: format!(
+                                "Sprint {} is not a planned sprint of this project",
+                                target_id
+                            ),
+                        }),
+                    )))
+                }
+            }
+        }
+
+        sprint.complete(conn, move_to, user.id).map(Ok)
+    });
+
+    match completed {
+        Ok(Err(response)) => response,
+        Ok(Ok(report)) => {
+            tracing::info!(
+                "Sprint {} completed: {} done, {} carried over",
+                sprint_id,
+                report.completed.len(),
+                report.incomplete.len()
+            );
+            (
+                StatusCode::OK,
+                Json(ApiResponse::Success(SprintReportResponse {
+                    sprint: SprintResponse::from(report.sprint),
+                    completed: report.completed,
+                    incomplete: report.incomplete,
+                    moved_to: move_to,
+                })),
+            )
+        }
+        Err(err) => {
+            internal_server_error::<SprintReportResponse>("Failed to complete sprint", &err)
+        }
+    }
+}
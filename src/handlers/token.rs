@@ -0,0 +1,218 @@
+use crate::auth::{display_prefix, generate_token, hash_token, AuthUser};
+use crate::error::internal_server_error;
+use crate::handlers::api_response::ApiResponse;
+use crate::models::{ApiToken, NewApiToken, User};
+use crate::routes::router::AppState;
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+#[derive(Debug, Deserialize)]
+pub struct CreateTokenRequest {
+    pub user_id: i32,
+    pub name: String,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TokenResponse {
+    pub id: i32,
+    pub user_id: i32,
+    pub name: String,
+    pub token_prefix: String,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+impl From<ApiToken> for TokenResponse {
+    fn from(token: ApiToken) -> Self {
+        TokenResponse {
+            id: token.id,
+            user_id: token.user_id,
+            name: token.name,
+            token_prefix: token.token_prefix,
+            created_at: token.created_at,
+            last_used_at: token.last_used_at,
+            expires_at: token.expires_at,
+            revoked_at: token.revoked_at,
+        }
+    }
+}
+
+/// Returned once, at issue time; the raw token cannot be retrieved later.
+#[derive(Debug, Serialize)]
+pub struct IssuedTokenResponse {
+    #[serde(flatten)]
+    pub metadata: TokenResponse,
+    pub token: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TokenListParams {
+    pub user_id: Option<i32>,
+}
+
+pub(crate) fn require_admin<T>(auth: &AuthUser) -> Result<(), (StatusCode, Json<ApiResponse<T>>)> {
+    if auth.0.is_admin {
+        Ok(())
+    } else {
+        Err((
+            StatusCode::FORBIDDEN,
+            Json(ApiResponse::Error {
+                error: "Administrator access required".to_string(),
+            }),
+        ))
+    }
+}
+
+pub async fn create_token(
+    State(state): State<Arc<AppState>>,
+    auth: AuthUser,
+    Json(payload): Json<CreateTokenRequest>,
+) -> impl IntoResponse {
+    if let Err(denied) = require_admin::<IssuedTokenResponse>(&auth) {
+        return denied;
+    }
+
+    let mut conn = match state.pool.get() {
+        Ok(conn) => conn,
+        Err(err) => {
+            return internal_server_error::<IssuedTokenResponse>(
+                "Database connection failed",
+                &err,
+            );
+        }
+    };
+
+    let name = payload.name.trim();
+    if name.is_empty() || name.len() > 100 {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::Error {
+                error: "Token name must be between 1 and 100 characters".to_string(),
+            }),
+        );
+    }
+    if payload.expires_at.is_some_and(|at| at <= Utc::now()) {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::Error {
+                error: "expires_at must be in the future".to_string(),
+            }),
+        );
+    }
+
+    match User::find_active(&mut conn, payload.user_id) {
+        Ok(Some(_)) => {}
+        Ok(None) => {
+            return (
+                StatusCode::BAD_REQUEST,
+                Json(ApiResponse::Error {
+                    error: format!("No active user with ID {}", payload.user_id),
+                }),
+            );
+        }
+        Err(err) => return internal_server_error::<IssuedTokenResponse>("Database error", &err),
+    }
+
+    let token = generate_token();
+    let token_hash = hash_token(&token);
+    let new_token = NewApiToken {
+        user_id: payload.user_id,
+        name,
+        token_prefix: display_prefix(&token),
+        token_hash: &token_hash,
+        expires_at: payload.expires_at,
+    };
+
+    match ApiToken::create(&mut conn, &new_token) {
+        Ok(created) => {
+            tracing::info!(
+                "User {} issued token {} for user {}",
+                auth.0.id,
+                created.id,
+                created.user_id
+            );
+            (
+                StatusCode::CREATED,
+                Json(ApiResponse::Success(IssuedTokenResponse {
+                    metadata: TokenResponse::from(created),
+                    token,
+                })),
+            )
+        }
+        Err(err) => internal_server_error::<IssuedTokenResponse>("Failed to create token", &err),
+    }
+}
+
+pub async fn get_tokens(
+    State(state): State<Arc<AppState>>,
+    auth: AuthUser,
+    Query(params): Query<TokenListParams>,
+) -> impl IntoResponse {
+    if let Err(denied) = require_admin::<Vec<TokenResponse>>(&auth) {
+        return denied;
+    }
+
+    let mut conn = match state.pool.get() {
+        Ok(conn) => conn,
+        Err(err) => {
+            return internal_server_error::<Vec<TokenResponse>>("Database connection failed", &err);
+        }
+    };
+
+    match ApiToken::list(&mut conn, params.user_id) {
+        Ok(tokens) => {
+            let response: Vec<TokenResponse> =
+                tokens.into_iter().map(TokenResponse::from).collect();
+            (StatusCode::OK, Json(ApiResponse::Success(response)))
+        }
+        Err(err) => internal_server_error::<Vec<TokenResponse>>("Failed to retrieve tokens", &err),
+    }
+}
+
+pub async fn revoke_token(
+    State(state): State<Arc<AppState>>,
+    auth: AuthUser,
+    Path(token_id): Path<i32>,
+) -> impl IntoResponse {
+    if let Err(denied) = require_admin::<TokenResponse>(&auth) {
+        return denied;
+    }
+
+    let mut conn = match state.pool.get() {
+        Ok(conn) => conn,
+        Err(err) => {
+            return internal_server_error::<TokenResponse>("Database connection failed", &err);
+        }
+    };
+
+    match ApiToken::find_by_id(&mut conn, token_id) {
+        Ok(Some(_)) => match ApiToken::revoke(&mut conn, token_id) {
+            Ok(token) => {
+                tracing::info!("User {} revoked token {}", auth.0.id, token_id);
+                (
+                    StatusCode::OK,
+                    Json(ApiResponse::Success(TokenResponse::from(token))),
+                )
+            }
+            Err(err) => internal_server_error::<TokenResponse>("Failed to revoke token", &err),
+        },
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse::Error {
+                error: format!("Token with ID {} not found", token_id),
+            }),
+        ),
+        Err(err) => internal_server_error::<TokenResponse>("Database error", &err),
+    }
+}
@@ -0,0 +1,277 @@
+use crate::auth::AuthUser;
+use crate::error::internal_server_error;
+use crate::handlers::api_response::ApiResponse;
+use crate::handlers::token::require_admin;
+use crate::models::{NewUser, UpdateUser, User};
+use crate::routes::router::AppState;
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use chrono::{DateTime, Utc};
+use diesel::result::{DatabaseErrorKind, Error as DieselError};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+#[derive(Debug, Deserialize)]
+pub struct CreateUserRequest {
+    pub username: Option<String>,
+    pub display_name: Option<String>,
+    pub email: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct UserResponse {
+    pub id: i32,
+    pub username: String,
+    pub display_name: String,
+    pub email: Option<String>,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<User> for UserResponse {
+    fn from(user: User) -> Self {
+        UserResponse {
+            id: user.id,
+            username: user.username,
+            display_name: user.display_name,
+            email: user.email,
+            is_active: user.is_active,
+            created_at: user.created_at,
+            updated_at: user.updated_at,
+        }
+    }
+}
+
+/// Compact form embedded wherever another resource references a user.
+#[derive(Debug, Clone, Serialize)]
+pub struct UserSummary {
+    pub id: i32,
+    pub username: String,
+    pub display_name: String,
+}
+
+impl From<&User> for UserSummary {
+    fn from(user: &User) -> Self {
+        UserSummary {
+            id: user.id,
+            username: user.username.clone(),
+            display_name: user.display_name.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UserListParams {
+    pub active: Option<bool>,
+}
+
+fn conflict<T>(message: &str) -> (StatusCode, Json<ApiResponse<T>>) {
+    (
+        StatusCode::CONFLICT,
+        Json(ApiResponse::Error {
+            error: message.to_string(),
+        }),
+    )
+}
+
+pub async fn create_user(
+    State(state): State<Arc<AppState>>,
+    auth: AuthUser,
+    Json(payload): Json<CreateUserRequest>,
+) -> impl IntoResponse {
+    if let Err(denied) = require_admin::<UserResponse>(&auth) {
+        return denied;
+    }
+
+    let mut conn = match state.pool.get() {
+        Ok(conn) => conn,
+        Err(err) => {
+            return internal_server_error::<UserResponse>("Database connection failed", &err);
+        }
+    };
+
+    let username = payload.username.unwrap_or_default().trim().to_string();
+    if username.is_empty() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::<UserResponse>::Error {
+                error: "Username is required.".to_string(),
+            }),
+        );
+    }
+
+    let display_name = payload
+        .display_name
+        .filter(|name| !name.trim().is_empty())
+        .unwrap_or_else(|| username.clone());
+
+    let new_user = NewUser {
+        username: &username,
+        display_name: &display_name,
+        email: payload.email.as_deref(),
+    };
+
+    match User::create(&mut conn, &new_user) {
+        Ok(user) => (
+            StatusCode::CREATED,
+            Json(ApiResponse::Success(UserResponse::from(user))),
+        ),
+        Err(DieselError::DatabaseError(DatabaseErrorKind::UniqueViolation, _)) => {
+            conflict("Username or email is already taken")
+        }
+        Err(err) => internal_server_error::<UserResponse>("Failed to create user", &err),
+    }
+}
+
+pub async fn get_users(
+    State(state): State<Arc<AppState>>,
+    auth: AuthUser,
+    Query(params): Query<UserListParams>,
+) -> impl IntoResponse {
+    if let Err(denied) = require_admin::<Vec<UserResponse>>(&auth) {
+        return denied;
+    }
+    let mut conn = match state.pool.get() {
+        Ok(conn) => conn,
+        Err(err) => {
+            return internal_server_error::<Vec<UserResponse>>("Database connection failed", &err);
+        }
+    };
+
+    match User::list(&mut conn, params.active) {
+        Ok(users) => {
+            let response: Vec<UserResponse> = users.into_iter().map(UserResponse::from).collect();
+            (StatusCode::OK, Json(ApiResponse::Success(response)))
+        }
+        Err(err) => internal_server_error::<Vec<UserResponse>>("Failed to retrieve users", &err),
+    }
+}
+
+pub async fn get_user(
+    State(state): State<Arc<AppState>>,
+    auth: AuthUser,
+    Path(user_id): Path<i32>,
+) -> impl IntoResponse {
+    if let Err(denied) = require_admin::<UserResponse>(&auth) {
+        return denied;
+    }
+    let mut conn = match state.pool.get() {
+        Ok(conn) => conn,
+        Err(err) => {
+            return internal_server_error::<UserResponse>("Database connection failed", &err);
+        }
+    };
+
+    match User::find_by_id(&mut conn, user_id) {
+        Ok(Some(user)) => (
+            StatusCode::OK,
+            Json(ApiResponse::Success(UserResponse::from(user))),
+        ),
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse::Error {
+                error: format!("User with ID {} not found", user_id),
+            }),
+        ),
+        Err(err) => internal_server_error::<UserResponse>("Database error", &err),
+    }
+}
+
+pub async fn update_user(
+    State(state): State<Arc<AppState>>,
+    auth: AuthUser,
+    Path(user_id): Path<i32>,
+    Json(payload): Json<UpdateUser>,
+) -> impl IntoResponse {
+    if let Err(denied) = require_admin::<UserResponse>(&auth) {
+        return denied;
+    }
+
+    let mut conn = match state.pool.get() {
+        Ok(conn) => conn,
+        Err(err) => {
+            return internal_server_error::<UserResponse>("Database connection failed", &err);
+        }
+    };
+
+    if payload.display_name.is_none() && payload.email.is_none() && payload.is_active.is_none() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::<UserResponse>::Error {
+                error: "At least one field must be provided for update.".to_string(),
+            }),
+        );
+    }
+
+    match User::find_by_id(&mut conn, user_id) {
+        Ok(Some(_)) => match User::update(&mut conn, user_id, &payload) {
+            Ok(user) => (
+                StatusCode::OK,
+                Json(ApiResponse::Success(UserResponse::from(user))),
+            ),
+            Err(DieselError::DatabaseError(DatabaseErrorKind::UniqueViolation, _)) => {
+                conflict("Email is already taken")
+            }
+            Err(err) => internal_server_error::<UserResponse>("Failed to update user", &err),
+        },
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse::Error {
+                error: format!("User with ID {} not found", user_id),
+            }),
+        ),
+        Err(err) => internal_server_error::<UserResponse>("Database error", &err),
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct DeleteResponse {
+    success: bool,
+    message: String,
+}
+
+pub async fn delete_user(
+    State(state): State<Arc<AppState>>,
+    auth: AuthUser,
+    Path(user_id): Path<i32>,
+) -> impl IntoResponse {
+    if let Err(denied) = require_admin::<DeleteResponse>(&auth) {
+        return denied;
+    }
+
+    let mut conn = match state.pool.get() {
+        Ok(conn) => conn,
+        Err(err) => {
+            return internal_server_error::<DeleteResponse>("Database connection failed", &err);
+        }
+    };
+
+    match User::delete(&mut conn, user_id) {
+        Ok(true) => {
+            tracing::info!("User with ID {} deleted successfully", user_id);
+            (
+                StatusCode::OK,
+                Json(ApiResponse::Success(DeleteResponse {
+                    success: true,
+                    message: format!("User with ID {} successfully deleted", user_id),
+                })),
+            )
+        }
+        Ok(false) => (
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse::Error {
+                error: format!("User with ID {} not found", user_id),
+            }),
+        ),
+        Err(DieselError::DatabaseError(DatabaseErrorKind::ForeignKeyViolation, _)) => {
+            conflict("User has issues, comments, worklogs or history on record; deactivate the account instead")
+        }
+        Err(err) => internal_server_error::<DeleteResponse>("Failed to delete user", &err),
+    }
+}
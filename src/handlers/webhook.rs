@@ -0,0 +1,471 @@
+use crate::auth::{require_project_role, to_hex, AuthUser};
+use crate::error::{internal_server_error, AppError};
+use crate::event_bus::EventEnvelope;
+use crate::handlers::api_response::ApiResponse;
+use crate::models::{
+    NewWebhook, ProjectRole, UpdateWebhook, Webhook, WebhookDelivery, DELIVERY_DELIVERED,
+    DELIVERY_FAILED, DELIVERY_PENDING, WEBHOOK_EVENTS,
+};
+use crate::routes::router::AppState;
+use crate::webhook_worker::check_destination;
+
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use diesel::result::Error as DieselError;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::Arc;
+
+/// Shortest secret accepted from callers; generated ones are longer.
+const MIN_SECRET_LEN: usize = 16;
+const DEFAULT_DELIVERY_LIMIT: i64 = 50;
+const MAX_DELIVERY_LIMIT: i64 = 200;
+
+#[derive(Debug, Deserialize)]
+pub struct CreateWebhookRequest {
+    pub url: String,
+    /// Generated when omitted.
+    pub secret: Option<String>,
+    pub events: Vec<String>,
+    pub is_active: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateWebhookRequest {
+    pub url: Option<String>,
+    pub secret: Option<String>,
+    pub events: Option<Vec<String>>,
+    pub is_active: Option<bool>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WebhookResponse {
+    pub id: i32,
+    pub project_id: i32,
+    pub url: String,
+    pub events: Vec<String>,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<Webhook> for WebhookResponse {
+    fn from(webhook: Webhook) -> Self {
+        WebhookResponse {
+            id: webhook.id,
+            project_id: webhook.project_id,
+            url: webhook.url,
+            events: webhook.events,
+            is_active: webhook.is_active,
+            created_at: webhook.created_at,
+            updated_at: webhook.updated_at,
+        }
+    }
+}
+
+/// Returned on creation only; the secret is not shown again.
+#[derive(Debug, Serialize)]
+pub struct CreatedWebhookResponse {
+    #[serde(flatten)]
+    pub webhook: WebhookResponse,
+    pub secret: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DeliveryParams {
+    /// `pending`, `delivered` or `failed`.
+    pub status: Option<String>,
+    pub limit: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DeliveryResponse {
+    pub id: i32,
+    pub webhook_id: i32,
+    pub event: String,
+    pub status: String,
+    pub attempts: i32,
+    /// When a pending delivery is tried next.
+    pub next_attempt_at: Option<DateTime<Utc>>,
+    pub last_attempt_at: Option<DateTime<Utc>>,
+    pub response_status: Option<i32>,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub delivered_at: Option<DateTime<Utc>>,
+    pub payload: Value,
+}
+
+impl From<WebhookDelivery> for DeliveryResponse {
+    fn from(delivery: WebhookDelivery) -> Self {
+        DeliveryResponse {
+            id: delivery.id,
+            webhook_id: delivery.webhook_id,
+            next_attempt_at: (delivery.status == DELIVERY_PENDING)
+                .then_some(delivery.next_attempt_at),
+            event: delivery.event,
+            status: delivery.status,
+            attempts: delivery.attempts,
+            last_attempt_at: delivery.last_attempt_at,
+            response_status: delivery.response_status,
+            last_error: delivery.last_error,
+            created_at: delivery.created_at,
+            delivered_at: delivery.delivered_at,
+            payload: serde_json::from_str(&delivery.payload)
+                .unwrap_or(Value::String(delivery.payload)),
+        }
+    }
+}
+
+/// Queues `event` for the project's subscribed webhooks. Call it inside the
+/// transaction that makes the change.
+pub(crate) fn queue_event<T: Serialize>(
+    conn: &mut PgConnection,
+    project_id: i32,
+    event: &str,
+    actor_id: i32,
+    data: &T,
+) -> QueryResult<usize> {
+    let payload =
+        serde_json::to_string(&EventEnvelope::new(event, project_id, Some(actor_id), data))
+            .map_err(|err| DieselError::SerializationError(Box::new(err)))?;
+
+    WebhookDelivery::enqueue(conn, project_id, event, &payload)
+}
+
+fn generate_secret() -> String {
+    let mut bytes = [0u8; 24];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    format!("whsec_{}", to_hex(&bytes))
+}
+
+/// Webhooks are sent from inside the server, so unless private hosts are
+/// allowed they may not point back into its network.
+async fn check_url(url: &str, allow_private_hosts: bool) -> Result<String, String> {
+    let url = url.trim();
+    let parsed = match reqwest::Url::parse(url) {
+        Ok(parsed) if matches!(parsed.scheme(), "http" | "https") && parsed.has_host() => parsed,
+        _ => return Err(format!("'{}' is not an http or https URL", url)),
+    };
+    if !allow_private_hosts {
+        check_destination(&parsed).await?;
+    }
+    Ok(url.to_string())
+}
+
+fn check_events(events: &[String]) -> Result<Vec<String>, String> {
+    if events.is_empty() {
+        return Err(format!(
+            "Subscribe to at least one event. Allowed values: {:?}",
+            WEBHOOK_EVENTS
+        ));
+    }
+    let mut checked: Vec<String> = Vec::new();
+    for event in events {
+        if !WEBHOOK_EVENTS.contains(&event.as_str()) {
+            return Err(format!(
+                "Unknown event '{}'. Allowed values: {:?}",
+                event, WEBHOOK_EVENTS
+            ));
+        }
+        if !checked.contains(event) {
+            checked.push(event.clone());
+        }
+    }
+    Ok(checked)
+}
+
+fn check_secret(secret: &str) -> Result<(), String> {
+    if secret.len() < MIN_SECRET_LEN {
+        return Err(format!(
+            "secret must be at least {} characters",
+            MIN_SECRET_LEN
+        ));
+    }
+    Ok(())
+}
+
+fn bad_request<T>(error: String) -> (StatusCode, Json<ApiResponse<T>>) {
+    (StatusCode::BAD_REQUEST, Json(ApiResponse::Error { error }))
+}
+
+fn find_webhook(
+    conn: &mut PgConnection,
+    project_id: i32,
+    webhook_id: i32,
+) -> Result<Webhook, AppError> {
+    match Webhook::find_by_id(conn, webhook_id)? {
+        Some(webhook) if webhook.project_id == project_id => Ok(webhook),
+        _ => Err(AppError::WebhookNotFound(webhook_id)),
+    }
+}
+
+pub async fn get_webhooks(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
+    Path(project_id): Path<i32>,
+) -> impl IntoResponse {
+    let mut conn = match state.pool.get() {
+        Ok(conn) => conn,
+        Err(err) => {
+            return internal_server_error::<Vec<WebhookResponse>>(
+                "Database connection failed",
+                &err,
+            );
+        }
+    };
+
+    if let Err(err) = require_project_role(&mut conn, &user, project_id, ProjectRole::Maintainer) {
+        return err.into_api_response();
+    }
+
+    match Webhook::for_project(&mut conn, project_id) {
+        Ok(webhooks) => {
+            let response: Vec<WebhookResponse> =
+                webhooks.into_iter().map(WebhookResponse::from).collect();
+            (StatusCode::OK, Json(ApiResponse::Success(response)))
+        }
+        Err(err) => internal_server_error::<Vec<WebhookResponse>>("Failed to load webhooks", &err),
+    }
+}
+
+pub async fn get_webhook(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
+    Path((project_id, webhook_id)): Path<(i32, i32)>,
+) -> impl IntoResponse {
+    let mut conn = match state.pool.get() {
+        Ok(conn) => conn,
+        Err(err) => {
+            return internal_server_error::<WebhookResponse>("Database connection failed", &err);
+        }
+    };
+
+    if let Err(err) = require_project_role(&mut conn, &user, project_id, ProjectRole::Maintainer) {
+        return err.into_api_response();
+    }
+
+    match find_webhook(&mut conn, project_id, webhook_id) {
+        Ok(webhook) => (
+            StatusCode::OK,
+            Json(ApiResponse::Success(WebhookResponse::from(webhook))),
+        ),
+        Err(err) => err.into_api_response(),
+    }
+}
+
+pub async fn create_webhook(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
+    Path(project_id): Path<i32>,
+    Json(payload): Json<CreateWebhookRequest>,
+) -> impl IntoResponse {
+    let mut conn = match state.pool.get() {
+        Ok(conn) => conn,
+        Err(err) => {
+            return internal_server_error::<CreatedWebhookResponse>(
+                "Database connection failed",
+                &err,
+            );
+        }
+    };
+
+    if let Err(err) = require_project_role(&mut conn, &user, project_id, ProjectRole::Maintainer) {
+        return err.into_api_response();
+    }
+
+    let url = match check_url(&payload.url, state.allow_private_webhook_hosts).await {
+        Ok(url) => url,
+        Err(error) => return bad_request(error),
+    };
+    let events = match check_events(&payload.events) {
+        Ok(events) => events,
+        Err(error) => return bad_request(error),
+    };
+    let secret = match payload.secret {
+        Some(secret) => match check_secret(&secret) {
+            Ok(()) => secret,
+            Err(error) => return bad_request(error),
+        },
+        None => generate_secret(),
+    };
+
+    let new_webhook = NewWebhook {
+        project_id,
+        url: &url,
+        secret: &secret,
+        events: &events,
+        is_active: payload.is_active.unwrap_or(true),
+    };
+
+    match Webhook::create(&mut conn, &new_webhook) {
+        Ok(webhook) => {
+            tracing::info!(
+                "User {} added webhook {} to project {}",
+                user.id,
+                webhook.id,
+                project_id
+            );
+            let response = CreatedWebhookResponse {
+                webhook: WebhookResponse::from(webhook),
+                secret,
+            };
+            (StatusCode::CREATED, Json(ApiResponse::Success(response)))
+        }
+        Err(err) => {
+            internal_server_error::<CreatedWebhookResponse>("Failed to create webhook", &err)
+        }
+    }
+}
+
+pub async fn update_webhook(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
+    Path((project_id, webhook_id)): Path<(i32, i32)>,
+    Json(payload): Json<UpdateWebhookRequest>,
+) -> impl IntoResponse {
+    let mut conn = match state.pool.get() {
+        Ok(conn) => conn,
+        Err(err) => {
+            return internal_server_error::<WebhookResponse>("Database connection failed", &err);
+        }
+    };
+
+    if let Err(err) = require_project_role(&mut conn, &user, project_id, ProjectRole::Maintainer) {
+        return err.into_api_response();
+    }
+    if let Err(err) = find_webhook(&mut conn, project_id, webhook_id) {
+        return err.into_api_response();
+    }
+
+    let mut changes = UpdateWebhook {
+        is_active: payload.is_active,
+        ..UpdateWebhook::default()
+    };
+    if let Some(url) = payload.url.as_deref() {
+        match check_url(url, state.allow_private_webhook_hosts).await {
+            Ok(url) => changes.url = Some(url),
+            Err(error) => return bad_request(error),
+        }
+    }
+    if let Some(events) = payload.events.as_deref() {
+        match check_events(events) {
+            Ok(events) => changes.events = Some(events),
+            Err(error) => return bad_request(error),
+        }
+    }
+    if let Some(secret) = payload.secret {
+        if let Err(error) = check_secret(&secret) {
+            return bad_request(error);
+        }
+        changes.secret = Some(secret);
+    }
+    if changes.is_empty() {
+        return bad_request("At least one field must be provided for update.".to_string());
+    }
+
+    match Webhook::update(&mut conn, webhook_id, &changes) {
+        Ok(updated) => (
+            StatusCode::OK,
+            Json(ApiResponse::Success(WebhookResponse::from(updated))),
+        ),
+        Err(err) => internal_server_error::<WebhookResponse>("Failed to update webhook", &err),
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct DeleteResponse {
+    pub success: bool,
+    pub message: String,
+}
+
+pub async fn delete_webhook(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
+    Path((project_id, webhook_id)): Path<(i32, i32)>,
+) -> impl IntoResponse {
+    let mut conn = match state.pool.get() {
+        Ok(conn) => conn,
+        Err(err) => {
+            return internal_server_error::<DeleteResponse>("Database connection failed", &err);
+        }
+    };
+
+    if let Err(err) = require_project_role(&mut conn, &user, project_id, ProjectRole::Maintainer) {
+        return err.into_api_response();
+    }
+    if let Err(err) = find_webhook(&mut conn, project_id, webhook_id) {
+        return err.into_api_response();
+    }
+
+    match Webhook::delete(&mut conn, webhook_id) {
+        Ok(_) => {
+            tracing::info!("Webhook {} deleted by user {}", webhook_id, user.id);
+            let response = DeleteResponse {
+                success: true,
+                message: format!("Webhook with ID {} successfully deleted", webhook_id),
+            };
+            (StatusCode::OK, Json(ApiResponse::Success(response)))
+        }
+        Err(err) => internal_server_error::<DeleteResponse>("Failed to delete webhook", &err),
+    }
+}
+
+/// Delivery log of a webhook, newest first.
+pub async fn get_deliveries(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
+    Path((project_id, webhook_id)): Path<(i32, i32)>,
+    Query(params): Query<DeliveryParams>,
+) -> impl IntoResponse {
+    let mut conn = match state.pool.get() {
+        Ok(conn) => conn,
+        Err(err) => {
+            return internal_server_error::<Vec<DeliveryResponse>>(
+                "Database connection failed",
+                &err,
+            );
+        }
+    };
+
+    if let Err(err) = require_project_role(&mut conn, &user, project_id, ProjectRole::Maintainer) {
+        return err.into_api_response();
+    }
+    if let Err(err) = find_webhook(&mut conn, project_id, webhook_id) {
+        return err.into_api_response();
+    }
+
+    let statuses = [DELIVERY_PENDING, DELIVERY_DELIVERED, DELIVERY_FAILED];
+    if let Some(status) = params.status.as_deref() {
+        if !statuses.contains(&status) {
+            return bad_request(format!(
+                "Invalid status value. Allowed values: {:?}",
+                statuses
+            ));
+        }
+    }
+    let limit = params.limit.unwrap_or(DEFAULT_DELIVERY_LIMIT);
+    if !(1..=MAX_DELIVERY_LIMIT).contains(&limit) {
+        return bad_request(format!(
+            "limit must be between 1 and {}",
+            MAX_DELIVERY_LIMIT
+        ));
+    }
+
+    match WebhookDelivery::for_webhook(&mut conn, webhook_id, params.status.as_deref(), limit) {
+        Ok(deliveries) => {
+            let response: Vec<DeliveryResponse> =
+                deliveries.into_iter().map(DeliveryResponse::from).collect();
+            (StatusCode::OK, Json(ApiResponse::Success(response)))
+        }
+        Err(err) => {
+            internal_server_error::<Vec<DeliveryResponse>>("Failed to load deliveries", &err)
+        }
+    }
+}
@@ -0,0 +1,182 @@
+use crate::auth::{require_project_role, AuthUser};
+use crate::error::internal_server_error;
+use crate::handlers::api_response::ApiResponse;
+use crate::models::{Project, ProjectRole, StateDefinition, TransitionDefinition, Workflow};
+use crate::routes::router::AppState;
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::Arc;
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WorkflowStateBody {
+    pub name: String,
+    pub category: String,
+    #[serde(default)]
+    pub is_initial: bool,
+    /// Most issues allowed in the status at once; none for no limit.
+    #[serde(default)]
+    pub wip_limit: Option<i32>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WorkflowTransitionBody {
+    pub from: String,
+    pub to: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct WorkflowBody {
+    pub states: Vec<WorkflowStateBody>,
+    pub transitions: Vec<WorkflowTransitionBody>,
+}
+
+impl From<Workflow> for WorkflowBody {
+    fn from(workflow: Workflow) -> Self {
+        let transitions = workflow
+            .transition_names()
+            .into_iter()
+            .map(|(from, to)| WorkflowTransitionBody {
+                from: from.to_string(),
+                to: to.to_string(),
+            })
+            .collect();
+
+        WorkflowBody {
+            states: workflow
+                .states
+                .into_iter()
+                .map(|s| WorkflowStateBody {
+                    name: s.name,
+                    category: s.category,
+                    is_initial: s.is_initial,
+                    wip_limit: s.wip_limit,
+                })
+                .collect(),
+            transitions,
+        }
+    }
+}
+
+pub async fn get_workflow(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
+    Path(project_id): Path<i32>,
+) -> impl IntoResponse {
+    let mut conn = match state.pool.get() {
+        Ok(conn) => conn,
+        Err(err) => {
+            return internal_server_error::<WorkflowBody>("Database connection failed", &err)
+        }
+    };
+
+    if let Err(err) = require_project_role(&mut conn, &user, project_id, ProjectRole::Viewer) {
+        return err.into_api_response();
+    }
+
+    match Project::find_by_id(&mut conn, project_id) {
+        Ok(Some(_)) => match Workflow::load(&mut conn, project_id) {
+            Ok(workflow) => (
+                StatusCode::OK,
+                Json(ApiResponse::Success(WorkflowBody::from(workflow))),
+            ),
+            Err(err) => internal_server_error::<WorkflowBody>("Failed to load workflow", &err),
+        },
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse::Error {
+                error: format!("Project with ID {} not found", project_id),
+            }),
+        ),
+        Err(err) => internal_server_error::<WorkflowBody>("Database error", &err),
+    }
+}
+
+pub async fn update_workflow(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
+    Path(project_id): Path<i32>,
+    Json(payload): Json<WorkflowBody>,
+) -> impl IntoResponse {
+    let mut conn = match state.pool.get() {
+        Ok(conn) => conn,
+        Err(err) => {
+            return internal_server_error::<WorkflowBody>("Database connection failed", &err)
+        }
+    };
+
+    if let Err(err) = require_project_role(&mut conn, &user, project_id, ProjectRole::Maintainer) {
+        return err.into_api_response();
+    }
+
+    let states: Vec<StateDefinition> = payload
+        .states
+        .into_iter()
+        .map(|s| StateDefinition {
+            name: s.name.trim().to_string(),
+            category: s.category,
+            is_initial: s.is_initial,
+            wip_limit: s.wip_limit,
+        })
+        .collect();
+    let transitions: Vec<TransitionDefinition> = payload
+        .transitions
+        .into_iter()
+        .map(|t| TransitionDefinition {
+            from: t.from.trim().to_string(),
+            to: t.to.trim().to_string(),
+        })
+        .collect();
+
+    if let Err(message) = Workflow::validate_definition(&states, &transitions) {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::Error { error: message }),
+        );
+    }
+
+    match Project::find_by_id(&mut conn, project_id) {
+        Ok(Some(_)) => {
+            match Workflow::states_in_use_missing(&mut conn, project_id, &states) {
+                Ok(missing) if !missing.is_empty() => {
+                    return (
+                        StatusCode::CONFLICT,
+                        Json(ApiResponse::DetailedError {
+                            error: "Workflow drops states that issues are still in".to_string(),
+                            code: "states_in_use".to_string(),
+                            details: json!({ "states": missing }),
+                        }),
+                    )
+                }
+                Ok(_) => {}
+                Err(err) => {
+                    return internal_server_error::<WorkflowBody>("Failed to check issues", &err)
+                }
+            }
+
+            match Workflow::replace(&mut conn, project_id, &states, &transitions) {
+                Ok(workflow) => {
+                    tracing::info!("Replaced workflow of project {}", project_id);
+                    (
+                        StatusCode::OK,
+                        Json(ApiResponse::Success(WorkflowBody::from(workflow))),
+                    )
+                }
+                Err(err) => internal_server_error::<WorkflowBody>("Failed to save workflow", &err),
+            }
+        }
+        Ok(None) => (
+            StatusCode::NOT_FOUND,
+            Json(ApiResponse::Error {
+                error: format!("Project with ID {} not found", project_id),
+            }),
+        ),
+        Err(err) => internal_server_error::<WorkflowBody>("Database error", &err),
+    }
+}
@@ -0,0 +1,365 @@
+use crate::auth::{require_project_role, AuthUser};
+use crate::error::{internal_server_error, AppError};
+use crate::handlers::api_response::ApiResponse;
+use crate::handlers::comment::find_issue;
+use crate::handlers::issue::ensure_active_user;
+use crate::handlers::user::UserSummary;
+use crate::models::{
+    EstimateTotals, IssueFilter, NewWorklog, ProjectRole, UpdateWorklog, User, Worklog,
+};
+use crate::routes::router::AppState;
+
+use axum::{
+    extract::{Path, State},
+    http::StatusCode,
+    response::IntoResponse,
+    Json,
+};
+use chrono::{DateTime, NaiveDate, Utc};
+use diesel::prelude::*;
+use diesel::result::Error as DieselError;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+/// Upper bound on a single worklog: one full day.
+const MAX_WORKLOG_MINUTES: i32 = 24 * 60;
+
+#[derive(Debug, Deserialize)]
+pub struct CreateWorklogRequest {
+    pub minutes: i32,
+    /// Defaults to today.
+    pub work_date: Option<NaiveDate>,
+    #[serde(default)]
+    pub note: String,
+    /// Log time for someone else; defaults to the caller.
+    pub user_id: Option<i32>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UpdateWorklogRequest {
+    pub minutes: Option<i32>,
+    pub work_date: Option<NaiveDate>,
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct WorklogResponse {
+    pub id: i32,
+    pub issue_id: i32,
+    pub user: UserSummary,
+    pub minutes: i32,
+    pub work_date: NaiveDate,
+    pub note: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+impl WorklogResponse {
+    fn new(worklog: Worklog, user: &User) -> Self {
+        WorklogResponse {
+            id: worklog.id,
+            issue_id: worklog.issue_id,
+            user: UserSummary::from(user),
+            minutes: worklog.minutes,
+            work_date: worklog.work_date,
+            note: worklog.note,
+            created_at: worklog.created_at,
+            updated_at: worklog.updated_at,
+        }
+    }
+}
+
+/// Date range for time summaries; both ends are inclusive and either may be
+/// left open.
+#[derive(Debug, Default, Deserialize)]
+pub struct TimeRangeParams {
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+}
+
+impl TimeRangeParams {
+    pub fn validate(&self) -> Result<(), String> {
+        match (self.from, self.to) {
+            (Some(from), Some(to)) if from > to => Err("from cannot be after to".to_string()),
+            _ => Ok(()),
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct UserTime {
+    pub user: UserSummary,
+    pub minutes: i64,
+}
+
+/// Time logged on a set of issues within a date range, next to their
+/// estimates. The estimates ignore the range.
+#[derive(Debug, Serialize)]
+pub struct TimeSummary {
+    pub from: Option<NaiveDate>,
+    pub to: Option<NaiveDate>,
+    pub logged_minutes: i64,
+    pub by_user: Vec<UserTime>,
+    #[serde(flatten)]
+    pub estimates: EstimateTotals,
+}
+
+impl TimeSummary {
+    pub fn load(
+        conn: &mut PgConnection,
+        filter: &IssueFilter,
+        range: &TimeRangeParams,
+    ) -> QueryResult<Self> {
+        let totals = Worklog::minutes_by_user(conn, filter, range.from, range.to)?;
+        let user_ids: Vec<i32> = totals.iter().map(|(user_id, _)| *user_id).collect();
+        let users = User::find_many(conn, &user_ids)?;
+        let by_user = totals
+            .into_iter()
+            .map(|(user_id, minutes)| {
+                let user = users.get(&user_id).ok_or(DieselError::NotFound)?;
+                Ok(UserTime {
+                    user: UserSummary::from(user),
+                    minutes,
+                })
+            })
+            .collect::<QueryResult<Vec<_>>>()?;
+
+        Ok(TimeSummary {
+            from: range.from,
+            to: range.to,
+            logged_minutes: by_user.iter().map(|entry| entry.minutes).sum(),
+            by_user,
+            estimates: Worklog::estimate_totals(conn, filter)?,
+        })
+    }
+}
+
+fn find_worklog(
+    conn: &mut PgConnection,
+    issue_id: i32,
+    worklog_id: i32,
+) -> Result<Worklog, AppError> {
+    match Worklog::find_by_id(conn, worklog_id)? {
+        Some(worklog) if worklog.issue_id == issue_id => Ok(worklog),
+        _ => Err(AppError::WorklogNotFound(worklog_id)),
+    }
+}
+
+fn check_minutes(minutes: i32) -> Result<(), String> {
+    if (1..=MAX_WORKLOG_MINUTES).contains(&minutes) {
+        Ok(())
+    } else {
+        Err(format!(
+            "minutes must be between 1 and {}",
+            MAX_WORKLOG_MINUTES
+        ))
+    }
+}
+
+fn worklog_response(
+    conn: &mut PgConnection,
+    worklog: Worklog,
+) -> (StatusCode, Json<ApiResponse<WorklogResponse>>) {
+    match User::find_by_id(conn, worklog.user_id).and_then(|u| u.ok_or(DieselError::NotFound)) {
+        Ok(user) => (
+            StatusCode::OK,
+            Json(ApiResponse::Success(WorklogResponse::new(worklog, &user))),
+        ),
+        Err(err) => internal_server_error::<WorklogResponse>("Failed to load worklog", &err),
+    }
+}
+
+pub async fn get_worklogs(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
+    Path((project_id, issue_id)): Path<(i32, i32)>,
+) -> impl IntoResponse {
+    let mut conn = match state.pool.get() {
+        Ok(conn) => conn,
+        Err(err) => {
+            return internal_server_error::<Vec<WorklogResponse>>(
+                "Database connection failed",
+                &err,
+            );
+        }
+    };
+
+    if let Err(err) = require_project_role(&mut conn, &user, project_id, ProjectRole::Viewer) {
+        return err.into_api_response();
+    }
+    if let Err(err) = find_issue(&mut conn, project_id, issue_id) {
+        return err.into_api_response();
+    }
+
+    let loaded = Worklog::for_issue(&mut conn, issue_id).and_then(|worklogs| {
+        let mut user_ids: Vec<i32> = worklogs.iter().map(|w| w.user_id).collect();
+        user_ids.sort_unstable();
+        user_ids.dedup();
+        let users = User::find_many(&mut conn, &user_ids)?;
+        worklogs
+            .into_iter()
+            .map(|worklog| {
+                let user = users.get(&worklog.user_id).ok_or(DieselError::NotFound)?;
+                Ok(WorklogResponse::new(worklog, user))
+            })
+            .collect::<QueryResult<Vec<_>>>()
+    });
+
+    match loaded {
+        Ok(response) => (StatusCode::OK, Json(ApiResponse::Success(response))),
+        Err(err) => internal_server_error::<Vec<WorklogResponse>>("Failed to load worklogs", &err),
+    }
+}
+
+pub async fn create_worklog(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
+    Path((project_id, issue_id)): Path<(i32, i32)>,
+    Json(payload): Json<CreateWorklogRequest>,
+) -> impl IntoResponse {
+    let mut conn = match state.pool.get() {
+        Ok(conn) => conn,
+        Err(err) => {
+            return internal_server_error::<WorklogResponse>("Database connection failed", &err);
+        }
+    };
+
+    let role = match require_project_role(&mut conn, &user, project_id, ProjectRole::Reporter) {
+        Ok(role) => role,
+        Err(err) => return err.into_api_response(),
+    };
+    if let Err(err) = find_issue(&mut conn, project_id, issue_id) {
+        return err.into_api_response();
+    }
+
+    let user_id = payload.user_id.unwrap_or(user.id);
+    if user_id != user.id {
+        if role < ProjectRole::Maintainer {
+            return AppError::Forbidden("Only maintainers can log time for others".to_string())
+                .into_api_response();
+        }
+        if let Err((status, error)) = ensure_active_user(&mut conn, user_id) {
+            return (status, Json(ApiResponse::Error { error }));
+        }
+    }
+
+    if let Err(error) = check_minutes(payload.minutes) {
+        return (StatusCode::BAD_REQUEST, Json(ApiResponse::Error { error }));
+    }
+
+    let new_worklog = NewWorklog {
+        issue_id,
+        user_id,
+        minutes: payload.minutes,
+        work_date: payload.work_date.unwrap_or_else(|| Utc::now().date_naive()),
+        note: payload.note.trim(),
+    };
+
+    match Worklog::create(&mut conn, &new_worklog) {
+        Ok(worklog) => {
+            let (_, response) = worklog_response(&mut conn, worklog);
+            (StatusCode::CREATED, response)
+        }
+        Err(err) => internal_server_error::<WorklogResponse>("Failed to create worklog", &err),
+    }
+}
+
+pub async fn update_worklog(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
+    Path((project_id, issue_id, worklog_id)): Path<(i32, i32, i32)>,
+    Json(payload): Json<UpdateWorklogRequest>,
+) -> impl IntoResponse {
+    let mut conn = match state.pool.get() {
+        Ok(conn) => conn,
+        Err(err) => {
+            return internal_server_error::<WorklogResponse>("Database connection failed", &err);
+        }
+    };
+
+    let role = match require_project_role(&mut conn, &user, project_id, ProjectRole::Reporter) {
+        Ok(role) => role,
+        Err(err) => return err.into_api_response(),
+    };
+    let worklog = match find_issue(&mut conn, project_id, issue_id)
+        .and_then(|_| find_worklog(&mut conn, issue_id, worklog_id))
+    {
+        Ok(worklog) => worklog,
+        Err(err) => return err.into_api_response(),
+    };
+
+    if worklog.user_id != user.id && role < ProjectRole::Maintainer {
+        return AppError::Forbidden("Only the author can edit a worklog".to_string())
+            .into_api_response();
+    }
+
+    if let Some(Err(error)) = payload.minutes.map(check_minutes) {
+        return (StatusCode::BAD_REQUEST, Json(ApiResponse::Error { error }));
+    }
+
+    let changes = UpdateWorklog {
+        minutes: payload.minutes,
+        work_date: payload.work_date,
+        note: payload.note.map(|note| note.trim().to_string()),
+    };
+    if changes.is_empty() {
+        return (
+            StatusCode::BAD_REQUEST,
+            Json(ApiResponse::Error {
+                error: "At least one field must be provided for update.".to_string(),
+            }),
+        );
+    }
+
+    match Worklog::update(&mut conn, worklog_id, &changes) {
+        Ok(updated) => worklog_response(&mut conn, updated),
+        Err(err) => internal_server_error::<WorklogResponse>("Failed to update worklog", &err),
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct DeleteResponse {
+    pub success: bool,
+    pub message: String,
+}
+
+pub async fn delete_worklog(
+    State(state): State<Arc<AppState>>,
+    AuthUser(user): AuthUser,
+    Path((project_id, issue_id, worklog_id)): Path<(i32, i32, i32)>,
+) -> impl IntoResponse {
+    let mut conn = match state.pool.get() {
+        Ok(conn) => conn,
+        Err(err) => {
+            return internal_server_error::<DeleteResponse>("Database connection failed", &err);
+        }
+    };
+
+    let role = match require_project_role(&mut conn, &user, project_id, ProjectRole::Reporter) {
+        Ok(role) => role,
+        Err(err) => return err.into_api_response(),
+    };
+    let worklog = match find_issue(&mut conn, project_id, issue_id)
+        .and_then(|_| find_worklog(&mut conn, issue_id, worklog_id))
+    {
+        Ok(worklog) => worklog,
+        Err(err) => return err.into_api_response(),
+    };
+
+    if worklog.user_id != user.id && role < ProjectRole::Maintainer {
+        return AppError::Forbidden("Only the author can delete a worklog".to_string())
+            .into_api_response();
+    }
+
+    match Worklog::delete(&mut conn, worklog_id) {
+        Ok(_) => {
+            tracing::info!("Worklog {} deleted by user {}", worklog_id, user.id);
+            let response = DeleteResponse {
+                success: true,
+                message: format!("Worklog with ID {} successfully deleted", worklog_id),
+            };
+            (StatusCode::OK, Json(ApiResponse::Success(response)))
+        }
+        Err(err) => internal_server_error::<DeleteResponse>("Failed to delete worklog", &err),
+    }
+}
@@ -1,7 +1,13 @@
+pub mod auth;
+pub mod change_feed;
+pub mod config;
 pub mod db;
 pub mod error;
+pub mod event_bus;
 pub mod handlers;
 pub mod models;
+pub mod presence;
 pub mod routes;
+pub mod webhook_worker;
 
 pub use error::AppError;
@@ -1,48 +1,42 @@
-mod config;
-mod db;
-mod error;
-mod handlers;
-mod models;
-mod routes;
 use axum::serve;
-use diesel::pg::PgConnection;
-use diesel::r2d2::{ConnectionManager, Pool};
 use dotenvy::dotenv;
 use std::net::SocketAddr;
 use std::sync::Arc;
 use tokio::net::TcpListener;
-use tracing_subscriber;
 
-use crate::routes::create_router;
-pub use config::DatabaseConfig;
-pub use db::create_pool;
-pub use error::AppError;
-pub use models::Project;
+use ticket_manager::change_feed::{self, FeedConfig};
+use ticket_manager::config::{allow_private_webhook_hosts, DatabaseConfig};
+use ticket_manager::db::create_pool;
+use ticket_manager::routes::router::{build_router, AppState};
+use ticket_manager::webhook_worker::{self, WorkerConfig};
 
-pub struct AppState {
-    pub pool: PgPool,
-}
-
-pub type PgPool = Pool<ConnectionManager<PgConnection>>;
-
-pub fn establish_connection() -> PgPool {
-    dotenv().ok();
-
-    let database_url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set");
-
-    let manager = ConnectionManager::<PgConnection>::new(database_url);
-    Pool::builder()
-        .build(manager)
-        .expect("Failed to create pool")
-}
 #[tokio::main]
 async fn main() {
+    dotenv().ok();
     tracing_subscriber::fmt::init();
 
-    let conn = establish_connection();
-    let state = Arc::new(AppState { pool: conn });
-
-    let app: axum::Router = create_router(state.pool.clone());
+    let config = DatabaseConfig::from_env().expect("Failed to load database configuration");
+    let pool = create_pool(&config.connection_string);
+    let allow_private_webhook_hosts = allow_private_webhook_hosts();
+    webhook_worker::spawn(
+        pool.clone(),
+        WorkerConfig {
+            allow_private_hosts: allow_private_webhook_hosts,
+            ..WorkerConfig::default()
+        },
+    );
+
+    let state = Arc::new(AppState {
+        allow_private_webhook_hosts,
+        ..AppState::new(pool)
+    });
+    change_feed::spawn(
+        state.clone(),
+        config.connection_string.clone(),
+        FeedConfig::default(),
+    );
+
+    let app: axum::Router = build_router(state);
 
     let addr = SocketAddr::from(([127, 0, 0, 1], 3000));
     println!("Listening on {}", addr);
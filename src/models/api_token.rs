@@ -0,0 +1,103 @@
+use crate::db::schema::{api_tokens, users};
+use crate::models::User;
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = api_tokens)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct ApiToken {
+    pub id: i32,
+    pub user_id: i32,
+    pub name: String,
+    pub token_prefix: String,
+    pub token_hash: String,
+    pub created_at: DateTime<Utc>,
+    pub last_used_at: Option<DateTime<Utc>>,
+    pub expires_at: Option<DateTime<Utc>>,
+    pub revoked_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = api_tokens)]
+pub struct NewApiToken<'a> {
+    pub user_id: i32,
+    pub name: &'a str,
+    pub token_prefix: &'a str,
+    pub token_hash: &'a str,
+    pub expires_at: Option<DateTime<Utc>>,
+}
+
+impl ApiToken {
+    pub fn create(conn: &mut PgConnection, new_token: &NewApiToken) -> QueryResult<ApiToken> {
+        diesel::insert_into(api_tokens::table)
+            .values(new_token)
+            .returning(ApiToken::as_returning())
+            .get_result(conn)
+    }
+
+    pub fn list(conn: &mut PgConnection, user_id: Option<i32>) -> QueryResult<Vec<ApiToken>> {
+        let mut query = api_tokens::table.into_boxed();
+        if let Some(user_id) = user_id {
+            query = query.filter(api_tokens::user_id.eq(user_id));
+        }
+
+        query
+            .order(api_tokens::id.asc())
+            .select(ApiToken::as_select())
+            .load(conn)
+    }
+
+    pub fn find_by_id(conn: &mut PgConnection, token_id: i32) -> QueryResult<Option<ApiToken>> {
+        api_tokens::table
+            .find(token_id)
+            .select(ApiToken::as_select())
+            .first(conn)
+            .optional()
+    }
+
+    /// Marks the token revoked. Revoking twice keeps the original time.
+    pub fn revoke(conn: &mut PgConnection, token_id: i32) -> QueryResult<ApiToken> {
+        diesel::update(
+            api_tokens::table
+                .find(token_id)
+                .filter(api_tokens::revoked_at.is_null()),
+        )
+        .set(api_tokens::revoked_at.eq(Utc::now()))
+        .execute(conn)?;
+
+        api_tokens::table
+            .find(token_id)
+            .select(ApiToken::as_select())
+            .first(conn)
+    }
+
+    /// Resolves a token hash to its active owner, recording the use. Revoked
+    /// and expired tokens, and tokens of deactivated users, resolve to `None`.
+    pub fn authenticate(conn: &mut PgConnection, token_hash: &str) -> QueryResult<Option<User>> {
+        let now = Utc::now();
+        let found = api_tokens::table
+            .inner_join(users::table)
+            .filter(api_tokens::token_hash.eq(token_hash))
+            .filter(api_tokens::revoked_at.is_null())
+            .filter(
+                api_tokens::expires_at
+                    .is_null()
+                    .or(api_tokens::expires_at.gt(now)),
+            )
+            .filter(users::is_active.eq(true))
+            .select((api_tokens::id, User::as_select()))
+            .first::<(i32, User)>(conn)
+            .optional()?;
+
+        let Some((token_id, user)) = found else {
+            return Ok(None);
+        };
+
+        diesel::update(api_tokens::table.find(token_id))
+            .set(api_tokens::last_used_at.eq(now))
+            .execute(conn)?;
+
+        Ok(Some(user))
+    }
+}
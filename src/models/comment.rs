@@ -0,0 +1,71 @@
+use crate::db::schema::comments;
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = comments)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct Comment {
+    pub id: i32,
+    pub issue_id: i32,
+    pub author_id: i32,
+    pub parent_id: Option<i32>,
+    pub body: String,
+    pub created_at: DateTime<Utc>,
+    pub edited_at: Option<DateTime<Utc>>,
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = comments)]
+pub struct NewComment<'a> {
+    pub issue_id: i32,
+    pub author_id: i32,
+    pub parent_id: Option<i32>,
+    pub body: &'a str,
+}
+
+impl Comment {
+    pub fn is_deleted(&self) -> bool {
+        self.deleted_at.is_some()
+    }
+
+    pub fn create(conn: &mut PgConnection, new_comment: &NewComment) -> QueryResult<Comment> {
+        diesel::insert_into(comments::table)
+            .values(new_comment)
+            .returning(Comment::as_returning())
+            .get_result(conn)
+    }
+
+    /// Every comment on the issue, deleted ones included, oldest first.
+    pub fn list_for_issue(conn: &mut PgConnection, issue_id: i32) -> QueryResult<Vec<Comment>> {
+        comments::table
+            .filter(comments::issue_id.eq(issue_id))
+            .order((comments::created_at.asc(), comments::id.asc()))
+            .select(Comment::as_select())
+            .load(conn)
+    }
+
+    pub fn find_by_id(conn: &mut PgConnection, comment_id: i32) -> QueryResult<Option<Comment>> {
+        comments::table
+            .find(comment_id)
+            .select(Comment::as_select())
+            .first(conn)
+            .optional()
+    }
+
+    pub fn edit(&self, conn: &mut PgConnection, body: &str) -> QueryResult<Comment> {
+        diesel::update(comments::table.find(self.id))
+            .set((comments::body.eq(body), comments::edited_at.eq(Utc::now())))
+            .returning(Comment::as_returning())
+            .get_result(conn)
+    }
+
+    /// Marks the comment deleted; the row stays so its replies keep a parent.
+    pub fn soft_delete(&self, conn: &mut PgConnection) -> QueryResult<Comment> {
+        diesel::update(comments::table.find(self.id))
+            .set(comments::deleted_at.eq(Utc::now()))
+            .returning(Comment::as_returning())
+            .get_result(conn)
+    }
+}
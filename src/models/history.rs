@@ -0,0 +1,108 @@
+use crate::db::schema::audit_log;
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+
+pub const ENTITY_ISSUE: &str = "issue";
+pub const ENTITY_PROJECT: &str = "project";
+
+/// One changed field of an issue or project. Entries are append-only; the
+/// database rejects updates and deletes.
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = audit_log)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct HistoryEntry {
+    pub id: i64,
+    pub entity_type: String,
+    pub entity_id: i32,
+    pub field: String,
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+    pub actor_id: i32,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Insertable)]
+#[diesel(table_name = audit_log)]
+pub struct NewHistoryEntry {
+    pub entity_type: &'static str,
+    pub entity_id: i32,
+    pub field: &'static str,
+    pub old_value: Option<String>,
+    pub new_value: Option<String>,
+    pub actor_id: i32,
+}
+
+/// Collects the fields that actually change in an update.
+pub struct ChangeSet {
+    entity_type: &'static str,
+    entity_id: i32,
+    actor_id: i32,
+    entries: Vec<NewHistoryEntry>,
+}
+
+impl ChangeSet {
+    pub fn new(entity_type: &'static str, entity_id: i32, actor_id: i32) -> Self {
+        ChangeSet {
+            entity_type,
+            entity_id,
+            actor_id,
+            entries: Vec::new(),
+        }
+    }
+
+    /// Records `field` if `new` is set and differs from `old`.
+    pub fn track<T: PartialEq + ToString>(
+        &mut self,
+        field: &'static str,
+        old: &T,
+        new: Option<&T>,
+    ) {
+        self.track_optional(field, Some(old), new.map(Some));
+    }
+
+    /// As `track`, for nullable fields.
+    pub fn track_optional<T: PartialEq + ToString>(
+        &mut self,
+        field: &'static str,
+        old: Option<&T>,
+        new: Option<Option<&T>>,
+    ) {
+        match new {
+            Some(new) if new != old => self.entries.push(NewHistoryEntry {
+                entity_type: self.entity_type,
+                entity_id: self.entity_id,
+                field,
+                old_value: old.map(ToString::to_string),
+                new_value: new.map(ToString::to_string),
+                actor_id: self.actor_id,
+            }),
+            _ => {}
+        }
+    }
+
+    pub fn save(self, conn: &mut PgConnection) -> QueryResult<usize> {
+        if self.entries.is_empty() {
+            return Ok(0);
+        }
+
+        diesel::insert_into(audit_log::table)
+            .values(&self.entries)
+            .execute(conn)
+    }
+}
+
+impl HistoryEntry {
+    /// History of one issue or project, oldest first.
+    pub fn for_entity(
+        conn: &mut PgConnection,
+        entity_type: &str,
+        entity_id: i32,
+    ) -> QueryResult<Vec<HistoryEntry>> {
+        audit_log::table
+            .filter(audit_log::entity_type.eq(entity_type))
+            .filter(audit_log::entity_id.eq(entity_id))
+            .order(audit_log::id.asc())
+            .select(HistoryEntry::as_select())
+            .load(conn)
+    }
+}
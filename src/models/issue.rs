@@ -1,5 +1,16 @@
-use crate::db::schema::issues;
+use crate::db::schema::{issue_labels, issues, labels, project_members, projects};
+use crate::models::{
+    like_pattern, rank_after, ChangeSet, Label, LabelChanges, Priority, ENTITY_ISSUE,
+};
+use chrono::{DateTime, NaiveDate, Utc};
+use diesel::pg::Pg;
 use diesel::prelude::*;
+use diesel::sql_types::Integer;
+use serde::Deserialize;
+use std::collections::HashSet;
+
+/// Namespace of the per-project advisory lock guarding issue hierarchies.
+const HIERARCHY_LOCK_SPACE: i32 = 0x6869_6572;
 
 #[derive(Queryable, Selectable)]
 #[diesel(table_name = crate::db::schema::issues)]
@@ -8,10 +19,27 @@ pub struct Issue {
     pub project_id: i32,
     pub title: String,
     pub description: String,
-    pub created_by: String,
-    pub assigned_to: Option<String>,
     pub status: String,
     pub is_open: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub created_by_id: i32,
+    pub assigned_to_id: Option<i32>,
+    /// Level of a `Priority`.
+    pub priority: i16,
+    /// Name of a `Severity`.
+    pub severity: String,
+    pub start_date: Option<NaiveDate>,
+    pub due_date: Option<NaiveDate>,
+    /// Sequence number within the project, e.g. `123` in `PROJ-123`.
+    pub number: i32,
+    pub parent_id: Option<i32>,
+    pub original_estimate_minutes: Option<i32>,
+    pub milestone_id: Option<i32>,
+    pub sprint_id: Option<i32>,
+    pub estimate_points: Option<i32>,
+    /// Position on the project's board; see `models::rank`.
+    pub rank: String,
 }
 
 #[derive(Insertable)]
@@ -20,36 +48,264 @@ pub struct NewIssue<'a> {
     pub project_id: i32,
     pub title: &'a str,
     pub description: &'a str,
-    pub created_by: &'a str,
-    pub assigned_to: Option<&'a str>,
+    pub created_by_id: i32,
+    pub assigned_to_id: Option<i32>,
     pub status: &'a str,
     pub is_open: bool,
+    pub priority: i16,
+    pub severity: &'a str,
+    pub start_date: Option<NaiveDate>,
+    pub due_date: Option<NaiveDate>,
+    pub parent_id: Option<i32>,
+    pub original_estimate_minutes: Option<i32>,
+    pub milestone_id: Option<i32>,
+    pub sprint_id: Option<i32>,
+    pub estimate_points: Option<i32>,
+}
+
+/// Criteria for listing issues. Every field is optional and the set ones are
+/// combined with `AND`; an empty `statuses` list means any status and an
+/// empty `labels` list any labels.
+#[derive(Debug, Clone, Default)]
+pub struct IssueFilter {
+    pub project_id: Option<i32>,
+    /// Only issues in projects this user is a member of.
+    pub member_id: Option<i32>,
+    pub statuses: Vec<String>,
+    pub is_open: Option<bool>,
+    pub assigned_to_id: Option<i32>,
+    pub created_by_id: Option<i32>,
+    pub parent_id: Option<i32>,
+    pub milestone_id: Option<i32>,
+    pub sprint_id: Option<i32>,
+    /// Only issues in no sprint.
+    pub in_backlog: bool,
+    pub created_after: Option<DateTime<Utc>>,
+    pub created_before: Option<DateTime<Utc>>,
+    pub updated_after: Option<DateTime<Utc>>,
+    pub updated_before: Option<DateTime<Utc>>,
+    /// Issues without a due date never match the due date bounds.
+    pub due_after: Option<NaiveDate>,
+    pub due_before: Option<NaiveDate>,
+    pub title_contains: Option<String>,
+    pub labels: Vec<String>,
+    /// Require every label in `labels` rather than any of them.
+    pub match_all_labels: bool,
+}
+
+impl IssueFilter {
+    /// Ids of issues carrying any of the named labels.
+    fn with_label(
+        names: Vec<String>,
+    ) -> issue_labels::BoxedQuery<'static, Pg, diesel::sql_types::Integer> {
+        issue_labels::table
+            .filter(
+                issue_labels::label_id.eq_any(
+                    labels::table
+                        .filter(labels::name.eq_any(names))
+                        .select(labels::id),
+                ),
+            )
+            .select(issue_labels::issue_id)
+            .into_boxed()
+    }
+
+    pub fn to_query(&self) -> issues::BoxedQuery<'static, Pg> {
+        let mut query = issues::table.into_boxed();
+
+        if let Some(project_id) = self.project_id {
+            query = query.filter(issues::project_id.eq(project_id));
+        }
+        if let Some(user_id) = self.member_id {
+            query = query.filter(
+                issues::project_id.eq_any(
+                    project_members::table
+                        .filter(project_members::user_id.eq(user_id))
+                        .select(project_members::project_id),
+                ),
+            );
+        }
+        if !self.statuses.is_empty() {
+            query = query.filter(issues::status.eq_any(self.statuses.clone()));
+        }
+        if let Some(is_open) = self.is_open {
+            query = query.filter(issues::is_open.eq(is_open));
+        }
+        if let Some(assigned_to_id) = self.assigned_to_id {
+            query = query.filter(issues::assigned_to_id.eq(assigned_to_id));
+        }
+        if let Some(created_by_id) = self.created_by_id {
+            query = query.filter(issues::created_by_id.eq(created_by_id));
+        }
+        if let Some(parent_id) = self.parent_id {
+            query = query.filter(issues::parent_id.eq(parent_id));
+        }
+        if let Some(milestone_id) = self.milestone_id {
+            query = query.filter(issues::milestone_id.eq(milestone_id));
+        }
+        if let Some(sprint_id) = self.sprint_id {
+            query = query.filter(issues::sprint_id.eq(sprint_id));
+        }
+        if self.in_backlog {
+            query = query.filter(issues::sprint_id.is_null());
+        }
+        if let Some(after) = self.created_after {
+            query = query.filter(issues::created_at.ge(after));
+        }
+        if let Some(before) = self.created_before {
+            query = query.filter(issues::created_at.lt(before));
+        }
+        if let Some(after) = self.updated_after {
+            query = query.filter(issues::updated_at.ge(after));
+        }
+        if let Some(before) = self.updated_before {
+            query = query.filter(issues::updated_at.lt(before));
+        }
+        if let Some(after) = self.due_after {
+            query = query.filter(issues::due_date.ge(after));
+        }
+        if let Some(before) = self.due_before {
+            query = query.filter(issues::due_date.lt(before));
+        }
+        if let Some(fragment) = &self.title_contains {
+            query = query.filter(issues::title.ilike(like_pattern(fragment)));
+        }
+        if self.match_all_labels {
+            for name in &self.labels {
+                query = query.filter(issues::id.eq_any(Self::with_label(vec![name.clone()])));
+            }
+        } else if !self.labels.is_empty() {
+            query = query.filter(issues::id.eq_any(Self::with_label(self.labels.clone())));
+        }
+
+        query
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IssueSortField {
+    #[default]
+    CreatedAt,
+    UpdatedAt,
+    Title,
+    /// Most urgent first, then oldest first.
+    Priority,
+}
+
+/// Sort value and id of the last issue on the previous page.
+#[derive(Debug, Clone)]
+pub enum IssueKeyset {
+    CreatedAt(DateTime<Utc>, i32),
+    UpdatedAt(DateTime<Utc>, i32),
+    Title(String, i32),
+    Priority(i16, DateTime<Utc>, i32),
+}
+
+#[derive(Debug, Default)]
+pub struct IssueListQuery {
+    pub filter: IssueFilter,
+    pub sort: IssueSortField,
+    pub descending: bool,
+    pub after: Option<IssueKeyset>,
+    pub limit: i64,
 }
 
 impl Issue {
-    pub fn create(
+    /// Inserts the issue under the project's next number. Bumping the
+    /// counter locks the project row, so concurrent inserts never share a
+    /// number.
+    pub fn create(conn: &mut PgConnection, new_issue: &NewIssue) -> QueryResult<Issue> {
+        conn.transaction(|conn| {
+            let next: i32 = diesel::update(projects::table.find(new_issue.project_id))
+                .set(projects::next_issue_number.eq(projects::next_issue_number + 1))
+                .returning(projects::next_issue_number)
+                .get_result(conn)?;
+            // The project row stays locked until commit, so the new rank is
+            // unique
+            let rank = rank_after(Self::last_rank(conn, new_issue.project_id)?.as_deref());
+
+            diesel::insert_into(issues::table)
+                .values((
+                    new_issue,
+                    issues::number.eq(next - 1),
+                    issues::rank.eq(rank),
+                ))
+                .returning(Issue::as_returning())
+                .get_result(conn)
+        })
+    }
+
+    /// Serializes rank changes in the project with issue creation, which
+    /// holds the same lock.
+    pub fn lock_board(conn: &mut PgConnection, proj_id: i32) -> QueryResult<()> {
+        projects::table
+            .find(proj_id)
+            .for_update()
+            .select(projects::id)
+            .first::<i32>(conn)
+            .map(|_| ())
+    }
+
+    /// The highest rank in the project, if it has any issues.
+    pub fn last_rank(conn: &mut PgConnection, proj_id: i32) -> QueryResult<Option<String>> {
+        issues::table
+            .filter(issues::project_id.eq(proj_id))
+            .select(diesel::dsl::max(issues::rank))
+            .first(conn)
+    }
+
+    /// The project's ranks directly before and after `rank`, skipping the
+    /// issue `except`.
+    pub fn rank_neighbours(
         conn: &mut PgConnection,
-        project_id: i32,
-        title: &str,
-        description: &str,
-        created_by: &str,
-        assigned_to: Option<&str>,
-        status: &str,
-    ) -> QueryResult<Issue> {
-        let new_issue = NewIssue {
-            project_id,
-            title,
-            description,
-            created_by,
-            assigned_to,
-            status,
-            is_open: true,
-        };
+        proj_id: i32,
+        rank: &str,
+        except: i32,
+    ) -> QueryResult<(Option<String>, Option<String>)> {
+        let others = issues::table
+            .filter(issues::project_id.eq(proj_id))
+            .filter(issues::id.ne(except));
+        let before = others
+            .filter(issues::rank.lt(rank))
+            .select(diesel::dsl::max(issues::rank))
+            .first(conn)?;
+        let after = others
+            .filter(issues::rank.gt(rank))
+            .select(diesel::dsl::min(issues::rank))
+            .first(conn)?;
+
+        Ok((before, after))
+    }
+
+    /// Issues matching `filter` in board order.
+    pub fn board(conn: &mut PgConnection, filter: &IssueFilter) -> QueryResult<Vec<Issue>> {
+        filter
+            .to_query()
+            .order(issues::rank.asc())
+            .select(Issue::as_select())
+            .load(conn)
+    }
 
-        diesel::insert_into(issues::table)
-            .values(&new_issue)
-            .returning(Issue::as_returning())
-            .get_result(conn)
+    pub fn find_many(conn: &mut PgConnection, issue_ids: &[i32]) -> QueryResult<Vec<Issue>> {
+        issues::table
+            .filter(issues::id.eq_any(issue_ids))
+            .order(issues::id.asc())
+            .select(Issue::as_select())
+            .load(conn)
+    }
+
+    pub fn find_by_number(
+        conn: &mut PgConnection,
+        proj_id: i32,
+        number: i32,
+    ) -> QueryResult<Option<Issue>> {
+        issues::table
+            .filter(issues::project_id.eq(proj_id))
+            .filter(issues::number.eq(number))
+            .select(Issue::as_select())
+            .first(conn)
+            .optional()
     }
 
     pub fn get_by_project(conn: &mut PgConnection, proj_id: i32) -> QueryResult<Vec<Issue>> {
@@ -59,6 +315,206 @@ impl Issue {
             .load(conn)
     }
 
+    pub fn count(conn: &mut PgConnection, filter: &IssueFilter) -> QueryResult<i64> {
+        filter.to_query().count().get_result(conn)
+    }
+
+    pub fn list(conn: &mut PgConnection, params: &IssueListQuery) -> QueryResult<Vec<Issue>> {
+        let mut query = params.filter.to_query();
+
+        query = match (params.after.clone(), params.descending) {
+            (None, _) => query,
+            (Some(IssueKeyset::CreatedAt(value, last_id)), false) => query.filter(
+                issues::created_at
+                    .gt(value)
+                    .or(issues::created_at.eq(value).and(issues::id.gt(last_id))),
+            ),
+            (Some(IssueKeyset::CreatedAt(value, last_id)), true) => query.filter(
+                issues::created_at
+                    .lt(value)
+                    .or(issues::created_at.eq(value).and(issues::id.lt(last_id))),
+            ),
+            (Some(IssueKeyset::UpdatedAt(value, last_id)), false) => query.filter(
+                issues::updated_at
+                    .gt(value)
+                    .or(issues::updated_at.eq(value).and(issues::id.gt(last_id))),
+            ),
+            (Some(IssueKeyset::UpdatedAt(value, last_id)), true) => query.filter(
+                issues::updated_at
+                    .lt(value)
+                    .or(issues::updated_at.eq(value).and(issues::id.lt(last_id))),
+            ),
+            (Some(IssueKeyset::Title(value, last_id)), false) => query.filter(
+                issues::title
+                    .gt(value.clone())
+                    .or(issues::title.eq(value).and(issues::id.gt(last_id))),
+            ),
+            (Some(IssueKeyset::Title(value, last_id)), true) => query.filter(
+                issues::title
+                    .lt(value.clone())
+                    .or(issues::title.eq(value).and(issues::id.lt(last_id))),
+            ),
+            (Some(IssueKeyset::Priority(level, created, last_id)), false) => query.filter(
+                issues::priority
+                    .gt(level)
+                    .or(issues::priority.eq(level).and(
+                        issues::created_at
+                            .gt(created)
+                            .or(issues::created_at.eq(created).and(issues::id.gt(last_id))),
+                    )),
+            ),
+            (Some(IssueKeyset::Priority(level, created, last_id)), true) => query.filter(
+                issues::priority
+                    .lt(level)
+                    .or(issues::priority.eq(level).and(
+                        issues::created_at
+                            .lt(created)
+                            .or(issues::created_at.eq(created).and(issues::id.lt(last_id))),
+                    )),
+            ),
+        };
+
+        query = match (params.sort, params.descending) {
+            (IssueSortField::CreatedAt, false) => {
+                query.order((issues::created_at.asc(), issues::id.asc()))
+            }
+            (IssueSortField::CreatedAt, true) => {
+                query.order((issues::created_at.desc(), issues::id.desc()))
+            }
+            (IssueSortField::UpdatedAt, false) => {
+                query.order((issues::updated_at.asc(), issues::id.asc()))
+            }
+            (IssueSortField::UpdatedAt, true) => {
+                query.order((issues::updated_at.desc(), issues::id.desc()))
+            }
+            (IssueSortField::Title, false) => query.order((issues::title.asc(), issues::id.asc())),
+            (IssueSortField::Title, true) => query.order((issues::title.desc(), issues::id.desc())),
+            (IssueSortField::Priority, false) => query.order((
+                issues::priority.asc(),
+                issues::created_at.asc(),
+                issues::id.asc(),
+            )),
+            (IssueSortField::Priority, true) => query.order((
+                issues::priority.desc(),
+                issues::created_at.desc(),
+                issues::id.desc(),
+            )),
+        };
+
+        query
+            .limit(params.limit)
+            .select(Issue::as_select())
+            .load(conn)
+    }
+
+    /// Open issues matching `filter` that were due before `today`, most
+    /// overdue first.
+    pub fn overdue(
+        conn: &mut PgConnection,
+        filter: &IssueFilter,
+        today: NaiveDate,
+    ) -> QueryResult<Vec<Issue>> {
+        filter
+            .to_query()
+            .filter(issues::is_open.eq(true))
+            .filter(issues::due_date.lt(today))
+            .order((
+                issues::due_date.asc(),
+                issues::priority.asc(),
+                issues::id.asc(),
+            ))
+            .select(Issue::as_select())
+            .load(conn)
+    }
+
+    /// Holds the project's hierarchy lock until the surrounding transaction
+    /// ends, so concurrent re-parenting cannot form a cycle.
+    pub fn lock_hierarchy(conn: &mut PgConnection, proj_id: i32) -> QueryResult<()> {
+        diesel::sql_query("SELECT pg_advisory_xact_lock($1, $2)")
+            .bind::<Integer, _>(HIERARCHY_LOCK_SPACE)
+            .bind::<Integer, _>(proj_id)
+            .execute(conn)
+            .map(|_| ())
+    }
+
+    /// Ids of the issue's parent, grandparent and so on up to the top.
+    pub fn ancestor_ids(conn: &mut PgConnection, issue_id: i32) -> QueryResult<Vec<i32>> {
+        let mut ancestors = Vec::new();
+        let mut current = issue_id;
+        while let Some(parent_id) = issues::table
+            .find(current)
+            .select(issues::parent_id)
+            .first::<Option<i32>>(conn)?
+        {
+            if parent_id == issue_id || ancestors.contains(&parent_id) {
+                break;
+            }
+            ancestors.push(parent_id);
+            current = parent_id;
+        }
+        Ok(ancestors)
+    }
+
+    /// Every issue below this one, level by level.
+    pub fn descendants(conn: &mut PgConnection, issue_id: i32) -> QueryResult<Vec<Issue>> {
+        let mut seen = HashSet::from([issue_id]);
+        let mut frontier = vec![issue_id];
+        let mut found = Vec::new();
+
+        while !frontier.is_empty() {
+            let children: Vec<Issue> = issues::table
+                .filter(issues::parent_id.eq_any(&frontier))
+                .order(issues::id.asc())
+                .select(Issue::as_select())
+                .load(conn)?;
+
+            frontier = Vec::new();
+            for child in children {
+                if seen.insert(child.id) {
+                    frontier.push(child.id);
+                    found.push(child);
+                }
+            }
+        }
+
+        Ok(found)
+    }
+
+    /// Direct children of the issue that are still open.
+    pub fn open_children(conn: &mut PgConnection, issue_id: i32) -> QueryResult<Vec<i32>> {
+        issues::table
+            .filter(issues::parent_id.eq(issue_id))
+            .filter(issues::is_open.eq(true))
+            .order(issues::id.asc())
+            .select(issues::id)
+            .load(conn)
+    }
+
+    /// Number of issues in a project per `(status, is_open)` pair.
+    pub fn status_counts(
+        conn: &mut PgConnection,
+        proj_id: i32,
+    ) -> QueryResult<Vec<(String, bool, i64)>> {
+        issues::table
+            .filter(issues::project_id.eq(proj_id))
+            .group_by((issues::status, issues::is_open))
+            .select((issues::status, issues::is_open, diesel::dsl::count_star()))
+            .load(conn)
+    }
+
+    /// Number of open issues in a project per priority level.
+    pub fn open_priority_counts(
+        conn: &mut PgConnection,
+        proj_id: i32,
+    ) -> QueryResult<Vec<(i16, i64)>> {
+        issues::table
+            .filter(issues::project_id.eq(proj_id))
+            .filter(issues::is_open.eq(true))
+            .group_by(issues::priority)
+            .select((issues::priority, diesel::dsl::count_star()))
+            .load(conn)
+    }
+
     pub fn get_by_id(conn: &mut PgConnection, issue_id: i32) -> QueryResult<Issue> {
         issues::table
             .find(issue_id)
@@ -66,11 +522,105 @@ impl Issue {
             .first(conn)
     }
 
-    pub fn update(&self, conn: &mut PgConnection, changes: IssueChanges) -> QueryResult<Issue> {
-        diesel::update(issues::table.find(self.id))
-            .set(changes)
-            .returning(Issue::as_returning())
-            .get_result(conn)
+    /// Applies `changes` and `label_changes` and records every field that
+    /// actually changed in the audit log, attributed to `actor_id`, in one
+    /// transaction.
+    pub fn update(
+        &self,
+        conn: &mut PgConnection,
+        changes: IssueChanges,
+        label_changes: &LabelChanges,
+        actor_id: i32,
+    ) -> QueryResult<Issue> {
+        conn.transaction(|conn| {
+            let current = issues::table
+                .find(self.id)
+                .for_update()
+                .select(Issue::as_select())
+                .first(conn)?;
+
+            let mut history = ChangeSet::new(ENTITY_ISSUE, self.id, actor_id);
+            history.track("title", &current.title, changes.title.as_ref());
+            history.track(
+                "description",
+                &current.description,
+                changes.description.as_ref(),
+            );
+            history.track_optional(
+                "assigned_to_id",
+                current.assigned_to_id.as_ref(),
+                changes.assigned_to_id.as_ref().map(Option::as_ref),
+            );
+            history.track("status", &current.status, changes.status.as_ref());
+            history.track("is_open", &current.is_open, changes.is_open.as_ref());
+            history.track(
+                "priority",
+                &Priority::from_level(current.priority).unwrap_or_default(),
+                changes.priority.and_then(Priority::from_level).as_ref(),
+            );
+            history.track("severity", &current.severity, changes.severity.as_ref());
+            history.track_optional(
+                "start_date",
+                current.start_date.as_ref(),
+                changes.start_date.as_ref().map(Option::as_ref),
+            );
+            history.track_optional(
+                "due_date",
+                current.due_date.as_ref(),
+                changes.due_date.as_ref().map(Option::as_ref),
+            );
+            history.track_optional(
+                "parent_id",
+                current.parent_id.as_ref(),
+                changes.parent_id.as_ref().map(Option::as_ref),
+            );
+            history.track_optional(
+                "original_estimate_minutes",
+                current.original_estimate_minutes.as_ref(),
+                changes
+                    .original_estimate_minutes
+                    .as_ref()
+                    .map(Option::as_ref),
+            );
+            history.track_optional(
+                "milestone_id",
+                current.milestone_id.as_ref(),
+                changes.milestone_id.as_ref().map(Option::as_ref),
+            );
+            history.track_optional(
+                "sprint_id",
+                current.sprint_id.as_ref(),
+                changes.sprint_id.as_ref().map(Option::as_ref),
+            );
+            history.track_optional(
+                "estimate_points",
+                current.estimate_points.as_ref(),
+                changes.estimate_points.as_ref().map(Option::as_ref),
+            );
+
+            if !label_changes.is_empty() {
+                let before = Label::names_for_issue(conn, self.id)?.join(",");
+                Label::apply(conn, self.id, label_changes)?;
+                let after = Label::names_for_issue(conn, self.id)?.join(",");
+                history.track("labels", &before, Some(&after));
+            }
+
+            let updated = if changes.is_empty() {
+                // Label-only edits still count as an update of the issue
+                diesel::update(issues::table.find(self.id))
+                    .set(issues::updated_at.eq(Utc::now()))
+                    .returning(Issue::as_returning())
+                    .get_result(conn)?
+            } else {
+                diesel::update(issues::table.find(self.id))
+                    .set(changes)
+                    .returning(Issue::as_returning())
+                    .get_result(conn)?
+            };
+            history.save(conn)?;
+
+            Ok(updated)
+        })
     }
 
     pub fn delete(conn: &mut PgConnection, issue_id: i32) -> QueryResult<bool> {
@@ -80,12 +630,43 @@ impl Issue {
     }
 }
 
-#[derive(AsChangeset)]
+#[derive(Default, AsChangeset)]
 #[diesel(table_name = issues)]
 pub struct IssueChanges {
     pub title: Option<String>,
     pub description: Option<String>,
-    pub assigned_to: Option<Option<String>>,
+    pub assigned_to_id: Option<Option<i32>>,
     pub status: Option<String>,
     pub is_open: Option<bool>,
+    pub priority: Option<i16>,
+    pub severity: Option<String>,
+    pub start_date: Option<Option<NaiveDate>>,
+    pub due_date: Option<Option<NaiveDate>>,
+    pub parent_id: Option<Option<i32>>,
+    pub original_estimate_minutes: Option<Option<i32>>,
+    pub milestone_id: Option<Option<i32>>,
+    pub sprint_id: Option<Option<i32>>,
+    pub estimate_points: Option<Option<i32>>,
+    /// Board position; not recorded in the history.
+    pub rank: Option<String>,
+}
+
+impl IssueChanges {
+    pub fn is_empty(&self) -> bool {
+        self.title.is_none()
+            && self.description.is_none()
+            && self.assigned_to_id.is_none()
+            && self.status.is_none()
+            && self.is_open.is_none()
+            && self.priority.is_none()
+            && self.severity.is_none()
+            && self.start_date.is_none()
+            && self.due_date.is_none()
+            && self.parent_id.is_none()
+            && self.original_estimate_minutes.is_none()
+            && self.milestone_id.is_none()
+            && self.sprint_id.is_none()
+            && self.estimate_points.is_none()
+            && self.rank.is_none()
+    }
 }
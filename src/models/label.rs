@@ -0,0 +1,160 @@
+use crate::db::schema::{issue_labels, labels};
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = labels)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct Label {
+    pub id: i32,
+    pub project_id: i32,
+    pub name: String,
+    pub color: String,
+    pub description: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = labels)]
+pub struct NewLabel<'a> {
+    pub project_id: i32,
+    pub name: &'a str,
+    pub color: Option<&'a str>,
+    pub description: &'a str,
+}
+
+#[derive(Debug, Deserialize, AsChangeset)]
+#[diesel(table_name = labels)]
+pub struct UpdateLabel {
+    pub name: Option<String>,
+    pub color: Option<String>,
+    pub description: Option<String>,
+}
+
+/// Labels to attach to and detach from an issue, by id.
+#[derive(Debug, Clone, Default)]
+pub struct LabelChanges {
+    pub attach: Vec<i32>,
+    pub detach: Vec<i32>,
+}
+
+impl LabelChanges {
+    pub fn is_empty(&self) -> bool {
+        self.attach.is_empty() && self.detach.is_empty()
+    }
+}
+
+/// Whether a color is a `#rrggbb` hex string, as the database requires.
+pub fn is_valid_color(color: &str) -> bool {
+    color.len() == 7 && color.starts_with('#') && color[1..].chars().all(|c| c.is_ascii_hexdigit())
+}
+
+impl Label {
+    pub fn create(conn: &mut PgConnection, new_label: &NewLabel) -> QueryResult<Label> {
+        diesel::insert_into(labels::table)
+            .values(new_label)
+            .returning(Label::as_returning())
+            .get_result(conn)
+    }
+
+    pub fn list(conn: &mut PgConnection, project_id: i32) -> QueryResult<Vec<Label>> {
+        labels::table
+            .filter(labels::project_id.eq(project_id))
+            .order(labels::name.asc())
+            .select(Label::as_select())
+            .load(conn)
+    }
+
+    pub fn find_by_id(conn: &mut PgConnection, label_id: i32) -> QueryResult<Option<Label>> {
+        labels::table
+            .find(label_id)
+            .select(Label::as_select())
+            .first(conn)
+            .optional()
+    }
+
+    pub fn update(
+        conn: &mut PgConnection,
+        label_id: i32,
+        changes: &UpdateLabel,
+    ) -> QueryResult<Label> {
+        diesel::update(labels::table.find(label_id))
+            .set(changes)
+            .returning(Label::as_returning())
+            .get_result(conn)
+    }
+
+    /// Deletes the label; the database detaches it from every issue.
+    pub fn delete(conn: &mut PgConnection, label_id: i32) -> QueryResult<bool> {
+        let count = diesel::delete(labels::table.find(label_id)).execute(conn)?;
+
+        Ok(count > 0)
+    }
+
+    /// Labels of each of `issue_ids`, by name. Issues without labels are absent.
+    pub fn for_issues(
+        conn: &mut PgConnection,
+        issue_ids: &[i32],
+    ) -> QueryResult<HashMap<i32, Vec<Label>>> {
+        let rows: Vec<(i32, Label)> = issue_labels::table
+            .inner_join(labels::table)
+            .filter(issue_labels::issue_id.eq_any(issue_ids))
+            .order(labels::name.asc())
+            .select((issue_labels::issue_id, Label::as_select()))
+            .load(conn)?;
+
+        let mut by_issue: HashMap<i32, Vec<Label>> = HashMap::new();
+        for (issue_id, label) in rows {
+            by_issue.entry(issue_id).or_default().push(label);
+        }
+        Ok(by_issue)
+    }
+
+    /// Label names attached to the issue, sorted.
+    pub fn names_for_issue(conn: &mut PgConnection, issue_id: i32) -> QueryResult<Vec<String>> {
+        issue_labels::table
+            .inner_join(labels::table)
+            .filter(issue_labels::issue_id.eq(issue_id))
+            .order(labels::name.asc())
+            .select(labels::name)
+            .load(conn)
+    }
+
+    /// Applies `changes` to the issue's labels. Attaching a label twice is a
+    /// no-op.
+    pub fn apply(
+        conn: &mut PgConnection,
+        issue_id: i32,
+        changes: &LabelChanges,
+    ) -> QueryResult<()> {
+        if !changes.detach.is_empty() {
+            diesel::delete(
+                issue_labels::table
+                    .filter(issue_labels::issue_id.eq(issue_id))
+                    .filter(issue_labels::label_id.eq_any(&changes.detach)),
+            )
+            .execute(conn)?;
+        }
+
+        let rows: Vec<_> = changes
+            .attach
+            .iter()
+            .map(|label_id| {
+                (
+                    issue_labels::issue_id.eq(issue_id),
+                    issue_labels::label_id.eq(*label_id),
+                )
+            })
+            .collect();
+        if !rows.is_empty() {
+            diesel::insert_into(issue_labels::table)
+                .values(&rows)
+                .on_conflict_do_nothing()
+                .execute(conn)?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,230 @@
+use crate::db::schema::issue_links;
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use diesel::sql_types::BigInt;
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+
+/// Serializes writers of `blocks` links so concurrent inserts cannot close a
+/// cycle the other one did not see.
+const BLOCKING_LOCK_KEY: i64 = 0x6973_7375_655f_6c6b;
+
+/// A relation as seen from one of its issues. Only `Blocks`, `Duplicates`,
+/// `RelatesTo` and `Clones` are stored; the others are their inverses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LinkType {
+    Blocks,
+    BlockedBy,
+    Duplicates,
+    DuplicatedBy,
+    RelatesTo,
+    Clones,
+    ClonedBy,
+}
+
+impl LinkType {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            LinkType::Blocks => "blocks",
+            LinkType::BlockedBy => "blocked_by",
+            LinkType::Duplicates => "duplicates",
+            LinkType::DuplicatedBy => "duplicated_by",
+            LinkType::RelatesTo => "relates_to",
+            LinkType::Clones => "clones",
+            LinkType::ClonedBy => "cloned_by",
+        }
+    }
+
+    /// The same relation seen from the other issue.
+    pub fn inverse(self) -> LinkType {
+        match self {
+            LinkType::Blocks => LinkType::BlockedBy,
+            LinkType::BlockedBy => LinkType::Blocks,
+            LinkType::Duplicates => LinkType::DuplicatedBy,
+            LinkType::DuplicatedBy => LinkType::Duplicates,
+            LinkType::RelatesTo => LinkType::RelatesTo,
+            LinkType::Clones => LinkType::ClonedBy,
+            LinkType::ClonedBy => LinkType::Clones,
+        }
+    }
+
+    /// Whether this is the stored direction of the relation.
+    pub fn is_stored(self) -> bool {
+        matches!(
+            self,
+            LinkType::Blocks | LinkType::Duplicates | LinkType::RelatesTo | LinkType::Clones
+        )
+    }
+
+    fn parse_stored(value: &str) -> Option<LinkType> {
+        [
+            LinkType::Blocks,
+            LinkType::Duplicates,
+            LinkType::RelatesTo,
+            LinkType::Clones,
+        ]
+        .into_iter()
+        .find(|link_type| link_type.as_str() == value)
+    }
+}
+
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = issue_links)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct IssueLink {
+    pub id: i32,
+    pub source_id: i32,
+    pub target_id: i32,
+    pub link_type: String,
+    pub created_by_id: i32,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = issue_links)]
+pub struct NewIssueLink<'a> {
+    pub source_id: i32,
+    pub target_id: i32,
+    /// A stored `LinkType`.
+    pub link_type: &'a str,
+    pub created_by_id: i32,
+}
+
+impl IssueLink {
+    /// The relation and the issue at the other end, from `issue_id`'s side.
+    pub fn view_from(&self, issue_id: i32) -> (LinkType, i32) {
+        let link_type = LinkType::parse_stored(&self.link_type).unwrap_or(LinkType::RelatesTo);
+        if self.source_id == issue_id {
+            (link_type, self.target_id)
+        } else {
+            (link_type.inverse(), self.source_id)
+        }
+    }
+
+    pub fn create(conn: &mut PgConnection, new_link: &NewIssueLink) -> QueryResult<IssueLink> {
+        diesel::insert_into(issue_links::table)
+            .values(new_link)
+            .returning(IssueLink::as_returning())
+            .get_result(conn)
+    }
+
+    /// Links in either direction between the two issues.
+    pub fn between(conn: &mut PgConnection, a: i32, b: i32) -> QueryResult<Vec<IssueLink>> {
+        issue_links::table
+            .filter(
+                issue_links::source_id
+                    .eq(a)
+                    .and(issue_links::target_id.eq(b))
+                    .or(issue_links::source_id
+                        .eq(b)
+                        .and(issue_links::target_id.eq(a))),
+            )
+            .select(IssueLink::as_select())
+            .load(conn)
+    }
+
+    /// Every link of the issue, in either direction, oldest first.
+    pub fn for_issue(conn: &mut PgConnection, issue_id: i32) -> QueryResult<Vec<IssueLink>> {
+        issue_links::table
+            .filter(
+                issue_links::source_id
+                    .eq(issue_id)
+                    .or(issue_links::target_id.eq(issue_id)),
+            )
+            .order(issue_links::id.asc())
+            .select(IssueLink::as_select())
+            .load(conn)
+    }
+
+    pub fn find_by_id(conn: &mut PgConnection, link_id: i32) -> QueryResult<Option<IssueLink>> {
+        issue_links::table
+            .find(link_id)
+            .select(IssueLink::as_select())
+            .first(conn)
+            .optional()
+    }
+
+    pub fn delete(conn: &mut PgConnection, link_id: i32) -> QueryResult<bool> {
+        let count = diesel::delete(issue_links::table.find(link_id)).execute(conn)?;
+
+        Ok(count > 0)
+    }
+
+    /// Holds the blocking-graph lock until the surrounding transaction ends.
+    pub fn lock_blocking_graph(conn: &mut PgConnection) -> QueryResult<()> {
+        diesel::sql_query("SELECT pg_advisory_xact_lock($1)")
+            .bind::<BigInt, _>(BLOCKING_LOCK_KEY)
+            .execute(conn)
+            .map(|_| ())
+    }
+
+    /// Every `blocks` link reachable from `issue_id`, following links
+    /// towards the issues it blocks (`downstream`) or towards its blockers.
+    /// Links come out in breadth-first order.
+    pub fn blocking_graph(
+        conn: &mut PgConnection,
+        issue_id: i32,
+        downstream: bool,
+    ) -> QueryResult<Vec<IssueLink>> {
+        let mut seen = HashSet::from([issue_id]);
+        let mut frontier = vec![issue_id];
+        let mut graph = Vec::new();
+
+        while !frontier.is_empty() {
+            let query = issue_links::table
+                .filter(issue_links::link_type.eq(LinkType::Blocks.as_str()))
+                .into_boxed();
+            let query = if downstream {
+                query.filter(issue_links::source_id.eq_any(frontier))
+            } else {
+                query.filter(issue_links::target_id.eq_any(frontier))
+            };
+            let links = query
+                .order(issue_links::id.asc())
+                .select(IssueLink::as_select())
+                .load(conn)?;
+
+            frontier = Vec::new();
+            for link in links {
+                let next = if downstream {
+                    link.target_id
+                } else {
+                    link.source_id
+                };
+                if seen.insert(next) {
+                    frontier.push(next);
+                }
+                graph.push(link);
+            }
+        }
+
+        Ok(graph)
+    }
+
+    /// Shortest chain of issues from `from` to `to` where each blocks the
+    /// next, if any.
+    pub fn blocking_path(
+        conn: &mut PgConnection,
+        from: i32,
+        to: i32,
+    ) -> QueryResult<Option<Vec<i32>>> {
+        let mut reached_from = HashMap::new();
+        for link in Self::blocking_graph(conn, from, true)? {
+            reached_from.entry(link.target_id).or_insert(link.source_id);
+        }
+        if !reached_from.contains_key(&to) {
+            return Ok(None);
+        }
+
+        let mut path = vec![to];
+        let mut current = to;
+        while current != from {
+            current = reached_from[&current];
+            path.push(current);
+        }
+        path.reverse();
+
+        Ok(Some(path))
+    }
+}
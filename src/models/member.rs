@@ -0,0 +1,126 @@
+use crate::db::schema::{project_members, users};
+use crate::models::User;
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Project roles, from least to most privileged; each role can do
+/// everything the ones before it can.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProjectRole {
+    Viewer,
+    Reporter,
+    Developer,
+    Maintainer,
+    Admin,
+}
+
+impl ProjectRole {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ProjectRole::Viewer => "viewer",
+            ProjectRole::Reporter => "reporter",
+            ProjectRole::Developer => "developer",
+            ProjectRole::Maintainer => "maintainer",
+            ProjectRole::Admin => "admin",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<ProjectRole> {
+        match value {
+            "viewer" => Some(ProjectRole::Viewer),
+            "reporter" => Some(ProjectRole::Reporter),
+            "developer" => Some(ProjectRole::Developer),
+            "maintainer" => Some(ProjectRole::Maintainer),
+            "admin" => Some(ProjectRole::Admin),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for ProjectRole {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = project_members)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct ProjectMember {
+    pub project_id: i32,
+    pub user_id: i32,
+    pub role: String,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = project_members)]
+struct NewProjectMember<'a> {
+    project_id: i32,
+    user_id: i32,
+    role: &'a str,
+}
+
+impl ProjectMember {
+    pub fn role(&self) -> ProjectRole {
+        // The column is constrained to the known roles
+        ProjectRole::parse(&self.role).unwrap_or(ProjectRole::Viewer)
+    }
+
+    pub fn role_of(
+        conn: &mut PgConnection,
+        project_id: i32,
+        user_id: i32,
+    ) -> QueryResult<Option<ProjectRole>> {
+        let role: Option<String> = project_members::table
+            .find((project_id, user_id))
+            .select(project_members::role)
+            .first(conn)
+            .optional()?;
+
+        Ok(role.as_deref().and_then(ProjectRole::parse))
+    }
+
+    /// Members of the project with their users, by username.
+    pub fn list(
+        conn: &mut PgConnection,
+        project_id: i32,
+    ) -> QueryResult<Vec<(ProjectMember, User)>> {
+        project_members::table
+            .inner_join(users::table)
+            .filter(project_members::project_id.eq(project_id))
+            .order(users::username.asc())
+            .select((ProjectMember::as_select(), User::as_select()))
+            .load(conn)
+    }
+
+    /// Adds the user to the project, or changes their role if already a member.
+    pub fn upsert(
+        conn: &mut PgConnection,
+        project_id: i32,
+        user_id: i32,
+        role: ProjectRole,
+    ) -> QueryResult<ProjectMember> {
+        diesel::insert_into(project_members::table)
+            .values(NewProjectMember {
+                project_id,
+                user_id,
+                role: role.as_str(),
+            })
+            .on_conflict((project_members::project_id, project_members::user_id))
+            .do_update()
+            .set(project_members::role.eq(role.as_str()))
+            .returning(ProjectMember::as_returning())
+            .get_result(conn)
+    }
+
+    pub fn remove(conn: &mut PgConnection, project_id: i32, user_id: i32) -> QueryResult<bool> {
+        let count =
+            diesel::delete(project_members::table.find((project_id, user_id))).execute(conn)?;
+
+        Ok(count > 0)
+    }
+}
@@ -0,0 +1,169 @@
+use crate::db::schema::{issues, milestones};
+use crate::models::{ChangeSet, ENTITY_ISSUE};
+use chrono::{DateTime, NaiveDate, Utc};
+use diesel::prelude::*;
+
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = milestones)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct Milestone {
+    pub id: i32,
+    pub project_id: i32,
+    pub name: String,
+    pub description: String,
+    pub target_date: Option<NaiveDate>,
+    pub released: bool,
+    pub released_at: Option<DateTime<Utc>>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = milestones)]
+pub struct NewMilestone<'a> {
+    pub project_id: i32,
+    pub name: &'a str,
+    pub description: &'a str,
+    pub target_date: Option<NaiveDate>,
+}
+
+#[derive(Debug, Default, AsChangeset)]
+#[diesel(table_name = milestones)]
+pub struct UpdateMilestone {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    pub target_date: Option<Option<NaiveDate>>,
+}
+
+impl UpdateMilestone {
+    pub fn is_empty(&self) -> bool {
+        self.name.is_none() && self.description.is_none() && self.target_date.is_none()
+    }
+}
+
+impl Milestone {
+    pub fn create(conn: &mut PgConnection, new_milestone: &NewMilestone) -> QueryResult<Milestone> {
+        diesel::insert_into(milestones::table)
+            .values(new_milestone)
+            .returning(Milestone::as_returning())
+            .get_result(conn)
+    }
+
+    /// Unreleased milestones first, each group by target date with undated
+    /// ones last.
+    pub fn list(conn: &mut PgConnection, project_id: i32) -> QueryResult<Vec<Milestone>> {
+        milestones::table
+            .filter(milestones::project_id.eq(project_id))
+            .order((
+                milestones::released.asc(),
+                milestones::target_date.asc().nulls_last(),
+                milestones::id.asc(),
+            ))
+            .select(Milestone::as_select())
+            .load(conn)
+    }
+
+    pub fn find_by_id(
+        conn: &mut PgConnection,
+        milestone_id: i32,
+    ) -> QueryResult<Option<Milestone>> {
+        milestones::table
+            .find(milestone_id)
+            .select(Milestone::as_select())
+            .first(conn)
+            .optional()
+    }
+
+    pub fn update(
+        conn: &mut PgConnection,
+        milestone_id: i32,
+        changes: &UpdateMilestone,
+    ) -> QueryResult<Milestone> {
+        diesel::update(milestones::table.find(milestone_id))
+            .set(changes)
+            .returning(Milestone::as_returning())
+            .get_result(conn)
+    }
+
+    /// Deletes the milestone; its issues are left without one.
+    pub fn delete(conn: &mut PgConnection, milestone_id: i32) -> QueryResult<bool> {
+        let count = diesel::delete(milestones::table.find(milestone_id)).execute(conn)?;
+
+        Ok(count > 0)
+    }
+
+    /// The unreleased milestone of the same project due soonest, other than
+    /// this one. Undated milestones come after dated ones.
+    pub fn next(&self, conn: &mut PgConnection) -> QueryResult<Option<Milestone>> {
+        milestones::table
+            .filter(milestones::project_id.eq(self.project_id))
+            .filter(milestones::released.eq(false))
+            .filter(milestones::id.ne(self.id))
+            .order((
+                milestones::target_date.asc().nulls_last(),
+                milestones::id.asc(),
+            ))
+            .select(Milestone::as_select())
+            .first(conn)
+            .optional()
+    }
+
+    /// Number of the milestone's issues per `(status, is_open)` pair.
+    pub fn status_counts(
+        conn: &mut PgConnection,
+        milestone_id: i32,
+    ) -> QueryResult<Vec<(String, bool, i64)>> {
+        issues::table
+            .filter(issues::milestone_id.eq(milestone_id))
+            .group_by((issues::status, issues::is_open))
+            .select((issues::status, issues::is_open, diesel::dsl::count_star()))
+            .load(conn)
+    }
+
+    /// Marks the milestone released and, given `move_to`, moves its open
+    /// issues there, recording the move in each issue's history. Returns
+    /// the released milestone and the ids of the moved issues, or
+    /// `NotFound` if it was already released.
+    pub fn release(
+        &self,
+        conn: &mut PgConnection,
+        move_to: Option<i32>,
+        actor_id: i32,
+    ) -> QueryResult<(Milestone, Vec<i32>)> {
+        conn.transaction(|conn| {
+            let released = diesel::update(
+                milestones::table
+                    .find(self.id)
+                    .filter(milestones::released.eq(false)),
+            )
+            .set((
+                milestones::released.eq(true),
+                milestones::released_at.eq(Utc::now()),
+            ))
+            .returning(Milestone::as_returning())
+            .get_result(conn)?;
+
+            let Some(target_id) = move_to else {
+                return Ok((released, Vec::new()));
+            };
+            let moved: Vec<i32> = diesel::update(
+                issues::table
+                    .filter(issues::milestone_id.eq(self.id))
+                    .filter(issues::is_open.eq(true)),
+            )
+            .set((
+                issues::milestone_id.eq(target_id),
+                issues::updated_at.eq(Utc::now()),
+            ))
+            .returning(issues::id)
+            .get_results(conn)?;
+
+            for issue_id in &moved {
+                let mut history = ChangeSet::new(ENTITY_ISSUE, *issue_id, actor_id);
+                history.track("milestone_id", &self.id, Some(&target_id));
+                history.save(conn)?;
+            }
+
+            Ok((released, moved))
+        })
+    }
+}
@@ -1,5 +1,52 @@
+mod api_token;
+mod comment;
+mod history;
 mod issue;
+mod label;
+mod link;
+mod member;
+mod milestone;
+mod priority;
 mod project;
+mod rank;
+mod sprint;
+mod user;
+mod webhook;
+mod workflow;
+mod worklog;
 
-pub use issue::{Issue, IssueChanges};
-pub use project::{Project, UpdateProject};
+pub use api_token::{ApiToken, NewApiToken};
+pub use comment::{Comment, NewComment};
+pub use history::{ChangeSet, HistoryEntry, NewHistoryEntry, ENTITY_ISSUE, ENTITY_PROJECT};
+pub use issue::{
+    Issue, IssueChanges, IssueFilter, IssueKeyset, IssueListQuery, IssueSortField, NewIssue,
+};
+pub use label::{is_valid_color, Label, LabelChanges, NewLabel, UpdateLabel};
+pub use link::{IssueLink, LinkType, NewIssueLink};
+pub use member::{ProjectMember, ProjectRole};
+pub use milestone::{Milestone, NewMilestone, UpdateMilestone};
+pub use priority::{Priority, Severity};
+pub use project::{Project, ProjectKeyset, ProjectListQuery, ProjectSortField, UpdateProject};
+pub use rank::{rank_after, rank_between};
+pub use sprint::{NewSprint, Sprint, SprintReport, SprintState, UpdateSprint};
+pub use user::{NewUser, UpdateUser, User};
+pub use webhook::{
+    NewWebhook, UpdateWebhook, Webhook, WebhookDelivery, DELIVERY_DELIVERED, DELIVERY_FAILED,
+    DELIVERY_PENDING, EVENT_ISSUE_CREATED, EVENT_ISSUE_DELETED, EVENT_ISSUE_UPDATED,
+    EVENT_PROJECT_UPDATED, WEBHOOK_EVENTS,
+};
+pub use workflow::{
+    StateDefinition, TransitionDefinition, Workflow, WorkflowState, WorkflowTransition,
+    CATEGORY_DONE, CATEGORY_OPEN,
+};
+pub use worklog::{EstimateTotals, NewWorklog, UpdateWorklog, Worklog};
+
+/// Wraps user input in `%` for a substring `ILIKE`, escaping the pattern
+/// metacharacters so they match literally.
+pub(crate) fn like_pattern(fragment: &str) -> String {
+    let escaped = fragment
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+    format!("%{}%", escaped)
+}
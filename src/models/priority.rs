@@ -0,0 +1,90 @@
+use serde::Serialize;
+use std::fmt;
+
+/// Issue priority, `P0` being the most urgent. Stored as its level (0-4).
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+pub enum Priority {
+    P0,
+    P1,
+    #[default]
+    P2,
+    P3,
+    P4,
+}
+
+impl Priority {
+    pub const ALL: [Priority; 5] = [
+        Priority::P0,
+        Priority::P1,
+        Priority::P2,
+        Priority::P3,
+        Priority::P4,
+    ];
+
+    pub fn level(self) -> i16 {
+        self as i16
+    }
+
+    pub fn from_level(level: i16) -> Option<Priority> {
+        Self::ALL.get(usize::try_from(level).ok()?).copied()
+    }
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Priority::P0 => "P0",
+            Priority::P1 => "P1",
+            Priority::P2 => "P2",
+            Priority::P3 => "P3",
+            Priority::P4 => "P4",
+        }
+    }
+
+    /// Accepts `P0`-`P4`, case-insensitively.
+    pub fn parse(value: &str) -> Option<Priority> {
+        Self::ALL
+            .into_iter()
+            .find(|p| p.as_str().eq_ignore_ascii_case(value))
+    }
+}
+
+impl fmt::Display for Priority {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// How badly an issue hurts, independent of when it will be worked on.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    Blocker,
+    Critical,
+    #[default]
+    Major,
+    Minor,
+    Trivial,
+}
+
+impl Severity {
+    pub const ALL: [Severity; 5] = [
+        Severity::Blocker,
+        Severity::Critical,
+        Severity::Major,
+        Severity::Minor,
+        Severity::Trivial,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Severity::Blocker => "blocker",
+            Severity::Critical => "critical",
+            Severity::Major => "major",
+            Severity::Minor => "minor",
+            Severity::Trivial => "trivial",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<Severity> {
+        Self::ALL.into_iter().find(|s| s.as_str() == value)
+    }
+}
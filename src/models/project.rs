@@ -1,7 +1,12 @@
-use crate::db::schema::projects;
+use crate::db::schema::{project_keys, project_members, projects};
+use crate::models::workflow::Workflow;
+use crate::models::{like_pattern, ChangeSet, ENTITY_PROJECT};
+use chrono::{DateTime, Utc};
+use diesel::pg::Pg;
 use diesel::prelude::*;
 use diesel::AsChangeset;
 use serde::Deserialize;
+use std::collections::HashMap;
 
 #[derive(Queryable, Selectable)]
 #[diesel(table_name = crate::db::schema::projects)]
@@ -10,6 +15,12 @@ pub struct Project {
     pub id: i32,
     pub name: String,
     pub description: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    /// Short unique key prefixing issue numbers, e.g. `PROJ` in `PROJ-123`.
+    pub key: String,
+    /// Levels of sub-issues allowed below a top-level issue.
+    pub max_issue_depth: i16,
 }
 
 #[derive(Insertable)]
@@ -17,6 +28,7 @@ pub struct Project {
 pub struct NewProject<'a> {
     pub name: &'a str,
     pub description: &'a str,
+    pub key: &'a str,
 }
 
 #[derive(Debug, Deserialize, AsChangeset)]
@@ -24,36 +36,192 @@ pub struct NewProject<'a> {
 pub struct UpdateProject {
     pub name: Option<String>,
     pub description: Option<String>,
+    pub key: Option<String>,
+    pub max_issue_depth: Option<i16>,
+}
+
+#[derive(Debug, Clone, Copy, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProjectSortField {
+    #[default]
+    Name,
+    CreatedAt,
+    UpdatedAt,
+}
+
+/// Sort value and id of the last project on the previous page.
+#[derive(Debug, Clone)]
+pub enum ProjectKeyset {
+    Name(String, i32),
+    CreatedAt(DateTime<Utc>, i32),
+    UpdatedAt(DateTime<Utc>, i32),
+}
+
+#[derive(Debug, Default)]
+pub struct ProjectListQuery {
+    pub name_contains: Option<String>,
+    /// Only projects this user is a member of.
+    pub member_id: Option<i32>,
+    pub sort: ProjectSortField,
+    pub descending: bool,
+    pub after: Option<ProjectKeyset>,
+    pub limit: i64,
 }
 
 impl Project {
+    /// Creates the project with its default workflow. Without an explicit
+    /// `key` one is derived from the name.
     pub fn create(
         conn: &mut PgConnection,
         project_name: &str,
         project_description: &str,
+        project_key: Option<&str>,
     ) -> Result<Project, diesel::result::Error> {
-        use crate::db::schema::projects::dsl::*;
+        conn.transaction(|conn| {
+            let key = match project_key {
+                Some(key) => key.to_string(),
+                None => Self::derive_key(conn, project_name)?,
+            };
+            let new_project = NewProject {
+                name: project_name,
+                description: project_description,
+                key: &key,
+            };
 
-        let new_project = NewProject {
-            name: project_name,
-            description: project_description,
-        };
+            let project = diesel::insert_into(projects::table)
+                .values(&new_project)
+                .returning(Project::as_returning())
+                .get_result(conn)?;
+            diesel::insert_into(project_keys::table)
+                .values((
+                    project_keys::key.eq(&project.key),
+                    project_keys::project_id.eq(project.id),
+                ))
+                .execute(conn)?;
+
+            Workflow::create_default(conn, project.id)?;
 
-        diesel::insert_into(projects)
-            .values(&new_project)
-            .returning(Project::as_returning())
-            .get_result(conn)
+            Ok(project)
+        })
     }
-    pub fn get_first_n(
-        conn: &mut PgConnection,
-        limit: i64,
-    ) -> Result<Vec<Project>, diesel::result::Error> {
-        use crate::db::schema::projects::dsl::*;
 
-        projects
-            .limit(limit)
+    /// Uppercases `key` if it is 2-10 letters and digits starting with a
+    /// letter.
+    pub fn normalize_key(key: &str) -> Option<String> {
+        let key = key.trim().to_ascii_uppercase();
+        let mut chars = key.chars();
+        let valid = (2..=10).contains(&key.len())
+            && chars.next().is_some_and(|c| c.is_ascii_uppercase())
+            && chars.all(|c| c.is_ascii_uppercase() || c.is_ascii_digit());
+        valid.then_some(key)
+    }
+
+    /// Up to four letters of `name`, numbered if that key was ever used.
+    fn derive_key(conn: &mut PgConnection, name: &str) -> QueryResult<String> {
+        let mut prefix: String = name
+            .chars()
+            .filter(char::is_ascii_alphabetic)
+            .take(4)
+            .map(|c| c.to_ascii_uppercase())
+            .collect();
+        if prefix.len() < 2 {
+            prefix = "PRJ".to_string();
+        }
+
+        let taken: Vec<String> = project_keys::table
+            .filter(project_keys::key.like(format!("{}%", prefix)))
+            .select(project_keys::key)
+            .load(conn)?;
+
+        Ok(std::iter::once(prefix.clone())
+            .chain((2..).map(|n| format!("{}{}", prefix, n)))
+            .find(|candidate| !taken.contains(candidate))
+            .unwrap_or(prefix))
+    }
+
+    fn filtered(params: &ProjectListQuery) -> projects::BoxedQuery<'static, Pg> {
+        let mut query = projects::table.into_boxed();
+        if let Some(fragment) = params.name_contains.as_deref() {
+            query = query.filter(projects::name.ilike(like_pattern(fragment)));
+        }
+        if let Some(user_id) = params.member_id {
+            query = query.filter(
+                projects::id.eq_any(
+                    project_members::table
+                        .filter(project_members::user_id.eq(user_id))
+                        .select(project_members::project_id),
+                ),
+            );
+        }
+        query
+    }
+
+    pub fn count(conn: &mut PgConnection, params: &ProjectListQuery) -> QueryResult<i64> {
+        Self::filtered(params).count().get_result(conn)
+    }
+
+    pub fn list(conn: &mut PgConnection, params: &ProjectListQuery) -> QueryResult<Vec<Project>> {
+        let mut query = Self::filtered(params);
+
+        query = match (params.after.clone(), params.descending) {
+            (None, _) => query,
+            (Some(ProjectKeyset::Name(value, last_id)), false) => query.filter(
+                projects::name
+                    .gt(value.clone())
+                    .or(projects::name.eq(value).and(projects::id.gt(last_id))),
+            ),
+            (Some(ProjectKeyset::Name(value, last_id)), true) => query.filter(
+                projects::name
+                    .lt(value.clone())
+                    .or(projects::name.eq(value).and(projects::id.lt(last_id))),
+            ),
+            (Some(ProjectKeyset::CreatedAt(value, last_id)), false) => query.filter(
+                projects::created_at
+                    .gt(value)
+                    .or(projects::created_at.eq(value).and(projects::id.gt(last_id))),
+            ),
+            (Some(ProjectKeyset::CreatedAt(value, last_id)), true) => query.filter(
+                projects::created_at
+                    .lt(value)
+                    .or(projects::created_at.eq(value).and(projects::id.lt(last_id))),
+            ),
+            (Some(ProjectKeyset::UpdatedAt(value, last_id)), false) => query.filter(
+                projects::updated_at
+                    .gt(value)
+                    .or(projects::updated_at.eq(value).and(projects::id.gt(last_id))),
+            ),
+            (Some(ProjectKeyset::UpdatedAt(value, last_id)), true) => query.filter(
+                projects::updated_at
+                    .lt(value)
+                    .or(projects::updated_at.eq(value).and(projects::id.lt(last_id))),
+            ),
+        };
+
+        query = match (params.sort, params.descending) {
+            (ProjectSortField::Name, false) => {
+                query.order((projects::name.asc(), projects::id.asc()))
+            }
+            (ProjectSortField::Name, true) => {
+                query.order((projects::name.desc(), projects::id.desc()))
+            }
+            (ProjectSortField::CreatedAt, false) => {
+                query.order((projects::created_at.asc(), projects::id.asc()))
+            }
+            (ProjectSortField::CreatedAt, true) => {
+                query.order((projects::created_at.desc(), projects::id.desc()))
+            }
+            (ProjectSortField::UpdatedAt, false) => {
+                query.order((projects::updated_at.asc(), projects::id.asc()))
+            }
+            (ProjectSortField::UpdatedAt, true) => {
+                query.order((projects::updated_at.desc(), projects::id.desc()))
+            }
+        };
+
+        query
+            .limit(params.limit)
             .select(Project::as_select())
-            .load::<Project>(conn)
+            .load(conn)
     }
 
     pub fn delete_by_id(conn: &mut PgConnection, project_id: i32) -> QueryResult<usize> {
@@ -69,21 +237,87 @@ impl Project {
 
         projects
             .filter(id.eq(project_id))
-            .select((id, name, description))
+            .select(Project::as_select())
+            .first(conn)
+            .optional()
+    }
+
+    /// Finds a project by its current or any former key.
+    pub fn find_by_key(conn: &mut PgConnection, key: &str) -> QueryResult<Option<Project>> {
+        project_keys::table
+            .inner_join(projects::table)
+            .filter(project_keys::key.eq(key))
+            .select(Project::as_select())
             .first(conn)
             .optional()
     }
 
+    /// Current keys of the given projects.
+    pub fn keys_for(
+        conn: &mut PgConnection,
+        project_ids: &[i32],
+    ) -> QueryResult<HashMap<i32, String>> {
+        let keys: Vec<(i32, String)> = projects::table
+            .filter(projects::id.eq_any(project_ids))
+            .select((projects::id, projects::key))
+            .load(conn)?;
+
+        Ok(keys.into_iter().collect())
+    }
+
+    /// Applies `updates`, auditing changed fields as done by `actor_id`.
     pub fn update(
         conn: &mut PgConnection,
         project_id: i32,
         updates: &UpdateProject,
+        actor_id: i32,
     ) -> QueryResult<Project> {
-        use crate::db::schema::projects::dsl::*;
+        conn.transaction(|conn| {
+            let current = projects::table
+                .find(project_id)
+                .for_update()
+                .select(Project::as_select())
+                .first(conn)?;
+
+            let mut history = ChangeSet::new(ENTITY_PROJECT, project_id, actor_id);
+            history.track("name", &current.name, updates.name.as_ref());
+            history.track(
+                "description",
+                &current.description,
+                updates.description.as_ref(),
+            );
+            history.track("key", &current.key, updates.key.as_ref());
+            history.track(
+                "max_issue_depth",
+                &current.max_issue_depth,
+                updates.max_issue_depth.as_ref(),
+            );
+
+            // Keep the old key resolving; a key held by another project
+            // fails on the primary key
+            if let Some(key) = updates.key.as_ref().filter(|key| **key != current.key) {
+                let owner = project_keys::table
+                    .find(key)
+                    .select(project_keys::project_id)
+                    .first::<i32>(conn)
+                    .optional()?;
+                if owner != Some(project_id) {
+                    diesel::insert_into(project_keys::table)
+                        .values((
+                            project_keys::key.eq(key),
+                            project_keys::project_id.eq(project_id),
+                        ))
+                        .execute(conn)?;
+                }
+            }
+
+            let updated = diesel::update(projects::table.find(project_id))
+                .set(updates)
+                .returning(Project::as_returning())
+                .get_result(conn)?;
+            history.save(conn)?;
 
-        diesel::update(projects.filter(id.eq(project_id)))
-            .set(updates)
-            .returning(Project::as_returning())
-            .get_result(conn)
+            Ok(updated)
+        })
     }
 }
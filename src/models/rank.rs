@@ -0,0 +1,85 @@
+//! Lexicographic ranks for manual ordering.
+//!
+//! A rank is a string of base-36 digits read as a fraction after the point,
+//! so ranks sort by plain byte comparison and there is always room for a new
+//! one between two others. Ranks never end in `0`, which keeps every pair of
+//! distinct ranks a gap apart.
+
+const DIGITS: &[u8] = b"0123456789abcdefghijklmnopqrstuvwxyz";
+const BASE: u64 = 36;
+/// Appending to either end steps the leading `HEAD_WIDTH` digits by
+/// `END_STEP`, so long runs of appends keep ranks short.
+const HEAD_WIDTH: u32 = 8;
+const END_STEP: u64 = BASE.pow(4);
+
+fn digit(rank: &[u8], index: usize) -> u64 {
+    rank.get(index)
+        .and_then(|c| DIGITS.iter().position(|d| d == c))
+        .unwrap_or(0) as u64
+}
+
+fn head(rank: &str) -> u64 {
+    (0..HEAD_WIDTH as usize).fold(0, |n, i| n * BASE + digit(rank.as_bytes(), i))
+}
+
+fn format_head(mut n: u64) -> String {
+    let mut out = vec![b'0'; HEAD_WIDTH as usize];
+    for slot in out.iter_mut().rev() {
+        *slot = DIGITS[(n % BASE) as usize];
+        n /= BASE;
+    }
+    let trimmed = out.iter().rposition(|c| *c != b'0').map_or(0, |i| i + 1);
+    out.truncate(trimmed);
+    String::from_utf8(out).expect("rank digits are ASCII")
+}
+
+/// Digit-by-digit midpoint of `prev` and `next`, with `None` standing for
+/// the ends of the range.
+fn midpoint(prev: &[u8], next: Option<&[u8]>) -> Option<String> {
+    let mut out = Vec::new();
+    let mut upper = next;
+    for i in 0.. {
+        let lo = digit(prev, i);
+        let hi = match upper {
+            Some(next) if i >= next.len() && lo == 0 => return None,
+            Some(next) => digit(next, i),
+            None => BASE,
+        };
+        if hi < lo {
+            return None;
+        }
+        if hi - lo > 1 {
+            out.push(DIGITS[(lo + (hi - lo) / 2) as usize]);
+            break;
+        }
+        out.push(DIGITS[lo as usize]);
+        if hi - lo == 1 {
+            upper = None;
+        }
+    }
+
+    Some(String::from_utf8(out).expect("rank digits are ASCII"))
+}
+
+/// A rank sorting after `prev`, or a first rank when there is none.
+pub fn rank_after(prev: Option<&str>) -> String {
+    match prev {
+        None => format_head(BASE.pow(HEAD_WIDTH) / 2),
+        Some(prev) if head(prev) + END_STEP < BASE.pow(HEAD_WIDTH) => {
+            format_head(head(prev) + END_STEP)
+        }
+        Some(prev) => midpoint(prev.as_bytes(), None).expect("ranks are unbounded above"),
+    }
+}
+
+/// A rank sorting after `prev` and before `next`; `None` for either means
+/// the start or end of the list. Returns `None` when `prev` does not sort
+/// before `next`.
+pub fn rank_between(prev: Option<&str>, next: Option<&str>) -> Option<String> {
+    match (prev, next) {
+        (prev, None) => Some(rank_after(prev)),
+        (None, Some(next)) if head(next) > END_STEP => Some(format_head(head(next) - END_STEP)),
+        (Some(prev), Some(next)) if prev >= next => None,
+        (prev, Some(next)) => midpoint(prev.unwrap_or("").as_bytes(), Some(next.as_bytes())),
+    }
+}
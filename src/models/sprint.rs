@@ -0,0 +1,241 @@
+use crate::db::schema::{issues, sprints};
+use crate::models::{ChangeSet, ENTITY_ISSUE};
+use chrono::{DateTime, NaiveDate, Utc};
+use diesel::prelude::*;
+use serde::Serialize;
+use std::fmt;
+
+/// Sprints are planned, run one at a time, then closed for good.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SprintState {
+    Planned,
+    Active,
+    Closed,
+}
+
+impl SprintState {
+    pub const ALL: [SprintState; 3] = [
+        SprintState::Planned,
+        SprintState::Active,
+        SprintState::Closed,
+    ];
+
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SprintState::Planned => "planned",
+            SprintState::Active => "active",
+            SprintState::Closed => "closed",
+        }
+    }
+
+    pub fn parse(value: &str) -> Option<SprintState> {
+        Self::ALL.into_iter().find(|s| s.as_str() == value)
+    }
+}
+
+impl fmt::Display for SprintState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = sprints)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct Sprint {
+    pub id: i32,
+    pub project_id: i32,
+    pub name: String,
+    pub goal: String,
+    /// Name of a `SprintState`.
+    pub state: String,
+    pub start_date: Option<NaiveDate>,
+    pub end_date: Option<NaiveDate>,
+    pub started_at: Option<DateTime<Utc>>,
+    pub completed_at: Option<DateTime<Utc>>,
+    pub completed_issues: Option<i32>,
+    pub incomplete_issues: Option<i32>,
+    pub created_at: DateTime<Utc>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = sprints)]
+pub struct NewSprint<'a> {
+    pub project_id: i32,
+    pub name: &'a str,
+    pub goal: &'a str,
+    pub start_date: Option<NaiveDate>,
+    pub end_date: Option<NaiveDate>,
+}
+
+#[derive(Debug, Default, AsChangeset)]
+#[diesel(table_name = sprints)]
+pub struct UpdateSprint {
+    pub name: Option<String>,
+    pub goal: Option<String>,
+    pub start_date: Option<Option<NaiveDate>>,
+    pub end_date: Option<Option<NaiveDate>>,
+}
+
+impl UpdateSprint {
+    pub fn is_empty(&self) -> bool {
+        self.name.is_none()
+            && self.goal.is_none()
+            && self.start_date.is_none()
+            && self.end_date.is_none()
+    }
+}
+
+/// Outcome of completing a sprint.
+#[derive(Debug)]
+pub struct SprintReport {
+    pub sprint: Sprint,
+    /// Issues that were done when the sprint closed; they stay in it.
+    pub completed: Vec<i32>,
+    /// Issues still open, now moved out of the sprint.
+    pub incomplete: Vec<i32>,
+}
+
+impl Sprint {
+    pub fn state(&self) -> SprintState {
+        SprintState::parse(&self.state).unwrap_or(SprintState::Planned)
+    }
+
+    pub fn create(conn: &mut PgConnection, new_sprint: &NewSprint) -> QueryResult<Sprint> {
+        diesel::insert_into(sprints::table)
+            .values(new_sprint)
+            .returning(Sprint::as_returning())
+            .get_result(conn)
+    }
+
+    /// Sprints of the project, most recently planned first.
+    pub fn list(conn: &mut PgConnection, project_id: i32) -> QueryResult<Vec<Sprint>> {
+        sprints::table
+            .filter(sprints::project_id.eq(project_id))
+            .order(sprints::id.desc())
+            .select(Sprint::as_select())
+            .load(conn)
+    }
+
+    pub fn find_by_id(conn: &mut PgConnection, sprint_id: i32) -> QueryResult<Option<Sprint>> {
+        sprints::table
+            .find(sprint_id)
+            .select(Sprint::as_select())
+            .first(conn)
+            .optional()
+    }
+
+    /// As `find_by_id`, locking the row until the surrounding transaction
+    /// ends.
+    pub fn lock(conn: &mut PgConnection, sprint_id: i32) -> QueryResult<Option<Sprint>> {
+        sprints::table
+            .find(sprint_id)
+            .for_update()
+            .select(Sprint::as_select())
+            .first(conn)
+            .optional()
+    }
+
+    pub fn update(
+        conn: &mut PgConnection,
+        sprint_id: i32,
+        changes: &UpdateSprint,
+    ) -> QueryResult<Sprint> {
+        diesel::update(sprints::table.find(sprint_id))
+            .set(changes)
+            .returning(Sprint::as_returning())
+            .get_result(conn)
+    }
+
+    /// Deletes the sprint; its issues go back to the backlog.
+    pub fn delete(conn: &mut PgConnection, sprint_id: i32) -> QueryResult<bool> {
+        let count = diesel::delete(sprints::table.find(sprint_id)).execute(conn)?;
+
+        Ok(count > 0)
+    }
+
+    /// Number of the sprint's issues per `(status, is_open)` pair.
+    pub fn status_counts(
+        conn: &mut PgConnection,
+        sprint_id: i32,
+    ) -> QueryResult<Vec<(String, bool, i64)>> {
+        issues::table
+            .filter(issues::sprint_id.eq(sprint_id))
+            .group_by((issues::status, issues::is_open))
+            .select((issues::status, issues::is_open, diesel::dsl::count_star()))
+            .load(conn)
+    }
+
+    /// Makes the sprint active with the given dates. Fails with a unique
+    /// violation if another sprint of the project is already active.
+    pub fn start(
+        conn: &mut PgConnection,
+        sprint_id: i32,
+        start_date: NaiveDate,
+        end_date: NaiveDate,
+    ) -> QueryResult<Sprint> {
+        diesel::update(sprints::table.find(sprint_id))
+            .set((
+                sprints::state.eq(SprintState::Active.as_str()),
+                sprints::start_date.eq(start_date),
+                sprints::end_date.eq(end_date),
+                sprints::started_at.eq(Utc::now()),
+            ))
+            .returning(Sprint::as_returning())
+            .get_result(conn)
+    }
+
+    /// Closes the sprint and moves its open issues to `move_to`, or to the
+    /// backlog when `None`, recording the move in each issue's history. The
+    /// caller is expected to hold the sprint's row lock.
+    pub fn complete(
+        &self,
+        conn: &mut PgConnection,
+        move_to: Option<i32>,
+        actor_id: i32,
+    ) -> QueryResult<SprintReport> {
+        conn.transaction(|conn| {
+            let members: Vec<(i32, bool)> = issues::table
+                .filter(issues::sprint_id.eq(self.id))
+                .order(issues::id.asc())
+                .for_update()
+                .select((issues::id, issues::is_open))
+                .load(conn)?;
+            let (incomplete, completed): (Vec<_>, Vec<_>) =
+                members.into_iter().partition(|(_, is_open)| *is_open);
+            let completed: Vec<i32> = completed.into_iter().map(|(id, _)| id).collect();
+            let incomplete: Vec<i32> = incomplete.into_iter().map(|(id, _)| id).collect();
+
+            if !incomplete.is_empty() {
+                diesel::update(issues::table.filter(issues::id.eq_any(&incomplete)))
+                    .set((
+                        issues::sprint_id.eq(move_to),
+                        issues::updated_at.eq(Utc::now()),
+                    ))
+                    .execute(conn)?;
+                for issue_id in &incomplete {
+                    let mut history = ChangeSet::new(ENTITY_ISSUE, *issue_id, actor_id);
+                    history.track_optional("sprint_id", Some(&self.id), Some(move_to.as_ref()));
+                    history.save(conn)?;
+                }
+            }
+
+            let sprint = diesel::update(sprints::table.find(self.id))
+                .set((
+                    sprints::state.eq(SprintState::Closed.as_str()),
+                    sprints::completed_at.eq(Utc::now()),
+                    sprints::completed_issues.eq(completed.len() as i32),
+                    sprints::incomplete_issues.eq(incomplete.len() as i32),
+                ))
+                .returning(Sprint::as_returning())
+                .get_result(conn)?;
+
+            Ok(SprintReport {
+                sprint,
+                completed,
+                incomplete,
+            })
+        })
+    }
+}
@@ -0,0 +1,116 @@
+use crate::db::schema::users;
+use chrono::{DateTime, Utc};
+use diesel::prelude::*;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = users)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct User {
+    pub id: i32,
+    pub username: String,
+    pub display_name: String,
+    pub email: Option<String>,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+    pub is_admin: bool,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = users)]
+pub struct NewUser<'a> {
+    pub username: &'a str,
+    pub display_name: &'a str,
+    pub email: Option<&'a str>,
+}
+
+#[derive(Debug, Deserialize, AsChangeset)]
+#[diesel(table_name = users)]
+pub struct UpdateUser {
+    pub display_name: Option<String>,
+    pub email: Option<String>,
+    pub is_active: Option<bool>,
+}
+
+impl User {
+    pub fn create(conn: &mut PgConnection, new_user: &NewUser) -> QueryResult<User> {
+        diesel::insert_into(users::table)
+            .values(new_user)
+            .returning(User::as_returning())
+            .get_result(conn)
+    }
+
+    pub fn list(conn: &mut PgConnection, active: Option<bool>) -> QueryResult<Vec<User>> {
+        let mut query = users::table.into_boxed();
+        if let Some(active) = active {
+            query = query.filter(users::is_active.eq(active));
+        }
+
+        query
+            .order(users::username.asc())
+            .select(User::as_select())
+            .load(conn)
+    }
+
+    pub fn find_by_id(conn: &mut PgConnection, user_id: i32) -> QueryResult<Option<User>> {
+        users::table
+            .find(user_id)
+            .select(User::as_select())
+            .first(conn)
+            .optional()
+    }
+
+    pub fn find_active(conn: &mut PgConnection, user_id: i32) -> QueryResult<Option<User>> {
+        users::table
+            .find(user_id)
+            .filter(users::is_active.eq(true))
+            .select(User::as_select())
+            .first(conn)
+            .optional()
+    }
+
+    /// Users by id, for embedding in responses that reference several of them.
+    pub fn find_many(conn: &mut PgConnection, ids: &[i32]) -> QueryResult<HashMap<i32, User>> {
+        let found = users::table
+            .filter(users::id.eq_any(ids))
+            .select(User::as_select())
+            .load(conn)?;
+
+        Ok(found.into_iter().map(|user| (user.id, user)).collect())
+    }
+
+    /// Users by username, for resolving names given in imported data.
+    pub fn find_by_usernames(
+        conn: &mut PgConnection,
+        usernames: &[String],
+    ) -> QueryResult<HashMap<String, User>> {
+        let found = users::table
+            .filter(users::username.eq_any(usernames))
+            .select(User::as_select())
+            .load(conn)?;
+
+        Ok(found
+            .into_iter()
+            .map(|user| (user.username.clone(), user))
+            .collect())
+    }
+
+    pub fn update(
+        conn: &mut PgConnection,
+        user_id: i32,
+        changes: &UpdateUser,
+    ) -> QueryResult<User> {
+        diesel::update(users::table.find(user_id))
+            .set(changes)
+            .returning(User::as_returning())
+            .get_result(conn)
+    }
+
+    pub fn delete(conn: &mut PgConnection, user_id: i32) -> QueryResult<bool> {
+        let count = diesel::delete(users::table.find(user_id)).execute(conn)?;
+
+        Ok(count > 0)
+    }
+}
@@ -0,0 +1,285 @@
+use crate::db::schema::{webhook_deliveries, webhooks};
+use chrono::{DateTime, Duration, Utc};
+use diesel::prelude::*;
+use diesel::sql_types::Text;
+use std::collections::HashMap;
+
+pub const EVENT_ISSUE_CREATED: &str = "issue.created";
+pub const EVENT_ISSUE_UPDATED: &str = "issue.updated";
+pub const EVENT_ISSUE_DELETED: &str = "issue.deleted";
+pub const EVENT_PROJECT_UPDATED: &str = "project.updated";
+
+/// Every event a webhook can subscribe to.
+pub const WEBHOOK_EVENTS: [&str; 4] = [
+    EVENT_ISSUE_CREATED,
+    EVENT_ISSUE_UPDATED,
+    EVENT_ISSUE_DELETED,
+    EVENT_PROJECT_UPDATED,
+];
+
+pub const DELIVERY_PENDING: &str = "pending";
+pub const DELIVERY_DELIVERED: &str = "delivered";
+pub const DELIVERY_FAILED: &str = "failed";
+
+/// A project's subscription to some of its events.
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = webhooks)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct Webhook {
+    pub id: i32,
+    pub project_id: i32,
+    pub url: String,
+    pub secret: String,
+    pub events: Vec<String>,
+    pub is_active: bool,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = webhooks)]
+pub struct NewWebhook<'a> {
+    pub project_id: i32,
+    pub url: &'a str,
+    pub secret: &'a str,
+    pub events: &'a [String],
+    pub is_active: bool,
+}
+
+#[derive(Debug, Default, AsChangeset)]
+#[diesel(table_name = webhooks)]
+pub struct UpdateWebhook {
+    pub url: Option<String>,
+    pub secret: Option<String>,
+    pub events: Option<Vec<String>>,
+    pub is_active: Option<bool>,
+}
+
+impl UpdateWebhook {
+    pub fn is_empty(&self) -> bool {
+        self.url.is_none()
+            && self.secret.is_none()
+            && self.events.is_none()
+            && self.is_active.is_none()
+    }
+}
+
+/// One event on its way to one webhook.
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = webhook_deliveries)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct WebhookDelivery {
+    pub id: i32,
+    pub webhook_id: i32,
+    pub event: String,
+    pub payload: String,
+    /// `pending`, `delivered` or `failed`.
+    pub status: String,
+    pub attempts: i32,
+    pub next_attempt_at: DateTime<Utc>,
+    pub last_attempt_at: Option<DateTime<Utc>>,
+    pub response_status: Option<i32>,
+    pub last_error: Option<String>,
+    pub created_at: DateTime<Utc>,
+    pub delivered_at: Option<DateTime<Utc>>,
+}
+
+impl Webhook {
+    pub fn create(conn: &mut PgConnection, new_webhook: &NewWebhook) -> QueryResult<Webhook> {
+        diesel::insert_into(webhooks::table)
+            .values(new_webhook)
+            .returning(Webhook::as_returning())
+            .get_result(conn)
+    }
+
+    pub fn find_by_id(conn: &mut PgConnection, webhook_id: i32) -> QueryResult<Option<Webhook>> {
+        webhooks::table
+            .find(webhook_id)
+            .select(Webhook::as_select())
+            .first(conn)
+            .optional()
+    }
+
+    pub fn for_project(conn: &mut PgConnection, project_id: i32) -> QueryResult<Vec<Webhook>> {
+        webhooks::table
+            .filter(webhooks::project_id.eq(project_id))
+            .order(webhooks::id.asc())
+            .select(Webhook::as_select())
+            .load(conn)
+    }
+
+    pub fn update(
+        conn: &mut PgConnection,
+        webhook_id: i32,
+        changes: &UpdateWebhook,
+    ) -> QueryResult<Webhook> {
+        diesel::update(webhooks::table.find(webhook_id))
+            .set((changes, webhooks::updated_at.eq(Utc::now())))
+            .returning(Webhook::as_returning())
+            .get_result(conn)
+    }
+
+    /// Deletes the webhook along with its delivery log.
+    pub fn delete(conn: &mut PgConnection, webhook_id: i32) -> QueryResult<bool> {
+        let count = diesel::delete(webhooks::table.find(webhook_id)).execute(conn)?;
+
+        Ok(count > 0)
+    }
+}
+
+impl WebhookDelivery {
+    /// Queues `payload` for every active webhook of the project subscribed
+    /// to `event`. Run it in the transaction that makes the change, so the
+    /// event is recorded exactly when the change is. Returns the number of
+    /// deliveries queued.
+    pub fn enqueue(
+        conn: &mut PgConnection,
+        project_id: i32,
+        event: &str,
+        payload: &str,
+    ) -> QueryResult<usize> {
+        let subscribed = webhooks::table
+            .filter(webhooks::project_id.eq(project_id))
+            .filter(webhooks::is_active.eq(true))
+            .filter(webhooks::events.contains(vec![event.to_string()]))
+            .select((
+                webhooks::id,
+                event.into_sql::<Text>(),
+                payload.into_sql::<Text>(),
+            ));
+
+        diesel::insert_into(webhook_deliveries::table)
+            .values(subscribed)
+            .into_columns((
+                webhook_deliveries::webhook_id,
+                webhook_deliveries::event,
+                webhook_deliveries::payload,
+            ))
+            .execute(conn)
+    }
+
+    /// Claims up to `limit` pending deliveries that are due, oldest first,
+    /// together with their webhooks. Claimed deliveries are pushed back by
+    /// `lease` so other workers skip them; recording the attempt settles
+    /// them. Deliveries of inactive webhooks wait until they are active.
+    pub fn claim_due(
+        conn: &mut PgConnection,
+        limit: i64,
+        lease: Duration,
+    ) -> QueryResult<Vec<(WebhookDelivery, Webhook)>> {
+        conn.transaction(|conn| {
+            let now = Utc::now();
+            let active = webhooks::table
+                .filter(webhooks::is_active.eq(true))
+                .select(webhooks::id);
+            let due: Vec<i32> = webhook_deliveries::table
+                .filter(webhook_deliveries::status.eq(DELIVERY_PENDING))
+                .filter(webhook_deliveries::next_attempt_at.le(now))
+                .filter(webhook_deliveries::webhook_id.eq_any(active))
+                .order((
+                    webhook_deliveries::next_attempt_at.asc(),
+                    webhook_deliveries::id.asc(),
+                ))
+                .limit(limit)
+                .select(webhook_deliveries::id)
+                .for_update()
+                .skip_locked()
+                .load(conn)?;
+            if due.is_empty() {
+                return Ok(Vec::new());
+            }
+
+            let claimed: Vec<WebhookDelivery> = diesel::update(
+                webhook_deliveries::table.filter(webhook_deliveries::id.eq_any(&due)),
+            )
+            .set(webhook_deliveries::next_attempt_at.eq(now + lease))
+            .returning(WebhookDelivery::as_returning())
+            .get_results(conn)?;
+            let webhook_ids: Vec<i32> = claimed.iter().map(|d| d.webhook_id).collect();
+            let by_id: HashMap<i32, Webhook> = webhooks::table
+                .filter(webhooks::id.eq_any(&webhook_ids))
+                .select(Webhook::as_select())
+                .load(conn)?
+                .into_iter()
+                .map(|webhook| (webhook.id, webhook))
+                .collect();
+
+            let mut claimed: Vec<(WebhookDelivery, Webhook)> = claimed
+                .into_iter()
+                .filter_map(|delivery| {
+                    let webhook = by_id.get(&delivery.webhook_id)?.clone();
+                    Some((delivery, webhook))
+                })
+                .collect();
+            claimed.sort_by_key(|(delivery, _)| delivery.id);
+            Ok(claimed)
+        })
+    }
+
+    pub fn mark_delivered(
+        conn: &mut PgConnection,
+        delivery_id: i32,
+        response_status: i32,
+    ) -> QueryResult<WebhookDelivery> {
+        let now = Utc::now();
+        diesel::update(webhook_deliveries::table.find(delivery_id))
+            .set((
+                webhook_deliveries::status.eq(DELIVERY_DELIVERED),
+                webhook_deliveries::attempts.eq(webhook_deliveries::attempts + 1),
+                webhook_deliveries::last_attempt_at.eq(now),
+                webhook_deliveries::response_status.eq(response_status),
+                webhook_deliveries::last_error.eq(None::<String>),
+                webhook_deliveries::delivered_at.eq(now),
+            ))
+            .returning(WebhookDelivery::as_returning())
+            .get_result(conn)
+    }
+
+    /// Records a failed attempt. The delivery is retried at `retry_at`, or
+    /// given up on without one.
+    pub fn mark_failed(
+        conn: &mut PgConnection,
+        delivery_id: i32,
+        response_status: Option<i32>,
+        error: &str,
+        retry_at: Option<DateTime<Utc>>,
+    ) -> QueryResult<WebhookDelivery> {
+        let now = Utc::now();
+        diesel::update(webhook_deliveries::table.find(delivery_id))
+            .set((
+                webhook_deliveries::status.eq(if retry_at.is_some() {
+                    DELIVERY_PENDING
+                } else {
+                    DELIVERY_FAILED
+                }),
+                webhook_deliveries::attempts.eq(webhook_deliveries::attempts + 1),
+                webhook_deliveries::next_attempt_at.eq(retry_at.unwrap_or(now)),
+                webhook_deliveries::last_attempt_at.eq(now),
+                webhook_deliveries::response_status.eq(response_status),
+                webhook_deliveries::last_error.eq(error),
+            ))
+            .returning(WebhookDelivery::as_returning())
+            .get_result(conn)
+    }
+
+    /// Deliveries of the webhook, newest first.
+    pub fn for_webhook(
+        conn: &mut PgConnection,
+        webhook_id: i32,
+        status: Option<&str>,
+        limit: i64,
+    ) -> QueryResult<Vec<WebhookDelivery>> {
+        let mut query = webhook_deliveries::table
+            .filter(webhook_deliveries::webhook_id.eq(webhook_id))
+            .into_boxed();
+        if let Some(status) = status {
+            query = query.filter(webhook_deliveries::status.eq(status));
+        }
+
+        query
+            .order(webhook_deliveries::id.desc())
+            .limit(limit)
+            .select(WebhookDelivery::as_select())
+            .load(conn)
+    }
+}
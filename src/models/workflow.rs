@@ -0,0 +1,368 @@
+use crate::db::schema::{issues, workflow_states, workflow_transitions};
+use diesel::prelude::*;
+use std::collections::HashSet;
+
+pub const CATEGORY_OPEN: &str = "open";
+pub const CATEGORY_DONE: &str = "done";
+
+/// Workflow every new project starts with: `(name, category)`, first one initial.
+const DEFAULT_STATES: &[(&str, &str)] = &[
+    ("open", CATEGORY_OPEN),
+    ("in_progress", CATEGORY_OPEN),
+    ("resolved", CATEGORY_DONE),
+    ("closed", CATEGORY_DONE),
+];
+
+const DEFAULT_TRANSITIONS: &[(&str, &str)] = &[
+    ("open", "in_progress"),
+    ("open", "resolved"),
+    ("open", "closed"),
+    ("in_progress", "open"),
+    ("in_progress", "resolved"),
+    ("in_progress", "closed"),
+    ("resolved", "open"),
+    ("resolved", "closed"),
+    ("closed", "open"),
+];
+
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = workflow_states)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct WorkflowState {
+    pub id: i32,
+    pub project_id: i32,
+    pub name: String,
+    pub category: String,
+    pub position: i32,
+    pub is_initial: bool,
+    /// Most issues allowed in this status at once.
+    pub wip_limit: Option<i32>,
+}
+
+impl WorkflowState {
+    pub fn is_open(&self) -> bool {
+        self.category == CATEGORY_OPEN
+    }
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = workflow_states)]
+struct NewWorkflowState<'a> {
+    project_id: i32,
+    name: &'a str,
+    category: &'a str,
+    position: i32,
+    is_initial: bool,
+    wip_limit: Option<i32>,
+}
+
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = workflow_transitions)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct WorkflowTransition {
+    pub id: i32,
+    pub project_id: i32,
+    pub from_state_id: i32,
+    pub to_state_id: i32,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = workflow_transitions)]
+struct NewWorkflowTransition {
+    project_id: i32,
+    from_state_id: i32,
+    to_state_id: i32,
+}
+
+#[derive(Debug, Clone)]
+pub struct StateDefinition {
+    pub name: String,
+    pub category: String,
+    pub is_initial: bool,
+    pub wip_limit: Option<i32>,
+}
+
+#[derive(Debug, Clone)]
+pub struct TransitionDefinition {
+    pub from: String,
+    pub to: String,
+}
+
+#[derive(Debug)]
+pub struct Workflow {
+    pub states: Vec<WorkflowState>,
+    pub transitions: Vec<WorkflowTransition>,
+}
+
+impl Workflow {
+    pub fn load(conn: &mut PgConnection, project_id: i32) -> QueryResult<Workflow> {
+        let states = workflow_states::table
+            .filter(workflow_states::project_id.eq(project_id))
+            .order((workflow_states::position.asc(), workflow_states::id.asc()))
+            .select(WorkflowState::as_select())
+            .load(conn)?;
+
+        let transitions = workflow_transitions::table
+            .filter(workflow_transitions::project_id.eq(project_id))
+            .order(workflow_transitions::id.asc())
+            .select(WorkflowTransition::as_select())
+            .load(conn)?;
+
+        Ok(Workflow {
+            states,
+            transitions,
+        })
+    }
+
+    /// WIP limit of the project's `status`, if it has one.
+    pub fn wip_limit(
+        conn: &mut PgConnection,
+        project_id: i32,
+        status: &str,
+    ) -> QueryResult<Option<i32>> {
+        workflow_states::table
+            .filter(workflow_states::project_id.eq(project_id))
+            .filter(workflow_states::name.eq(status))
+            .select(workflow_states::wip_limit)
+            .first::<Option<i32>>(conn)
+            .optional()
+            .map(Option::flatten)
+    }
+
+    pub fn state(&self, name: &str) -> Option<&WorkflowState> {
+        self.states.iter().find(|s| s.name == name)
+    }
+
+    pub fn initial_state(&self) -> Option<&WorkflowState> {
+        self.states.iter().find(|s| s.is_initial)
+    }
+
+    pub fn state_names(&self) -> Vec<&str> {
+        self.states.iter().map(|s| s.name.as_str()).collect()
+    }
+
+    fn state_name(&self, id: i32) -> Option<&str> {
+        self.states
+            .iter()
+            .find(|s| s.id == id)
+            .map(|s| s.name.as_str())
+    }
+
+    /// `(from, to)` state name pairs, in definition order.
+    pub fn transition_names(&self) -> Vec<(&str, &str)> {
+        self.transitions
+            .iter()
+            .filter_map(|t| {
+                Some((
+                    self.state_name(t.from_state_id)?,
+                    self.state_name(t.to_state_id)?,
+                ))
+            })
+            .collect()
+    }
+
+    /// States an issue currently in `from` may move to.
+    pub fn allowed_targets(&self, from: &str) -> Vec<&str> {
+        self.transition_names()
+            .into_iter()
+            .filter(|(f, _)| *f == from)
+            .map(|(_, to)| to)
+            .collect()
+    }
+
+    pub fn can_transition(&self, from: &str, to: &str) -> bool {
+        from == to || self.allowed_targets(from).contains(&to)
+    }
+
+    /// Checks a workflow definition before `replace` writes it. Returns a
+    /// message suitable for a 400 response.
+    pub fn validate_definition(
+        states: &[StateDefinition],
+        transitions: &[TransitionDefinition],
+    ) -> Result<(), String> {
+        if states.is_empty() {
+            return Err("A workflow needs at least one state".to_string());
+        }
+
+        let mut names = HashSet::new();
+        for state in states {
+            if state.name.trim().is_empty() || state.name.len() > 50 {
+                return Err("State names must be between 1 and 50 characters".to_string());
+            }
+            if !names.insert(state.name.as_str()) {
+                return Err(format!("Duplicate state '{}'", state.name));
+            }
+            if state.category != CATEGORY_OPEN && state.category != CATEGORY_DONE {
+                return Err(format!(
+                    "Invalid category '{}' for state '{}'. Allowed values: [\"{}\", \"{}\"]",
+                    state.category, state.name, CATEGORY_OPEN, CATEGORY_DONE
+                ));
+            }
+            if state.wip_limit.is_some_and(|limit| limit < 1) {
+                return Err(format!(
+                    "WIP limit of state '{}' must be at least 1",
+                    state.name
+                ));
+            }
+        }
+
+        if states.iter().filter(|s| s.is_initial).count() != 1 {
+            return Err("Exactly one state must be marked initial".to_string());
+        }
+
+        let mut pairs = HashSet::new();
+        for transition in transitions {
+            for name in [&transition.from, &transition.to] {
+                if !names.contains(name.as_str()) {
+                    return Err(format!("Transition references unknown state '{}'", name));
+                }
+            }
+            if transition.from == transition.to {
+                return Err(format!(
+                    "State '{}' cannot transition to itself",
+                    transition.from
+                ));
+            }
+            if !pairs.insert((transition.from.as_str(), transition.to.as_str())) {
+                return Err(format!(
+                    "Duplicate transition '{}' -> '{}'",
+                    transition.from, transition.to
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Statuses used by issues of the project that are missing from `states`;
+    /// such a definition cannot be saved.
+    pub fn states_in_use_missing(
+        conn: &mut PgConnection,
+        project_id: i32,
+        states: &[StateDefinition],
+    ) -> QueryResult<Vec<String>> {
+        let names: Vec<&str> = states.iter().map(|s| s.name.as_str()).collect();
+
+        issues::table
+            .filter(issues::project_id.eq(project_id))
+            .filter(issues::status.ne_all(names))
+            .select(issues::status)
+            .distinct()
+            .order(issues::status.asc())
+            .load(conn)
+    }
+
+    /// Replaces the project's workflow with the given definition. States are
+    /// matched by name so issues keep pointing at them; states left out are
+    /// deleted, which fails if an issue still uses them. Call
+    /// `validate_definition` first.
+    pub fn replace(
+        conn: &mut PgConnection,
+        project_id: i32,
+        states: &[StateDefinition],
+        transitions: &[TransitionDefinition],
+    ) -> QueryResult<Workflow> {
+        conn.transaction(|conn| {
+            let existing = Workflow::load(conn, project_id)?;
+
+            diesel::delete(
+                workflow_transitions::table.filter(workflow_transitions::project_id.eq(project_id)),
+            )
+            .execute(conn)?;
+
+            diesel::update(
+                workflow_states::table.filter(workflow_states::project_id.eq(project_id)),
+            )
+            .set(workflow_states::is_initial.eq(false))
+            .execute(conn)?;
+
+            let keep: Vec<&str> = states.iter().map(|s| s.name.as_str()).collect();
+            diesel::delete(
+                workflow_states::table
+                    .filter(workflow_states::project_id.eq(project_id))
+                    .filter(workflow_states::name.ne_all(keep)),
+            )
+            .execute(conn)?;
+
+            for (position, state) in states.iter().enumerate() {
+                match existing.state(&state.name) {
+                    Some(current) => {
+                        diesel::update(workflow_states::table.find(current.id))
+                            .set((
+                                workflow_states::category.eq(&state.category),
+                                workflow_states::position.eq(position as i32),
+                                workflow_states::is_initial.eq(state.is_initial),
+                                workflow_states::wip_limit.eq(state.wip_limit),
+                            ))
+                            .execute(conn)?;
+                    }
+                    None => {
+                        diesel::insert_into(workflow_states::table)
+                            .values(NewWorkflowState {
+                                project_id,
+                                name: &state.name,
+                                category: &state.category,
+                                position: position as i32,
+                                is_initial: state.is_initial,
+                                wip_limit: state.wip_limit,
+                            })
+                            .execute(conn)?;
+                    }
+                }
+            }
+
+            let saved = Workflow::load(conn, project_id)?;
+            let new_transitions: Vec<NewWorkflowTransition> = transitions
+                .iter()
+                .filter_map(|t| {
+                    Some(NewWorkflowTransition {
+                        project_id,
+                        from_state_id: saved.state(&t.from)?.id,
+                        to_state_id: saved.state(&t.to)?.id,
+                    })
+                })
+                .collect();
+
+            diesel::insert_into(workflow_transitions::table)
+                .values(&new_transitions)
+                .execute(conn)?;
+
+            // Keep is_open in line with categories that may have changed
+            for state in &saved.states {
+                diesel::update(
+                    issues::table
+                        .filter(issues::project_id.eq(project_id))
+                        .filter(issues::status.eq(&state.name))
+                        .filter(issues::is_open.ne(state.is_open())),
+                )
+                .set(issues::is_open.eq(state.is_open()))
+                .execute(conn)?;
+            }
+
+            Workflow::load(conn, project_id)
+        })
+    }
+
+    pub fn create_default(conn: &mut PgConnection, project_id: i32) -> QueryResult<Workflow> {
+        let states: Vec<StateDefinition> = DEFAULT_STATES
+            .iter()
+            .enumerate()
+            .map(|(i, (name, category))| StateDefinition {
+                name: name.to_string(),
+                category: category.to_string(),
+                is_initial: i == 0,
+                wip_limit: None,
+            })
+            .collect();
+
+        let transitions: Vec<TransitionDefinition> = DEFAULT_TRANSITIONS
+            .iter()
+            .map(|(from, to)| TransitionDefinition {
+                from: from.to_string(),
+                to: to.to_string(),
+            })
+            .collect();
+
+        Workflow::replace(conn, project_id, &states, &transitions)
+    }
+}
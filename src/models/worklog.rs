@@ -0,0 +1,186 @@
+use crate::db::schema::{issues, worklogs};
+use crate::models::IssueFilter;
+use chrono::{DateTime, NaiveDate, Utc};
+use diesel::prelude::*;
+use serde::Serialize;
+use std::collections::HashMap;
+
+/// Time one user spent on an issue on one day.
+#[derive(Debug, Clone, Queryable, Selectable)]
+#[diesel(table_name = worklogs)]
+#[diesel(check_for_backend(diesel::pg::Pg))]
+pub struct Worklog {
+    pub id: i32,
+    pub issue_id: i32,
+    pub user_id: i32,
+    pub minutes: i32,
+    pub work_date: NaiveDate,
+    pub note: String,
+    pub created_at: DateTime<Utc>,
+    pub updated_at: DateTime<Utc>,
+}
+
+#[derive(Insertable)]
+#[diesel(table_name = worklogs)]
+pub struct NewWorklog<'a> {
+    pub issue_id: i32,
+    pub user_id: i32,
+    pub minutes: i32,
+    pub work_date: NaiveDate,
+    pub note: &'a str,
+}
+
+#[derive(Debug, Default, AsChangeset)]
+#[diesel(table_name = worklogs)]
+pub struct UpdateWorklog {
+    pub minutes: Option<i32>,
+    pub work_date: Option<NaiveDate>,
+    pub note: Option<String>,
+}
+
+impl UpdateWorklog {
+    pub fn is_empty(&self) -> bool {
+        self.minutes.is_none() && self.work_date.is_none() && self.note.is_none()
+    }
+}
+
+/// Estimates of a set of issues against the time logged on them.
+#[derive(Debug, Default, Serialize)]
+pub struct EstimateTotals {
+    pub estimate_minutes: i64,
+    /// Estimate not yet covered by logged time, over open issues only.
+    pub remaining_estimate_minutes: i64,
+    pub estimate_points: i64,
+    /// Story points of open issues.
+    pub remaining_points: i64,
+}
+
+impl Worklog {
+    pub fn create(conn: &mut PgConnection, new_worklog: &NewWorklog) -> QueryResult<Worklog> {
+        diesel::insert_into(worklogs::table)
+            .values(new_worklog)
+            .returning(Worklog::as_returning())
+            .get_result(conn)
+    }
+
+    pub fn find_by_id(conn: &mut PgConnection, worklog_id: i32) -> QueryResult<Option<Worklog>> {
+        worklogs::table
+            .find(worklog_id)
+            .select(Worklog::as_select())
+            .first(conn)
+            .optional()
+    }
+
+    /// Worklogs of the issue by date worked, oldest first.
+    pub fn for_issue(conn: &mut PgConnection, issue_id: i32) -> QueryResult<Vec<Worklog>> {
+        worklogs::table
+            .filter(worklogs::issue_id.eq(issue_id))
+            .order((worklogs::work_date.asc(), worklogs::id.asc()))
+            .select(Worklog::as_select())
+            .load(conn)
+    }
+
+    pub fn update(
+        conn: &mut PgConnection,
+        worklog_id: i32,
+        changes: &UpdateWorklog,
+    ) -> QueryResult<Worklog> {
+        diesel::update(worklogs::table.find(worklog_id))
+            .set((changes, worklogs::updated_at.eq(Utc::now())))
+            .returning(Worklog::as_returning())
+            .get_result(conn)
+    }
+
+    pub fn delete(conn: &mut PgConnection, worklog_id: i32) -> QueryResult<bool> {
+        let count = diesel::delete(worklogs::table.find(worklog_id)).execute(conn)?;
+
+        Ok(count > 0)
+    }
+
+    /// Minutes logged on each of `issue_ids`. Issues without worklogs are
+    /// absent.
+    pub fn minutes_by_issue(
+        conn: &mut PgConnection,
+        issue_ids: &[i32],
+    ) -> QueryResult<HashMap<i32, i64>> {
+        let rows: Vec<(i32, Option<i64>)> = worklogs::table
+            .filter(worklogs::issue_id.eq_any(issue_ids))
+            .group_by(worklogs::issue_id)
+            .select((worklogs::issue_id, diesel::dsl::sum(worklogs::minutes)))
+            .load(conn)?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(issue_id, minutes)| (issue_id, minutes.unwrap_or(0)))
+            .collect())
+    }
+
+    /// Minutes each user logged on issues matching `filter` between `from`
+    /// and `to`, both inclusive, most time first.
+    pub fn minutes_by_user(
+        conn: &mut PgConnection,
+        filter: &IssueFilter,
+        from: Option<NaiveDate>,
+        to: Option<NaiveDate>,
+    ) -> QueryResult<Vec<(i32, i64)>> {
+        let mut query = worklogs::table
+            .filter(worklogs::issue_id.eq_any(filter.to_query().select(issues::id)))
+            .group_by(worklogs::user_id)
+            .select((worklogs::user_id, diesel::dsl::sum(worklogs::minutes)))
+            .into_boxed();
+        if let Some(from) = from {
+            query = query.filter(worklogs::work_date.ge(from));
+        }
+        if let Some(to) = to {
+            query = query.filter(worklogs::work_date.le(to));
+        }
+
+        let rows: Vec<(i32, Option<i64>)> = query.load(conn)?;
+        let mut totals: Vec<(i32, i64)> = rows
+            .into_iter()
+            .map(|(user_id, minutes)| (user_id, minutes.unwrap_or(0)))
+            .collect();
+        totals.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+
+        Ok(totals)
+    }
+
+    /// Estimate totals of the issues matching `filter`, counting all time
+    /// ever logged against them.
+    pub fn estimate_totals(
+        conn: &mut PgConnection,
+        filter: &IssueFilter,
+    ) -> QueryResult<EstimateTotals> {
+        let estimated: Vec<(i32, bool, Option<i32>, Option<i32>)> = filter
+            .to_query()
+            .filter(
+                issues::original_estimate_minutes
+                    .is_not_null()
+                    .or(issues::estimate_points.is_not_null()),
+            )
+            .select((
+                issues::id,
+                issues::is_open,
+                issues::original_estimate_minutes,
+                issues::estimate_points,
+            ))
+            .load(conn)?;
+        let issue_ids: Vec<i32> = estimated.iter().map(|(id, ..)| *id).collect();
+        let logged = Self::minutes_by_issue(conn, &issue_ids)?;
+
+        let mut totals = EstimateTotals::default();
+        for (issue_id, is_open, minutes, points) in estimated {
+            let minutes = i64::from(minutes.unwrap_or(0));
+            let points = i64::from(points.unwrap_or(0));
+            totals.estimate_minutes += minutes;
+            totals.estimate_points += points;
+            if is_open {
+                let spent = logged.get(&issue_id).copied().unwrap_or(0);
+                totals.remaining_estimate_minutes += (minutes - spent).max(0);
+                totals.remaining_points += points;
+            }
+        }
+
+        Ok(totals)
+    }
+}
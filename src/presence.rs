@@ -0,0 +1,99 @@
+//! Who is viewing which issue, across the open WebSocket connections.
+//!
+//! A connection views an issue while it is subscribed to it. Every change
+//! to an issue's viewers is broadcast with the full list, so clients never
+//! have to merge updates.
+
+use crate::handlers::user::UserSummary;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tokio::sync::broadcast;
+
+/// Presence updates buffered for connections that are busy.
+const CHANNEL_CAPACITY: usize = 256;
+
+#[derive(Debug)]
+pub struct PresenceUpdate {
+    pub project_id: i32,
+    pub issue_id: i32,
+    /// Each user once, in the order they started viewing.
+    pub viewers: Vec<UserSummary>,
+}
+
+/// Viewing connections of each `(project_id, issue_id)`, oldest first.
+type Viewers = HashMap<(i32, i32), Vec<(u64, UserSummary)>>;
+
+pub struct Presence {
+    sender: broadcast::Sender<Arc<PresenceUpdate>>,
+    next_connection: AtomicU64,
+    viewers: Mutex<Viewers>,
+}
+
+impl Presence {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(CHANNEL_CAPACITY);
+        Presence {
+            sender,
+            next_connection: AtomicU64::new(1),
+            viewers: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// A new ID to tell a user's connections apart.
+    pub fn connection_id(&self) -> u64 {
+        self.next_connection.fetch_add(1, Ordering::Relaxed)
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<Arc<PresenceUpdate>> {
+        self.sender.subscribe()
+    }
+
+    pub fn join(&self, connection_id: u64, project_id: i32, issue_id: i32, user: UserSummary) {
+        let mut viewers = self.viewers.lock().expect("presence lock poisoned");
+        let entry = viewers.entry((project_id, issue_id)).or_default();
+        if entry.iter().any(|(id, _)| *id == connection_id) {
+            return;
+        }
+        entry.push((connection_id, user));
+        self.broadcast(project_id, issue_id, entry);
+    }
+
+    pub fn leave(&self, connection_id: u64, project_id: i32, issue_id: i32) {
+        let mut viewers = self.viewers.lock().expect("presence lock poisoned");
+        let Some(entry) = viewers.get_mut(&(project_id, issue_id)) else {
+            return;
+        };
+        let before = entry.len();
+        entry.retain(|(id, _)| *id != connection_id);
+        if entry.len() == before {
+            return;
+        }
+        self.broadcast(project_id, issue_id, entry);
+        if entry.is_empty() {
+            viewers.remove(&(project_id, issue_id));
+        }
+    }
+
+    /// Called with the lock held, so updates go out in the order they happen.
+    fn broadcast(&self, project_id: i32, issue_id: i32, entry: &[(u64, UserSummary)]) {
+        let mut users: Vec<UserSummary> = Vec::with_capacity(entry.len());
+        for (_, user) in entry {
+            if !users.iter().any(|seen| seen.id == user.id) {
+                users.push(user.clone());
+            }
+        }
+        // Nobody listening is fine
+        let _ = self.sender.send(Arc::new(PresenceUpdate {
+            project_id,
+            issue_id,
+            viewers: users,
+        }));
+    }
+}
+
+impl Default for Presence {
+    fn default() -> Self {
+        Presence::new()
+    }
+}
@@ -1,4 +1,5 @@
 use axum::{
+    middleware,
     routing::{delete, get, post, put},
     Router,
 };
@@ -6,38 +7,276 @@ use diesel::r2d2::{ConnectionManager, Pool};
 use diesel::PgConnection;
 use std::sync::Arc;
 
+use crate::auth::require_auth;
+use crate::event_bus::EventBus;
 use crate::handlers::{
-    issue::{create_issue, delete_issue, get_project_issues, update_issue},
-    project::{create_project, delete_project, get_projects, update_project},
+    board::{get_board, move_issue},
+    comment::{create_comment, delete_comment, get_comment, get_comments, update_comment},
+    events::stream_events,
+    hierarchy::get_subtree,
+    history::{get_issue_history, get_project_history},
+    issue::{
+        create_issue, delete_issue, get_issue, get_issue_by_key, get_overdue_issues,
+        get_project_issues, update_issue,
+    },
+    issue_csv::{export_issues, import_issues},
+    label::{create_label, delete_label, get_labels, update_label},
+    link::{create_link, delete_link, get_dependencies, get_links},
+    member::{get_members, remove_member, set_member},
+    milestone::{
+        create_milestone, delete_milestone, get_milestone, get_milestones, release_milestone,
+        update_milestone,
+    },
+    project::{create_project, delete_project, get_project, get_projects, update_project},
+    socket::connect,
+    sprint::{
+        complete_sprint, create_sprint, delete_sprint, get_sprint, get_sprints, start_sprint,
+        update_sprint,
+    },
+    token::{create_token, get_tokens, revoke_token},
+    user::{create_user, delete_user, get_user, get_users, update_user},
+    webhook::{
+        create_webhook, delete_webhook, get_deliveries, get_webhook, get_webhooks, update_webhook,
+    },
+    workflow::{get_workflow, update_workflow},
+    worklog::{create_worklog, delete_worklog, get_worklogs, update_worklog},
 };
+use crate::presence::Presence;
 
 pub type DbPool = Pool<ConnectionManager<PgConnection>>;
 
 pub struct AppState {
     pub pool: DbPool,
+    /// Live project activity for the event stream and sockets.
+    pub events: EventBus,
+    pub presence: Presence,
+    /// Accept webhook URLs on the server's own network; see
+    /// `config::allow_private_webhook_hosts`.
+    pub allow_private_webhook_hosts: bool,
+}
+
+impl AppState {
+    pub fn new(pool: DbPool) -> Self {
+        AppState {
+            pool,
+            events: EventBus::default(),
+            presence: Presence::default(),
+            allow_private_webhook_hosts: false,
+        }
+    }
 }
 
 pub fn create_router(pool: DbPool) -> Router {
-    let state = Arc::new(AppState { pool });
+    build_router(Arc::new(AppState::new(pool)))
+}
+
+/// The API over existing state, for when background tasks share it.
+/// Every route requires a bearer token; see `auth::require_auth`.
+pub fn build_router(state: Arc<AppState>) -> Router {
     Router::new()
         // Project routes
         .route("/api/projects", post(create_project))
         .route("/api/projects", get(get_projects))
+        .route("/api/projects/{project_id}", get(get_project))
         .route("/api/projects/{project_id}", delete(delete_project))
         .route("/api/projects/{project_id}", put(update_project))
+        .route(
+            "/api/projects/{project_id}/history",
+            get(get_project_history),
+        )
+        // Workflow routes
+        .route("/api/projects/{project_id}/workflow", get(get_workflow))
+        .route("/api/projects/{project_id}/workflow", put(update_workflow))
+        // Label routes
+        .route("/api/projects/{project_id}/labels", get(get_labels))
+        .route("/api/projects/{project_id}/labels", post(create_label))
+        .route(
+            "/api/projects/{project_id}/labels/{label_id}",
+            put(update_label),
+        )
+        .route(
+            "/api/projects/{project_id}/labels/{label_id}",
+            delete(delete_label),
+        )
+        // Milestone routes
+        .route("/api/projects/{project_id}/milestones", get(get_milestones))
+        .route(
+            "/api/projects/{project_id}/milestones",
+            post(create_milestone),
+        )
+        .route(
+            "/api/projects/{project_id}/milestones/{milestone_id}",
+            get(get_milestone),
+        )
+        .route(
+            "/api/projects/{project_id}/milestones/{milestone_id}",
+            put(update_milestone),
+        )
+        .route(
+            "/api/projects/{project_id}/milestones/{milestone_id}",
+            delete(delete_milestone),
+        )
+        .route(
+            "/api/projects/{project_id}/milestones/{milestone_id}/release",
+            post(release_milestone),
+        )
+        // Sprint routes
+        .route("/api/projects/{project_id}/sprints", get(get_sprints))
+        .route("/api/projects/{project_id}/sprints", post(create_sprint))
+        .route(
+            "/api/projects/{project_id}/sprints/{sprint_id}",
+            get(get_sprint),
+        )
+        .route(
+            "/api/projects/{project_id}/sprints/{sprint_id}",
+            put(update_sprint),
+        )
+        .route(
+            "/api/projects/{project_id}/sprints/{sprint_id}",
+            delete(delete_sprint),
+        )
+        .route(
+            "/api/projects/{project_id}/sprints/{sprint_id}/start",
+            post(start_sprint),
+        )
+        .route(
+            "/api/projects/{project_id}/sprints/{sprint_id}/complete",
+            post(complete_sprint),
+        )
+        // Board routes
+        .route("/api/projects/{project_id}/board", get(get_board))
+        // Event stream
+        .route("/api/projects/{project_id}/events", get(stream_events))
+        // Subscriptions and presence over a WebSocket
+        .route("/api/ws", get(connect))
+        // Webhook routes
+        .route("/api/projects/{project_id}/webhooks", get(get_webhooks))
+        .route("/api/projects/{project_id}/webhooks", post(create_webhook))
+        .route(
+            "/api/projects/{project_id}/webhooks/{webhook_id}",
+            get(get_webhook),
+        )
+        .route(
+            "/api/projects/{project_id}/webhooks/{webhook_id}",
+            put(update_webhook),
+        )
+        .route(
+            "/api/projects/{project_id}/webhooks/{webhook_id}",
+            delete(delete_webhook),
+        )
+        .route(
+            "/api/projects/{project_id}/webhooks/{webhook_id}/deliveries",
+            get(get_deliveries),
+        )
+        // Member routes
+        .route("/api/projects/{project_id}/members", get(get_members))
+        .route(
+            "/api/projects/{project_id}/members/{user_id}",
+            put(set_member),
+        )
+        .route(
+            "/api/projects/{project_id}/members/{user_id}",
+            delete(remove_member),
+        )
+        // User routes
+        .route("/api/users", post(create_user))
+        .route("/api/users", get(get_users))
+        .route("/api/users/{user_id}", get(get_user))
+        .route("/api/users/{user_id}", put(update_user))
+        .route("/api/users/{user_id}", delete(delete_user))
+        // Admin token routes
+        .route("/api/admin/tokens", post(create_token))
+        .route("/api/admin/tokens", get(get_tokens))
+        .route("/api/admin/tokens/{token_id}", delete(revoke_token))
         // Issue routes
-        .route("/api/projects/{project_name}/issues", post(create_issue))
+        .route("/api/issues/overdue", get(get_overdue_issues))
+        .route("/api/issues/{issue_key}", get(get_issue_by_key))
+        .route("/api/projects/{project_id}/issues", post(create_issue))
+        .route("/api/projects/{project_id}/issues", get(get_project_issues))
+        .route("/api/projects/{project_id}/issues.csv", get(export_issues))
+        .route(
+            "/api/projects/{project_id}/issues/import",
+            post(import_issues),
+        )
         .route(
-            "/api/projects/{project_name}/issues",
-            get(get_project_issues),
+            "/api/projects/{project_id}/issues/{issue_id}",
+            get(get_issue),
         )
         .route(
-            "/api/projects/{project_name}/issues/{issue_id}",
+            "/api/projects/{project_id}/issues/{issue_id}",
             put(update_issue),
         )
         .route(
-            "/api/projects/{project_name}/issues/{issue_id}",
+            "/api/projects/{project_id}/issues/{issue_id}",
             delete(delete_issue),
         )
+        .route(
+            "/api/projects/{project_id}/issues/{issue_id}/history",
+            get(get_issue_history),
+        )
+        .route(
+            "/api/projects/{project_id}/issues/{issue_id}/subtree",
+            get(get_subtree),
+        )
+        .route(
+            "/api/projects/{project_id}/issues/{issue_id}/move",
+            post(move_issue),
+        )
+        // Link routes
+        .route(
+            "/api/projects/{project_id}/issues/{issue_id}/links",
+            get(get_links),
+        )
+        .route(
+            "/api/projects/{project_id}/issues/{issue_id}/links",
+            post(create_link),
+        )
+        .route(
+            "/api/projects/{project_id}/issues/{issue_id}/links/{link_id}",
+            delete(delete_link),
+        )
+        .route(
+            "/api/projects/{project_id}/issues/{issue_id}/dependencies",
+            get(get_dependencies),
+        )
+        // Comment routes
+        .route(
+            "/api/projects/{project_id}/issues/{issue_id}/comments",
+            post(create_comment),
+        )
+        .route(
+            "/api/projects/{project_id}/issues/{issue_id}/comments",
+            get(get_comments),
+        )
+        .route(
+            "/api/projects/{project_id}/issues/{issue_id}/comments/{comment_id}",
+            get(get_comment),
+        )
+        .route(
+            "/api/projects/{project_id}/issues/{issue_id}/comments/{comment_id}",
+            put(update_comment),
+        )
+        .route(
+            "/api/projects/{project_id}/issues/{issue_id}/comments/{comment_id}",
+            delete(delete_comment),
+        )
+        // Worklog routes
+        .route(
+            "/api/projects/{project_id}/issues/{issue_id}/worklogs",
+            post(create_worklog),
+        )
+        .route(
+            "/api/projects/{project_id}/issues/{issue_id}/worklogs",
+            get(get_worklogs),
+        )
+        .route(
+            "/api/projects/{project_id}/issues/{issue_id}/worklogs/{worklog_id}",
+            put(update_worklog),
+        )
+        .route(
+            "/api/projects/{project_id}/issues/{issue_id}/worklogs/{worklog_id}",
+            delete(delete_worklog),
+        )
+        .route_layer(middleware::from_fn_with_state(state.clone(), require_auth))
         .with_state(state)
 }
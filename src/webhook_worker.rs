@@ -0,0 +1,291 @@
+//! Background delivery of queued webhook events.
+//!
+//! Handlers only write to the `webhook_deliveries` outbox; this worker polls
+//! it, POSTs each payload to its webhook and records the outcome. Failed
+//! attempts are retried with exponential backoff until `max_attempts`.
+
+use crate::auth::to_hex;
+use crate::error::AppError;
+use crate::models::{Webhook, WebhookDelivery};
+use crate::routes::router::DbPool;
+
+use chrono::Utc;
+use hmac::{Hmac, Mac};
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use reqwest::header::CONTENT_TYPE;
+use reqwest::Url;
+use sha2::Sha256;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+
+/// `sha256=` followed by the hex HMAC of `{timestamp}.{body}`.
+pub const SIGNATURE_HEADER: &str = "X-Webhook-Signature";
+/// Unix time the request was signed at.
+pub const TIMESTAMP_HEADER: &str = "X-Webhook-Timestamp";
+pub const EVENT_HEADER: &str = "X-Webhook-Event";
+/// Delivery ID, the same on every retry.
+pub const DELIVERY_HEADER: &str = "X-Webhook-Delivery";
+
+#[derive(Debug, Clone)]
+pub struct WorkerConfig {
+    /// Pause between polls once the outbox has no due deliveries.
+    pub poll_interval: Duration,
+    /// Deliveries claimed per poll.
+    pub batch_size: i64,
+    pub request_timeout: Duration,
+    /// How long a claimed delivery is hidden from other workers.
+    pub lease: chrono::Duration,
+    /// Attempts before a delivery is marked failed.
+    pub max_attempts: i32,
+    /// Delay after the first failure; it doubles with every further one.
+    pub base_backoff: chrono::Duration,
+    pub max_backoff: chrono::Duration,
+    /// Deliver to loopback, private and link-local addresses too; see
+    /// `config::allow_private_webhook_hosts`.
+    pub allow_private_hosts: bool,
+}
+
+impl Default for WorkerConfig {
+    fn default() -> Self {
+        WorkerConfig {
+            poll_interval: Duration::from_secs(5),
+            batch_size: 20,
+            request_timeout: Duration::from_secs(10),
+            lease: chrono::Duration::minutes(5),
+            max_attempts: 8,
+            base_backoff: chrono::Duration::seconds(30),
+            max_backoff: chrono::Duration::hours(6),
+            allow_private_hosts: false,
+        }
+    }
+}
+
+impl WorkerConfig {
+    /// Delay before the next attempt after `failures` failed ones.
+    pub fn backoff(&self, failures: i32) -> chrono::Duration {
+        let exponent = (failures - 1).clamp(0, 20) as u32;
+        (self.base_backoff * 2i32.pow(exponent)).min(self.max_backoff)
+    }
+}
+
+/// Signature sent in `SIGNATURE_HEADER`. Receivers recompute it over the
+/// raw request body and the `TIMESTAMP_HEADER` value.
+pub fn signature(secret: &str, timestamp: i64, body: &str) -> String {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC accepts keys of any size");
+    mac.update(timestamp.to_string().as_bytes());
+    mac.update(b".");
+    mac.update(body.as_bytes());
+    format!("sha256={}", to_hex(&mac.finalize().into_bytes()))
+}
+
+/// Whether webhooks may be sent to `ip`. Loopback, private, link-local,
+/// shared, reserved, documentation, benchmarking, broadcast, multicast and
+/// unspecified addresses all lead into the server's own network or nowhere,
+/// and so do IPv6 forms that embed an IPv4 address.
+pub fn is_public_address(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [first, second, third, _] = ip.octets();
+            !(ip.is_unspecified()
+                || ip.is_loopback()
+                || ip.is_private()
+                || ip.is_link_local()
+                || ip.is_multicast()
+                || ip.is_documentation()
+                || first == 0
+                // 100.64.0.0/10, carrier-grade NAT
+                || (first == 100 && second & 0xc0 == 64)
+                // 192.0.0.0/24, protocol assignments
+                || (first == 192 && second == 0 && third == 0)
+                // 198.18.0.0/15, benchmarking
+                || (first == 198 && second & 0xfe == 18)
+                // 240.0.0.0/4, reserved, including broadcast
+                || first >= 240)
+        }
+        IpAddr::V6(ip) => {
+            let segments = ip.segments();
+            match ip.to_ipv4_mapped() {
+                Some(ip) => is_public_address(IpAddr::V4(ip)),
+                None => {
+                    !(ip.is_unspecified()
+                        || ip.is_loopback()
+                        || ip.is_unique_local()
+                        || ip.is_unicast_link_local()
+                        || ip.is_multicast()
+                        // 64:ff9b::/96 and 64:ff9b:1::/48, NAT64
+                        || (segments[0] == 0x64 && segments[1] == 0xff9b)
+                        // 2002::/16, 6to4
+                        || segments[0] == 0x2002
+                        // 2001:db8::/32, documentation
+                        || (segments[0] == 0x2001 && segments[1] == 0xdb8))
+                }
+            }
+        }
+    }
+}
+
+/// Checks every address the URL's host resolves to with
+/// `is_public_address`. A name that does not resolve passes, since there is
+/// nothing behind it to reach.
+pub async fn check_destination(url: &Url) -> Result<(), String> {
+    let host = url.host_str().unwrap_or_default();
+    let addresses: Vec<IpAddr> = match host.trim_matches(['[', ']']).parse() {
+        Ok(ip) => vec![ip],
+        Err(_) => match tokio::net::lookup_host((host, 0)).await {
+            Ok(found) => found.map(|addr| addr.ip()).collect(),
+            Err(_) => Vec::new(),
+        },
+    };
+    match addresses.into_iter().find(|ip| !is_public_address(*ip)) {
+        Some(ip) => Err(format!(
+            "'{}' points at {}, which is not a public address",
+            host, ip
+        )),
+        None => Ok(()),
+    }
+}
+
+/// Resolves host names for deliveries and refuses any that lead to a
+/// non-public address, so a name cannot pass `check_destination` and then
+/// resolve somewhere else for the request itself.
+struct PublicResolver;
+
+impl Resolve for PublicResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let found: Vec<SocketAddr> =
+                tokio::net::lookup_host((name.as_str(), 0)).await?.collect();
+            if let Some(addr) = found.iter().find(|addr| !is_public_address(addr.ip())) {
+                let error = format!(
+                    "'{}' points at {}, which is not a public address",
+                    name.as_str(),
+                    addr.ip()
+                );
+                return Err(error.into());
+            }
+            let addrs: Addrs = Box::new(found.into_iter());
+            Ok(addrs)
+        })
+    }
+}
+
+pub fn http_client(config: &WorkerConfig) -> reqwest::Client {
+    let mut builder = reqwest::Client::builder()
+        .timeout(config.request_timeout)
+        .redirect(reqwest::redirect::Policy::none());
+    if !config.allow_private_hosts {
+        builder = builder.dns_resolver(Arc::new(PublicResolver));
+    }
+    builder
+        .build()
+        .expect("Failed to build webhook HTTP client")
+}
+
+/// Sends the payload once. A 2xx response is a success; anything else is
+/// an error with the response status, if there was one.
+async fn send(
+    client: &reqwest::Client,
+    delivery: &WebhookDelivery,
+    webhook: &Webhook,
+) -> Result<i32, (Option<i32>, String)> {
+    let timestamp = Utc::now().timestamp();
+    let response = client
+        .post(&webhook.url)
+        .header(CONTENT_TYPE, "application/json")
+        .header(EVENT_HEADER, &delivery.event)
+        .header(DELIVERY_HEADER, delivery.id.to_string())
+        .header(TIMESTAMP_HEADER, timestamp.to_string())
+        .header(
+            SIGNATURE_HEADER,
+            signature(&webhook.secret, timestamp, &delivery.payload),
+        )
+        .body(delivery.payload.clone())
+        .send()
+        .await;
+
+    match response {
+        Ok(response) => {
+            let status = response.status();
+            if status.is_success() {
+                Ok(i32::from(status.as_u16()))
+            } else {
+                Err((
+                    Some(i32::from(status.as_u16())),
+                    format!("Endpoint responded with {}", status),
+                ))
+            }
+        }
+        Err(err) => Err((None, err.to_string())),
+    }
+}
+
+/// Checks the webhook's destination again before a delivery, as the
+/// addresses its host resolves to may have changed since it was saved.
+async fn check_target(config: &WorkerConfig, url: &str) -> Result<(), String> {
+    if config.allow_private_hosts {
+        return Ok(());
+    }
+    let url = Url::parse(url).map_err(|err| err.to_string())?;
+    check_destination(&url).await
+}
+
+/// Attempts every delivery that is due, once, and returns how many were
+/// attempted. The connection is only held between requests, not during.
+pub async fn deliver_due(
+    pool: &DbPool,
+    client: &reqwest::Client,
+    config: &WorkerConfig,
+) -> Result<usize, AppError> {
+    let claimed = {
+        let mut conn = pool.get()?;
+        WebhookDelivery::claim_due(&mut conn, config.batch_size, config.lease)?
+    };
+
+    for (delivery, webhook) in &claimed {
+        let outcome = match check_target(config, &webhook.url).await {
+            Ok(()) => send(client, delivery, webhook).await,
+            Err(error) => Err((None, error)),
+        };
+        let mut conn = pool.get()?;
+        match outcome {
+            Ok(status) => {
+                WebhookDelivery::mark_delivered(&mut conn, delivery.id, status)?;
+            }
+            Err((status, error)) => {
+                let failures = delivery.attempts + 1;
+                let retry_at =
+                    (failures < config.max_attempts).then(|| Utc::now() + config.backoff(failures));
+                if retry_at.is_none() {
+                    tracing::warn!(
+                        "Giving up on webhook delivery {} after {} attempts: {}",
+                        delivery.id,
+                        failures,
+                        error
+                    );
+                }
+                WebhookDelivery::mark_failed(&mut conn, delivery.id, status, &error, retry_at)?;
+            }
+        }
+    }
+
+    Ok(claimed.len())
+}
+
+/// Runs the delivery loop on the current runtime until the process exits.
+pub fn spawn(pool: DbPool, config: WorkerConfig) -> JoinHandle<()> {
+    tokio::spawn(async move {
+        let client = http_client(&config);
+        loop {
+            match deliver_due(&pool, &client, &config).await {
+                // A full batch means more may be waiting
+                Ok(count) if count as i64 >= config.batch_size => continue,
+                Ok(_) => {}
+                Err(err) => tracing::error!("Webhook delivery failed: {}", err),
+            }
+            tokio::time::sleep(config.poll_interval).await;
+        }
+    })
+}
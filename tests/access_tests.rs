@@ -0,0 +1,184 @@
+mod common;
+
+use axum::http::StatusCode;
+use common::{
+    add_project_member, create_named_issue, create_test_pool, create_token_request,
+    create_user_token, read_json, setup_test_project, setup_test_user,
+};
+use serde_json::json;
+use ticket_manager::models::ProjectRole;
+use ticket_manager::routes::router::create_router;
+use tower::ServiceExt;
+
+#[tokio::test]
+async fn test_viewer_can_read_but_not_change_project() {
+    let pool = create_test_pool();
+    let project_id = setup_test_project(&pool).await;
+    let viewer_id = setup_test_user(&pool).await;
+    add_project_member(&pool, project_id, viewer_id, ProjectRole::Viewer).await;
+    let token = create_user_token(&pool, viewer_id).await;
+    let project_uri = format!("/api/projects/{}", project_id);
+
+    let app = create_router(pool.clone());
+    let response = app
+        .oneshot(create_token_request("GET", &project_uri, &token, None))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let app = create_router(pool.clone());
+    let response = app
+        .oneshot(create_token_request(
+            "PUT",
+            &project_uri,
+            &token,
+            Some(json!({ "name": "Renamed" })),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    assert!(read_json(response).await["error"].is_string());
+
+    let app = create_router(pool.clone());
+    let response = app
+        .oneshot(create_token_request("DELETE", &project_uri, &token, None))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_non_members_cannot_see_project() {
+    let pool = create_test_pool();
+    let project_id = setup_test_project(&pool).await;
+    let outsider_id = setup_test_user(&pool).await;
+    let token = create_user_token(&pool, outsider_id).await;
+
+    let app = create_router(pool.clone());
+    let response = app
+        .oneshot(create_token_request(
+            "GET",
+            &format!("/api/projects/{}/issues", project_id),
+            &token,
+            None,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let app = create_router(pool.clone());
+    let response = app
+        .oneshot(create_token_request("GET", "/api/projects", &token, None))
+        .await
+        .unwrap();
+    let body = read_json(response).await;
+    assert_eq!(body["total"], 0);
+}
+
+#[tokio::test]
+async fn test_creator_administers_new_project() {
+    let pool = create_test_pool();
+    setup_test_project(&pool).await;
+    let user_id = setup_test_user(&pool).await;
+    let token = create_user_token(&pool, user_id).await;
+
+    let app = create_router(pool.clone());
+    let response = app
+        .oneshot(create_token_request(
+            "POST",
+            "/api/projects",
+            &token,
+            Some(json!({ "name": "Owned Project" })),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let project_id = read_json(response).await["id"].as_i64().unwrap();
+
+    let app = create_router(pool.clone());
+    let response = app
+        .oneshot(create_token_request(
+            "GET",
+            &format!("/api/projects/{}/members", project_id),
+            &token,
+            None,
+        ))
+        .await
+        .unwrap();
+    let members = read_json(response).await;
+    assert_eq!(members[0]["user"]["id"], user_id);
+    assert_eq!(members[0]["role"], "admin");
+
+    let app = create_router(pool.clone());
+    let response = app
+        .oneshot(create_token_request(
+            "DELETE",
+            &format!("/api/projects/{}", project_id),
+            &token,
+            None,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+}
+
+#[tokio::test]
+async fn test_reporters_only_edit_their_own_issues() {
+    let pool = create_test_pool();
+    let project_id = setup_test_project(&pool).await;
+    let reporter_id = setup_test_user(&pool).await;
+    add_project_member(&pool, project_id, reporter_id, ProjectRole::Reporter).await;
+    let token = create_user_token(&pool, reporter_id).await;
+    let others_issue = create_named_issue(&pool, project_id, "Not mine", "open", None).await;
+
+    let app = create_router(pool.clone());
+    let response = app
+        .oneshot(create_token_request(
+            "POST",
+            &format!("/api/projects/{}/issues", project_id),
+            &token,
+            Some(json!({ "title": "Mine", "description": "Filed by the reporter" })),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let own_issue = read_json(response).await;
+    assert_eq!(own_issue["created_by"]["id"], reporter_id);
+
+    let app = create_router(pool.clone());
+    let response = app
+        .oneshot(create_token_request(
+            "PUT",
+            &format!("/api/projects/{}/issues/{}", project_id, own_issue["id"]),
+            &token,
+            Some(json!({ "title": "Mine, edited" })),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let app = create_router(pool.clone());
+    let response = app
+        .oneshot(create_token_request(
+            "PUT",
+            &format!("/api/projects/{}/issues/{}", project_id, others_issue),
+            &token,
+            Some(json!({ "title": "Hijacked" })),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    add_project_member(&pool, project_id, reporter_id, ProjectRole::Developer).await;
+    let app = create_router(pool.clone());
+    let response = app
+        .oneshot(create_token_request(
+            "PUT",
+            &format!("/api/projects/{}/issues/{}", project_id, others_issue),
+            &token,
+            Some(json!({ "title": "Triaged" })),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+}
@@ -0,0 +1,186 @@
+mod common;
+
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+};
+use common::{
+    cleanup_test_data, create_empty_request, create_json_request, create_test_pool,
+    create_token_request, create_user_token, read_json, setup_test_user,
+};
+use serde_json::json;
+use ticket_manager::routes::router::create_router;
+use tower::ServiceExt;
+
+#[tokio::test]
+async fn test_requests_without_valid_token_are_rejected() {
+    let pool = create_test_pool();
+
+    let app = create_router(pool.clone());
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/projects")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    let body = read_json(response).await;
+    assert!(body["error"].is_string());
+
+    let app = create_router(pool);
+    let response = app
+        .oneshot(create_token_request(
+            "GET",
+            "/api/projects",
+            "tm_not_a_token",
+            None,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_issued_token_works_until_revoked() {
+    let pool = create_test_pool();
+    cleanup_test_data(&pool).await;
+    let user_id = setup_test_user(&pool).await;
+
+    let app = create_router(pool.clone());
+    let response = app
+        .oneshot(create_json_request(
+            "POST",
+            "/api/admin/tokens",
+            json!({ "user_id": user_id, "name": "ci" }),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let issued = read_json(response).await;
+    let token = issued["token"].as_str().unwrap().to_string();
+    assert!(token.starts_with(issued["token_prefix"].as_str().unwrap()));
+
+    let app = create_router(pool.clone());
+    let response = app
+        .oneshot(create_token_request("GET", "/api/projects", &token, None))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let app = create_router(pool.clone());
+    let response = app
+        .oneshot(create_empty_request(
+            "GET",
+            &format!("/api/admin/tokens?user_id={}", user_id),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let listed = read_json(response).await;
+    assert_eq!(listed.as_array().unwrap().len(), 1);
+    assert!(listed[0].get("token").is_none());
+    assert!(listed[0]["last_used_at"].is_string());
+
+    let app = create_router(pool.clone());
+    let response = app
+        .oneshot(create_empty_request(
+            "DELETE",
+            &format!("/api/admin/tokens/{}", issued["id"]),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let app = create_router(pool);
+    let response = app
+        .oneshot(create_token_request("GET", "/api/projects", &token, None))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn test_admin_endpoints_require_admin() {
+    let pool = create_test_pool();
+    cleanup_test_data(&pool).await;
+    let user_id = setup_test_user(&pool).await;
+
+    let app = create_router(pool.clone());
+    let response = app
+        .oneshot(create_json_request(
+            "POST",
+            "/api/admin/tokens",
+            json!({ "user_id": user_id, "name": "regular" }),
+        ))
+        .await
+        .unwrap();
+    let token = read_json(response).await["token"]
+        .as_str()
+        .unwrap()
+        .to_string();
+
+    let app = create_router(pool);
+    let response = app
+        .oneshot(create_token_request(
+            "GET",
+            "/api/admin/tokens",
+            &token,
+            None,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_user_management_requires_admin() {
+    let pool = create_test_pool();
+    cleanup_test_data(&pool).await;
+    let user_id = setup_test_user(&pool).await;
+    let other_id = setup_test_user(&pool).await;
+    let token = create_user_token(&pool, user_id).await;
+    let app = create_router(pool.clone());
+
+    let requests = [
+        ("GET", "/api/users".to_string(), None),
+        ("GET", format!("/api/users/{}", other_id), None),
+        (
+            "POST",
+            "/api/users".to_string(),
+            Some(json!({ "username": "intruder" })),
+        ),
+        (
+            "PUT",
+            format!("/api/users/{}", other_id),
+            Some(json!({ "is_active": false })),
+        ),
+        ("DELETE", format!("/api/users/{}", other_id), None),
+    ];
+    for (method, uri, body) in requests {
+        let response = app
+            .clone()
+            .oneshot(create_token_request(method, &uri, &token, body))
+            .await
+            .unwrap();
+        assert_eq!(
+            response.status(),
+            StatusCode::FORBIDDEN,
+            "{} {}",
+            method,
+            uri
+        );
+    }
+
+    // Nothing changed
+    let response = app
+        .oneshot(create_empty_request(
+            "GET",
+            &format!("/api/users/{}", other_id),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(read_json(response).await["is_active"], true);
+}
@@ -0,0 +1,321 @@
+mod common;
+
+use axum::http::StatusCode;
+use common::{
+    add_project_member, create_issue, create_test_pool, create_user_token, get, send, send_as,
+    setup_test_project, setup_test_user,
+};
+use serde_json::{json, Value};
+use ticket_manager::models::ProjectRole;
+use ticket_manager::routes::router::DbPool;
+
+/// Issue ids of each board column, keyed by status.
+async fn columns(pool: &DbPool, project_id: i32) -> Value {
+    let board = get(pool, &format!("/api/projects/{}/board", project_id)).await;
+    let columns = board["columns"].as_array().unwrap();
+    let statuses: Vec<&str> = columns
+        .iter()
+        .map(|c| c["status"].as_str().unwrap())
+        .collect();
+    assert_eq!(statuses, ["open", "in_progress", "resolved", "closed"]);
+    columns
+        .iter()
+        .map(|column| {
+            let ids: Vec<Value> = column["issues"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|issue| issue["id"].clone())
+                .collect();
+            (column["status"].as_str().unwrap().to_string(), json!(ids))
+        })
+        .collect::<serde_json::Map<_, _>>()
+        .into()
+}
+
+#[tokio::test]
+async fn test_reordering_within_a_column() {
+    let pool = create_test_pool();
+    let project_id = setup_test_project(&pool).await;
+    let mut ids = Vec::new();
+    for title in ["First", "Second", "Third"] {
+        ids.push(
+            create_issue(
+                &pool,
+                project_id,
+                json!({ "title": title, "description": "Card" }),
+            )
+            .await,
+        );
+    }
+    let (a, b, c) = (ids[0], ids[1], ids[2]);
+    let move_uri = |id: i64| format!("/api/projects/{}/issues/{}/move", project_id, id);
+    assert_eq!(columns(&pool, project_id).await["open"], json!([a, b, c]));
+
+    let (status, moved) = send(&pool, "POST", &move_uri(c), json!({ "after_id": a })).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(moved["status"], "open");
+    assert_eq!(columns(&pool, project_id).await["open"], json!([a, c, b]));
+
+    let (status, _) = send(&pool, "POST", &move_uri(b), json!({ "before_id": a })).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(columns(&pool, project_id).await["open"], json!([b, a, c]));
+
+    // Repeated drops into the same gap keep finding room
+    for _ in 0..20 {
+        let (status, _) = send(
+            &pool,
+            "POST",
+            &move_uri(c),
+            json!({ "after_id": b, "before_id": a }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+        let (status, _) = send(
+            &pool,
+            "POST",
+            &move_uri(a),
+            json!({ "after_id": b, "before_id": c }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+    }
+    assert_eq!(columns(&pool, project_id).await["open"], json!([b, a, c]));
+
+    let (status, _) = send(&pool, "POST", &move_uri(b), json!({})).await;
+    assert_eq!(status, StatusCode::OK);
+    let d = create_issue(
+        &pool,
+        project_id,
+        json!({ "title": "Fourth", "description": "Card" }),
+    )
+    .await;
+    assert_eq!(
+        columns(&pool, project_id).await["open"],
+        json!([a, c, b, d])
+    );
+
+    let (status, _) = send(
+        &pool,
+        "POST",
+        &move_uri(d),
+        json!({ "after_id": b, "before_id": a }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = send(&pool, "POST", &move_uri(d), json!({ "after_id": d })).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    // Reordering is not an edit worth recording
+    let history = get(
+        &pool,
+        &format!("/api/projects/{}/issues/{}/history", project_id, c),
+    )
+    .await;
+    assert_eq!(history, json!([]));
+}
+
+#[tokio::test]
+async fn test_moving_across_columns_follows_status_rules() {
+    let pool = create_test_pool();
+    let project_id = setup_test_project(&pool).await;
+    let parent = create_issue(
+        &pool,
+        project_id,
+        json!({ "title": "Parent", "description": "Card" }),
+    )
+    .await;
+    let child = create_issue(
+        &pool,
+        project_id,
+        json!({ "title": "Child", "description": "Card", "parent_id": parent }),
+    )
+    .await;
+    let other = create_issue(
+        &pool,
+        project_id,
+        json!({ "title": "Other", "description": "Card" }),
+    )
+    .await;
+    let move_uri = |id: i64| format!("/api/projects/{}/issues/{}/move", project_id, id);
+
+    let (status, moved) = send(
+        &pool,
+        "POST",
+        &move_uri(other),
+        json!({ "status": "in_progress" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(moved["status"], "in_progress");
+    let (status, moved) = send(
+        &pool,
+        "POST",
+        &move_uri(child),
+        json!({ "status": "in_progress", "before_id": other }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(moved["is_open"], true);
+    let board = columns(&pool, project_id).await;
+    assert_eq!(board["open"], json!([parent]));
+    assert_eq!(board["in_progress"], json!([child, other]));
+
+    // Anchors must be in the target column
+    let (status, _) = send(
+        &pool,
+        "POST",
+        &move_uri(child),
+        json!({ "status": "open", "after_id": other }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, body) = send(
+        &pool,
+        "POST",
+        &move_uri(parent),
+        json!({ "status": "resolved" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(body["code"], "open_children");
+    let (status, moved) = send(
+        &pool,
+        "POST",
+        &format!("{}?force=true", move_uri(parent)),
+        json!({ "status": "resolved" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(moved["is_open"], false);
+
+    let (status, body) = send(
+        &pool,
+        "POST",
+        &move_uri(parent),
+        json!({ "status": "in_progress", "after_id": child }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(body["code"], "invalid_transition");
+
+    let board = columns(&pool, project_id).await;
+    assert_eq!(board["open"], json!([]));
+    assert_eq!(board["resolved"], json!([parent]));
+    let history = get(
+        &pool,
+        &format!("/api/projects/{}/issues/{}/history", project_id, parent),
+    )
+    .await;
+    let fields: Vec<&str> = history
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|entry| entry["field"].as_str().unwrap())
+        .collect();
+    assert!(fields.contains(&"status"));
+    assert!(!fields.contains(&"rank"));
+}
+
+#[tokio::test]
+async fn test_wip_limits() {
+    let pool = create_test_pool();
+    let project_id = setup_test_project(&pool).await;
+    let developer_id = setup_test_user(&pool).await;
+    add_project_member(&pool, project_id, developer_id, ProjectRole::Developer).await;
+    let token = create_user_token(&pool, developer_id).await;
+    let issues_uri = format!("/api/projects/{}/issues", project_id);
+    let move_uri = |id: i64| format!("{}/{}/move", issues_uri, id);
+
+    let workflow_uri = format!("/api/projects/{}/workflow", project_id);
+    let mut workflow = get(&pool, &workflow_uri).await;
+    assert_eq!(workflow["states"][1]["name"], "in_progress");
+    workflow["states"][1]["wip_limit"] = json!(0);
+    let (status, _) = send(&pool, "PUT", &workflow_uri, workflow.clone()).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    workflow["states"][1]["wip_limit"] = json!(1);
+    let (status, body) = send(&pool, "PUT", &workflow_uri, workflow).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["states"][1]["wip_limit"], 1);
+
+    let mut ids = Vec::new();
+    for title in ["First", "Second"] {
+        let (status, issue) = send_as(
+            &pool,
+            &token,
+            "POST",
+            &issues_uri,
+            Some(json!({ "title": title, "description": "Card" })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+        ids.push(issue["id"].as_i64().unwrap());
+    }
+    let (first, second) = (ids[0], ids[1]);
+
+    let (status, _) = send_as(
+        &pool,
+        &token,
+        "POST",
+        &move_uri(first),
+        Some(json!({ "status": "in_progress" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    // Every way into a full column is refused for non-maintainers
+    let (status, body) = send_as(
+        &pool,
+        &token,
+        "POST",
+        &move_uri(second),
+        Some(json!({ "status": "in_progress" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(body["code"], "wip_limit_reached");
+    assert_eq!(body["details"]["wip_limit"], 1);
+    let (status, body) = send_as(
+        &pool,
+        &token,
+        "PUT",
+        &format!("{}/{}", issues_uri, second),
+        Some(json!({ "status": "in_progress" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(body["code"], "wip_limit_reached");
+    let (status, body) = send_as(
+        &pool,
+        &token,
+        "POST",
+        &issues_uri,
+        Some(json!({ "title": "Third", "description": "Card", "status": "in_progress" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(body["code"], "wip_limit_reached");
+
+    // Reordering inside the column is not adding to it
+    let (status, _) = send_as(&pool, &token, "POST", &move_uri(first), Some(json!({}))).await;
+    assert_eq!(status, StatusCode::OK);
+
+    // Maintainers may go over the limit
+    let (status, _) = send(
+        &pool,
+        "POST",
+        &move_uri(second),
+        json!({ "status": "in_progress", "after_id": first }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let board = get(&pool, &format!("/api/projects/{}/board", project_id)).await;
+    let column = &board["columns"][1];
+    assert_eq!(column["status"], "in_progress");
+    assert_eq!(column["issue_count"], 2);
+    assert_eq!(column["wip_limit"], 1);
+    assert_eq!(column["over_limit"], true);
+    assert_eq!(board["columns"][0]["wip_limit"], Value::Null);
+}
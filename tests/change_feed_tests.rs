@@ -0,0 +1,152 @@
+mod common;
+
+use common::{create_test_pool, setup_test_issue, setup_test_project};
+use diesel::sql_types::Integer;
+use diesel::{sql_query, RunQueryDsl};
+use serde_json::Value;
+use std::sync::Arc;
+use std::time::Duration;
+use ticket_manager::change_feed::{self, FeedConfig, FeedStatus, LISTENER_NAME};
+use ticket_manager::db::create_pool;
+use ticket_manager::event_bus::ProjectEvent;
+use ticket_manager::routes::router::{AppState, DbPool};
+use tokio::sync::broadcast::Receiver;
+
+fn feed_config() -> FeedConfig {
+    FeedConfig {
+        poll_interval: Duration::from_millis(20),
+        min_reconnect_delay: Duration::from_millis(50),
+        ..FeedConfig::default()
+    }
+}
+
+/// Starts a listener for a fresh instance and waits until it listens.
+async fn start_feed(pool: &DbPool) -> (Arc<AppState>, Arc<FeedStatus>) {
+    let state = Arc::new(AppState::new(pool.clone()));
+    let database_url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set");
+    let status = change_feed::spawn(state.clone(), database_url, feed_config());
+    wait_for_sessions(&status, 1).await;
+    (state, status)
+}
+
+async fn wait_for_sessions(status: &FeedStatus, sessions: u64) {
+    for _ in 0..200 {
+        if status.sessions() >= sessions {
+            return;
+        }
+        tokio::time::sleep(Duration::from_millis(25)).await;
+    }
+    panic!("The change feed did not start listening");
+}
+
+/// The next event, with its envelope parsed.
+async fn next_event(receiver: &mut Receiver<Arc<ProjectEvent>>) -> (String, Value) {
+    let event = tokio::time::timeout(Duration::from_secs(5), receiver.recv())
+        .await
+        .expect("Timed out waiting for an event")
+        .expect("Event bus closed");
+    (
+        event.event.clone(),
+        serde_json::from_str(&event.data).unwrap(),
+    )
+}
+
+fn execute(pool: &DbPool, query: &str, id: i32) {
+    let mut conn = pool.get().expect("Failed to get DB connection");
+    sql_query(query)
+        .bind::<Integer, _>(id)
+        .execute(&mut conn)
+        .expect("Failed to run query");
+}
+
+#[tokio::test]
+async fn test_changes_from_other_instances_are_published() {
+    // Connections of the test pool stand in for another instance
+    let pool = create_test_pool();
+    let project_id = setup_test_project(&pool).await;
+    let (state, _) = start_feed(&pool).await;
+    let mut events = state.events.receiver();
+
+    let issue_id = setup_test_issue(&pool, project_id).await;
+    let (event, envelope) = next_event(&mut events).await;
+    assert_eq!(event, "issue.created");
+    assert_eq!(envelope["project_id"], project_id);
+    assert_eq!(envelope["actor_id"], Value::Null);
+    assert_eq!(envelope["data"]["id"], issue_id);
+    assert!(envelope["data"]["key"].is_string());
+
+    execute(
+        &pool,
+        "UPDATE issues SET title = 'Renamed elsewhere' WHERE id = $1",
+        issue_id,
+    );
+    let (event, envelope) = next_event(&mut events).await;
+    assert_eq!(event, "issue.updated");
+    assert_eq!(envelope["data"]["title"], "Renamed elsewhere");
+
+    execute(
+        &pool,
+        "UPDATE projects SET description = 'Changed elsewhere' WHERE id = $1",
+        project_id,
+    );
+    let (event, envelope) = next_event(&mut events).await;
+    assert_eq!(event, "project.updated");
+    assert_eq!(envelope["data"]["description"], "Changed elsewhere");
+
+    execute(&pool, "DELETE FROM issues WHERE id = $1", issue_id);
+    let (event, envelope) = next_event(&mut events).await;
+    assert_eq!(event, "issue.deleted");
+    assert_eq!(envelope["data"]["id"], issue_id);
+    assert_eq!(envelope["data"]["project_id"], project_id);
+}
+
+#[tokio::test]
+async fn test_own_changes_are_not_published_twice() {
+    let pool = create_test_pool();
+    let project_id = setup_test_project(&pool).await;
+    let (state, _) = start_feed(&pool).await;
+    let mut events = state.events.receiver();
+    let database_url = std::env::var("DATABASE_URL").unwrap();
+    let own_pool = create_pool(&database_url);
+
+    execute(
+        &own_pool,
+        "UPDATE projects SET description = 'Mine' WHERE id = $1",
+        project_id,
+    );
+    execute(
+        &pool,
+        "UPDATE projects SET description = 'Theirs' WHERE id = $1",
+        project_id,
+    );
+    let (_, envelope) = next_event(&mut events).await;
+    assert_eq!(envelope["data"]["description"], "Theirs");
+}
+
+#[tokio::test]
+async fn test_listener_reconnects_after_connection_loss() {
+    let pool = create_test_pool();
+    let project_id = setup_test_project(&pool).await;
+    let (state, status) = start_feed(&pool).await;
+    let mut events = state.events.receiver();
+
+    {
+        let mut conn = pool.get().unwrap();
+        sql_query(format!(
+            "SELECT pg_terminate_backend(pid) FROM pg_stat_activity WHERE application_name = '{}'",
+            LISTENER_NAME
+        ))
+        .execute(&mut conn)
+        .unwrap();
+    }
+    wait_for_sessions(&status, 2).await;
+
+    execute(
+        &pool,
+        "UPDATE projects SET description = 'After reconnecting' WHERE id = $1",
+        project_id,
+    );
+    let (event, envelope) = next_event(&mut events).await;
+    assert_eq!(event, "project.updated");
+    assert_eq!(envelope["data"]["description"], "After reconnecting");
+}
@@ -0,0 +1,153 @@
+mod common;
+
+use axum::http::StatusCode;
+use common::{
+    add_project_member, create_empty_request, create_json_request, create_test_pool,
+    create_token_request, create_user_token, read_json, setup_test_issue, setup_test_project,
+    setup_test_user,
+};
+use serde_json::json;
+use ticket_manager::models::ProjectRole;
+use ticket_manager::routes::router::create_router;
+use tower::ServiceExt;
+
+#[tokio::test]
+async fn test_comment_threads() {
+    let pool = create_test_pool();
+    let project_id = setup_test_project(&pool).await;
+    let issue_id = setup_test_issue(&pool, project_id).await;
+    let comments_uri = format!("/api/projects/{}/issues/{}/comments", project_id, issue_id);
+
+    let app = create_router(pool.clone());
+    let response = app
+        .oneshot(create_json_request(
+            "POST",
+            &comments_uri,
+            json!({ "body": "First!" }),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let root = read_json(response).await;
+    assert_eq!(root["author"]["username"], "test_admin");
+
+    let app = create_router(pool.clone());
+    let response = app
+        .oneshot(create_json_request(
+            "POST",
+            &comments_uri,
+            json!({ "body": "A reply", "parent_id": root["id"] }),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+
+    let app = create_router(pool.clone());
+    let response = app
+        .oneshot(create_empty_request(
+            "DELETE",
+            &format!("{}/{}", comments_uri, root["id"]),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let app = create_router(pool.clone());
+    let response = app
+        .oneshot(create_empty_request("GET", &comments_uri))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let threads = read_json(response).await;
+    assert_eq!(threads.as_array().unwrap().len(), 1);
+    assert_eq!(threads[0]["is_deleted"], true);
+    assert!(threads[0]["body"].is_null());
+    assert_eq!(threads[0]["replies"][0]["body"], "A reply");
+}
+
+#[tokio::test]
+async fn test_only_author_edits_comment() {
+    let pool = create_test_pool();
+    let project_id = setup_test_project(&pool).await;
+    let issue_id = setup_test_issue(&pool, project_id).await;
+    let comments_uri = format!("/api/projects/{}/issues/{}/comments", project_id, issue_id);
+
+    let author_id = setup_test_user(&pool).await;
+    add_project_member(&pool, project_id, author_id, ProjectRole::Reporter).await;
+    let author_token = create_user_token(&pool, author_id).await;
+    let other_id = setup_test_user(&pool).await;
+    add_project_member(&pool, project_id, other_id, ProjectRole::Developer).await;
+    let other_token = create_user_token(&pool, other_id).await;
+
+    let app = create_router(pool.clone());
+    let response = app
+        .oneshot(create_token_request(
+            "POST",
+            &comments_uri,
+            &author_token,
+            Some(json!({ "body": "Original" })),
+        ))
+        .await
+        .unwrap();
+    let comment_uri = format!("{}/{}", comments_uri, read_json(response).await["id"]);
+
+    let app = create_router(pool.clone());
+    let response = app
+        .oneshot(create_token_request(
+            "PUT",
+            &comment_uri,
+            &other_token,
+            Some(json!({ "body": "Rewritten" })),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let app = create_router(pool.clone());
+    let response = app
+        .oneshot(create_token_request(
+            "PUT",
+            &comment_uri,
+            &author_token,
+            Some(json!({ "body": "Edited" })),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = read_json(response).await;
+    assert_eq!(body["body"], "Edited");
+    assert!(body["edited_at"].is_string());
+}
+
+#[tokio::test]
+async fn test_reply_must_be_on_same_issue() {
+    let pool = create_test_pool();
+    let project_id = setup_test_project(&pool).await;
+    let issue_id = setup_test_issue(&pool, project_id).await;
+    let other_issue_id = setup_test_issue(&pool, project_id).await;
+
+    let app = create_router(pool.clone());
+    let response = app
+        .oneshot(create_json_request(
+            "POST",
+            &format!(
+                "/api/projects/{}/issues/{}/comments",
+                project_id, other_issue_id
+            ),
+            json!({ "body": "Elsewhere" }),
+        ))
+        .await
+        .unwrap();
+    let parent_id = read_json(response).await["id"].clone();
+
+    let app = create_router(pool.clone());
+    let response = app
+        .oneshot(create_json_request(
+            "POST",
+            &format!("/api/projects/{}/issues/{}/comments", project_id, issue_id),
+            json!({ "body": "Reply", "parent_id": parent_id }),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
@@ -1,26 +1,79 @@
-use axum::{body::Body, http::Request};
-use diesel::prelude::*;
+#![allow(dead_code)]
+
+use axum::{
+    body::{to_bytes, Body},
+    http::{Request, StatusCode},
+    response::Response,
+    Router,
+};
 use diesel::r2d2::{ConnectionManager, Pool};
 use diesel::sql_query;
 use diesel::PgConnection;
 use diesel::RunQueryDsl;
-use serde_json::json;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use ticket_manager::{
-    models::{Issue, Project},
-    routes::router::DbPool,
+    auth::{display_prefix, generate_token, hash_token},
+    models::{
+        ApiToken, Issue, NewApiToken, NewIssue, NewUser, Project, ProjectMember, ProjectRole, User,
+    },
+    routes::router::{create_router, DbPool},
 };
+use tower::ServiceExt;
 
 static COUNTER: AtomicUsize = AtomicUsize::new(0);
 
+/// Bearer token of the `test_admin` user, sent by the request helpers.
+pub const TEST_TOKEN: &str = "tm_test_admin_token";
+
 pub fn create_test_pool() -> DbPool {
     dotenvy::dotenv().ok();
     let database_url = std::env::var("DATABASE_URL").expect("DATABASE_URL must be set");
 
     let manager = ConnectionManager::<PgConnection>::new(database_url);
-    Pool::builder()
+    let pool = Pool::builder()
         .build(manager)
-        .expect("Failed to create DB pool")
+        .expect("Failed to create DB pool");
+
+    let mut conn = pool.get().expect("Failed to get DB connection");
+    seed_test_admin(&mut conn);
+    pool
+}
+
+/// Makes sure `TEST_TOKEN` authenticates as an administrator.
+fn seed_test_admin(conn: &mut PgConnection) {
+    let token_hash = hash_token(TEST_TOKEN);
+    if ApiToken::authenticate(conn, &token_hash)
+        .expect("Failed to check test token")
+        .is_some()
+    {
+        return;
+    }
+
+    let user = User::create(
+        conn,
+        &NewUser {
+            username: "test_admin",
+            display_name: "Test Admin",
+            email: None,
+        },
+    )
+    .expect("Failed to create test admin");
+    sql_query("UPDATE users SET is_admin = true WHERE id = $1")
+        .bind::<diesel::sql_types::Integer, _>(user.id)
+        .execute(conn)
+        .expect("Failed to grant admin");
+
+    ApiToken::create(
+        conn,
+        &NewApiToken {
+            user_id: user.id,
+            name: "tests",
+            token_prefix: display_prefix(TEST_TOKEN),
+            token_hash: &token_hash,
+            expires_at: None,
+        },
+    )
+    .expect("Failed to create test token");
 }
 
 pub async fn setup_test_project(pool: &DbPool) -> i32 {
@@ -30,35 +83,137 @@ pub async fn setup_test_project(pool: &DbPool) -> i32 {
     let unique_id = COUNTER.fetch_add(1, Ordering::SeqCst);
     let project_name = format!("Test Project {}", unique_id);
 
-    let project = Project::create(&mut conn, &project_name, "Test Description")
+    let project = Project::create(&mut conn, &project_name, "Test Description", None)
         .expect("Failed to create test project");
 
     project.id
 }
 
+pub async fn create_named_project(pool: &DbPool, name: &str) -> i32 {
+    let mut conn = pool.get().expect("Failed to get DB connection");
+
+    Project::create(&mut conn, name, "Test Description", None)
+        .expect("Failed to create test project")
+        .id
+}
+
+pub async fn setup_test_user(pool: &DbPool) -> i32 {
+    let mut conn = pool.get().expect("Failed to get DB connection");
+    let unique_id = COUNTER.fetch_add(1, Ordering::SeqCst);
+    let username = format!("test_user_{}", unique_id);
+
+    User::create(
+        &mut conn,
+        &NewUser {
+            username: &username,
+            display_name: "Test User",
+            email: None,
+        },
+    )
+    .expect("Failed to create test user")
+    .id
+}
+
+/// Issues a fresh token for the user and returns it.
+pub async fn create_user_token(pool: &DbPool, user_id: i32) -> String {
+    let mut conn = pool.get().expect("Failed to get DB connection");
+    let token = generate_token();
+
+    ApiToken::create(
+        &mut conn,
+        &NewApiToken {
+            user_id,
+            name: "tests",
+            token_prefix: display_prefix(&token),
+            token_hash: &hash_token(&token),
+            expires_at: None,
+        },
+    )
+    .expect("Failed to create token");
+
+    token
+}
+
+pub async fn add_project_member(pool: &DbPool, project_id: i32, user_id: i32, role: ProjectRole) {
+    let mut conn = pool.get().expect("Failed to get DB connection");
+
+    ProjectMember::upsert(&mut conn, project_id, user_id, role)
+        .expect("Failed to add project member");
+}
+
 pub async fn setup_test_issue(pool: &DbPool, project_id: i32) -> i32 {
+    let created_by_id = setup_test_user(pool).await;
     let mut conn = pool.get().expect("Failed to get DB connection");
     let unique_id = COUNTER.fetch_add(1, Ordering::SeqCst);
 
+    let title = format!("Test Issue {}", unique_id);
     let issue = Issue::create(
         &mut conn,
-        project_id,
-        &format!("Test Issue {}", unique_id),
-        "Test Description",
-        "test_user",
-        None,
-        "open",
+        &NewIssue {
+            project_id,
+            title: &title,
+            description: "Test Description",
+            created_by_id,
+            assigned_to_id: None,
+            status: "open",
+            is_open: true,
+            priority: 2,
+            severity: "major",
+            start_date: None,
+            due_date: None,
+            parent_id: None,
+            original_estimate_minutes: None,
+            milestone_id: None,
+            sprint_id: None,
+            estimate_points: None,
+        },
     )
     .expect("Failed to create test issue");
 
     issue.id
 }
 
+pub async fn create_named_issue(
+    pool: &DbPool,
+    project_id: i32,
+    title: &str,
+    status: &str,
+    assigned_to_id: Option<i32>,
+) -> i32 {
+    let created_by_id = setup_test_user(pool).await;
+    let mut conn = pool.get().expect("Failed to get DB connection");
+
+    Issue::create(
+        &mut conn,
+        &NewIssue {
+            project_id,
+            title,
+            description: "Test Description",
+            created_by_id,
+            assigned_to_id,
+            status,
+            is_open: !matches!(status, "resolved" | "closed"),
+            priority: 2,
+            severity: "major",
+            start_date: None,
+            due_date: None,
+            parent_id: None,
+            original_estimate_minutes: None,
+            milestone_id: None,
+            sprint_id: None,
+            estimate_points: None,
+        },
+    )
+    .expect("Failed to create test issue")
+    .id
+}
+
 pub fn create_json_request(method: &str, uri: &str, body: serde_json::Value) -> Request<Body> {
     Request::builder()
         .method(method)
         .uri(uri)
         .header("Content-Type", "application/json")
+        .header("Authorization", format!("Bearer {}", TEST_TOKEN))
         .body(Body::from(body.to_string()))
         .unwrap()
 }
@@ -67,14 +222,138 @@ pub fn create_empty_request(method: &str, uri: &str) -> Request<Body> {
     Request::builder()
         .method(method)
         .uri(uri)
+        .header("Authorization", format!("Bearer {}", TEST_TOKEN))
         .body(Body::empty())
         .unwrap()
 }
 
+/// A request authenticated with `token` instead of `TEST_TOKEN`.
+pub fn create_token_request(
+    method: &str,
+    uri: &str,
+    token: &str,
+    body: Option<serde_json::Value>,
+) -> Request<Body> {
+    let builder = Request::builder()
+        .method(method)
+        .uri(uri)
+        .header("Authorization", format!("Bearer {}", token));
+
+    match body {
+        Some(body) => builder
+            .header("Content-Type", "application/json")
+            .body(Body::from(body.to_string())),
+        None => builder.body(Body::empty()),
+    }
+    .unwrap()
+}
+
+pub async fn read_json(response: Response) -> serde_json::Value {
+    let bytes = to_bytes(response.into_body(), usize::MAX)
+        .await
+        .expect("Failed to read response body");
+    serde_json::from_slice(&bytes).expect("Response body is not valid JSON")
+}
+
+/// Sends a JSON request as the test admin and returns the status and body.
+pub async fn send(
+    pool: &DbPool,
+    method: &str,
+    uri: &str,
+    body: serde_json::Value,
+) -> (StatusCode, serde_json::Value) {
+    send_to(&create_router(pool.clone()), method, uri, body).await
+}
+
+/// Like `send`, through an existing router, for tests that need its state
+/// to outlive one request.
+pub async fn send_to(
+    app: &Router,
+    method: &str,
+    uri: &str,
+    body: serde_json::Value,
+) -> (StatusCode, serde_json::Value) {
+    let response = app
+        .clone()
+        .oneshot(create_json_request(method, uri, body))
+        .await
+        .unwrap();
+    let status = response.status();
+    (status, read_json(response).await)
+}
+
+/// Sends a request authenticated with `token` instead of `TEST_TOKEN`.
+pub async fn send_as(
+    pool: &DbPool,
+    token: &str,
+    method: &str,
+    uri: &str,
+    body: Option<serde_json::Value>,
+) -> (StatusCode, serde_json::Value) {
+    let app = create_router(pool.clone());
+    let response = app
+        .oneshot(create_token_request(method, uri, token, body))
+        .await
+        .unwrap();
+    let status = response.status();
+    (status, read_json(response).await)
+}
+
+/// Sends a request without a body as the test admin.
+pub async fn send_empty(pool: &DbPool, method: &str, uri: &str) -> (StatusCode, serde_json::Value) {
+    let app = create_router(pool.clone());
+    let response = app
+        .oneshot(create_empty_request(method, uri))
+        .await
+        .unwrap();
+    let status = response.status();
+    (status, read_json(response).await)
+}
+
+/// GETs `uri` as the test admin, expecting `200`, and returns the body.
+pub async fn get(pool: &DbPool, uri: &str) -> serde_json::Value {
+    let app = create_router(pool.clone());
+    let response = app.oneshot(create_empty_request("GET", uri)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    read_json(response).await
+}
+
+/// Titles of a JSON array of issues, in order.
+pub fn titles(issues: &serde_json::Value) -> Vec<String> {
+    issues
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|issue| issue["title"].as_str().unwrap().to_string())
+        .collect()
+}
+
+/// Titles of the project's issues matching the listing `query`, sorted by
+/// title.
+pub async fn issue_titles(pool: &DbPool, project_id: i32, query: &str) -> Vec<String> {
+    let uri = format!("/api/projects/{}/issues?sort=title&{}", project_id, query);
+    titles(&get(pool, &uri).await["items"])
+}
+
 pub async fn cleanup_test_data(pool: &DbPool) {
     let mut conn = pool.get().expect("Failed to get DB connection");
 
-    sql_query("TRUNCATE TABLE projects RESTART IDENTITY CASCADE")
+    sql_query("TRUNCATE TABLE projects, users RESTART IDENTITY CASCADE")
         .execute(&mut conn)
         .expect("Failed to truncate test data");
+    seed_test_admin(&mut conn);
+}
+
+/// Creates an issue through the API from a full request body, for fields
+/// the direct factories above do not cover, and returns its id.
+pub async fn create_issue(pool: &DbPool, project_id: i32, body: serde_json::Value) -> i64 {
+    let (status, body) = send(
+        pool,
+        "POST",
+        &format!("/api/projects/{}/issues", project_id),
+        body,
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    body["id"].as_i64().unwrap()
 }
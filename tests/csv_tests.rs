@@ -0,0 +1,273 @@
+mod common;
+
+use axum::body::to_bytes;
+use axum::http::{header, StatusCode};
+use common::{
+    create_empty_request, create_named_issue, create_test_pool, issue_titles, send,
+    setup_test_project, setup_test_user,
+};
+use serde_json::{json, Value};
+use ticket_manager::handlers::issue_csv::EXPORT_PAGE_SIZE;
+use ticket_manager::models::User;
+use ticket_manager::routes::router::{create_router, DbPool};
+use tower::ServiceExt;
+
+async fn export(pool: &DbPool, uri: &str) -> (StatusCode, String) {
+    let response = create_router(pool.clone())
+        .oneshot(create_empty_request("GET", uri))
+        .await
+        .unwrap();
+    let status = response.status();
+    if status == StatusCode::OK {
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            "text/csv; charset=utf-8"
+        );
+    }
+    let body = to_bytes(response.into_body(), usize::MAX).await.unwrap();
+    (status, String::from_utf8(body.to_vec()).unwrap())
+}
+
+async fn import(pool: &DbPool, project_id: i32, body: Value) -> (StatusCode, Value) {
+    send(
+        pool,
+        "POST",
+        &format!("/api/projects/{}/issues/import", project_id),
+        body,
+    )
+    .await
+}
+
+async fn username(pool: &DbPool, user_id: i32) -> String {
+    let mut conn = pool.get().unwrap();
+    User::find_by_id(&mut conn, user_id)
+        .unwrap()
+        .expect("User not found")
+        .username
+}
+
+#[tokio::test]
+async fn test_export_selected_columns_with_filters() {
+    let pool = create_test_pool();
+    let project_id = setup_test_project(&pool).await;
+    let assignee_id = setup_test_user(&pool).await;
+    let assignee = username(&pool, assignee_id).await;
+    create_named_issue(
+        &pool,
+        project_id,
+        "Crash, \"badly\"",
+        "open",
+        Some(assignee_id),
+    )
+    .await;
+    create_named_issue(&pool, project_id, "Typo", "open", None).await;
+    create_named_issue(&pool, project_id, "Done already", "closed", None).await;
+    let uri = format!("/api/projects/{}/issues.csv", project_id);
+
+    let (status, csv) = export(
+        &pool,
+        &format!(
+            "{}?columns=title,assigned_to,status&status=open&sort=title",
+            uri
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        csv,
+        format!(
+            "title,assigned_to,status\n\"Crash, \"\"badly\"\"\",{},open\nTypo,,open\n",
+            assignee
+        )
+    );
+
+    // Without ?columns= the default set is exported
+    let (status, csv) = export(&pool, &uri).await;
+    assert_eq!(status, StatusCode::OK);
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(
+        lines[0],
+        "key,title,status,priority,severity,assigned_to,created_by,labels,due_date,created_at"
+    );
+    assert_eq!(lines.len(), 4);
+
+    let (status, _) = export(&pool, &format!("{}?columns=title,secret", uri)).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = export(&pool, &format!("{}?status=nonsense", uri)).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = export(&pool, "/api/projects/999999/issues.csv").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_import_with_mapping_and_dry_run() {
+    let pool = create_test_pool();
+    let project_id = setup_test_project(&pool).await;
+    let owner_id = setup_test_user(&pool).await;
+    let owner = username(&pool, owner_id).await;
+    let csv = format!(
+        "Summary,Owner,State,Due,Notes\n\
+         Write docs,{owner},in_progress,2030-01-31,ignored\n\
+         \"Fix login, again\",,,,\n"
+    );
+    let request = json!({
+        "csv": csv,
+        "mapping": { "Summary": "title", "Owner": "assigned_to", "State": "status", "Due": "due_date" },
+        "dry_run": true,
+    });
+
+    let (status, body) = import(&pool, project_id, request.clone()).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["dry_run"], true);
+    assert_eq!(body["rows"], 2);
+    assert_eq!(body["issues"], json!([]));
+    assert_eq!(body["ignored_columns"], json!(["Notes"]));
+    assert!(issue_titles(&pool, project_id, "").await.is_empty());
+
+    let mut request = request;
+    request["dry_run"] = json!(false);
+    let (status, body) = import(&pool, project_id, request).await;
+    assert_eq!(status, StatusCode::CREATED);
+    let issues = body["issues"].as_array().unwrap();
+    assert_eq!(issues.len(), 2);
+    assert_eq!(issues[0]["title"], "Write docs");
+    assert_eq!(issues[0]["assigned_to"]["id"], owner_id);
+    assert_eq!(issues[0]["status"], "in_progress");
+    assert_eq!(issues[0]["due_date"], "2030-01-31");
+    assert_eq!(issues[1]["title"], "Fix login, again");
+    assert_eq!(issues[1]["status"], "open");
+    assert_eq!(issues[1]["assigned_to"], Value::Null);
+    assert_eq!(
+        issue_titles(&pool, project_id, "").await,
+        vec!["Fix login, again", "Write docs"]
+    );
+
+    // An export imports again unchanged
+    let (_, exported) = export(
+        &pool,
+        &format!(
+            "/api/projects/{}/issues.csv?columns=title,status,assigned_to&sort=title",
+            project_id
+        ),
+    )
+    .await;
+    let (status, body) = import(&pool, project_id, json!({ "csv": exported })).await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(body["issues"][1]["assigned_to"]["id"], owner_id);
+    assert_eq!(issue_titles(&pool, project_id, "").await.len(), 4);
+}
+
+#[tokio::test]
+async fn test_import_reports_row_errors_and_creates_nothing() {
+    let pool = create_test_pool();
+    let project_id = setup_test_project(&pool).await;
+    let long_title = "x".repeat(201);
+    let long_username = "u".repeat(101);
+    let csv = format!(
+        "title,status,created_by,due_date,estimate_points\n\
+         Fine,open,,,3\n\
+         {long_title},open,,,\n\
+         Wrong status,someday,,,\n\
+         Nobody,,ghost,,\n\
+         Too long a name,,{long_username},,\n\
+         Bad numbers,,,31/01/2030,three\n\
+         Short,row\n"
+    );
+
+    let (status, body) = import(&pool, project_id, json!({ "csv": csv })).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(body["code"], "invalid_rows");
+    assert_eq!(body["details"]["rows"], 7);
+    let errors: Vec<(u64, Option<&str>)> = body["details"]["errors"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|error| (error["line"].as_u64().unwrap(), error["column"].as_str()))
+        .collect();
+    assert_eq!(
+        errors,
+        vec![
+            (3, Some("title")),
+            (4, Some("status")),
+            (5, Some("created_by")),
+            (6, Some("created_by")),
+            (7, Some("due_date")),
+            (7, Some("estimate_points")),
+            (8, None),
+        ]
+    );
+    assert!(body["error"].as_str().unwrap().starts_with("6 of 7 rows"));
+    assert!(issue_titles(&pool, project_id, "").await.is_empty());
+
+    // Problems with the file as a whole are not row errors
+    let (status, _) = import(&pool, project_id, json!({ "csv": "summary\nNo title\n" })).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = import(
+        &pool,
+        project_id,
+        json!({ "csv": "title\nA\n", "mapping": { "title": "key" } }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = import(
+        &pool,
+        project_id,
+        json!({ "csv": "title\nA\n", "mapping": { "Missing": "title" } }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_export_streams_every_page() {
+    let pool = create_test_pool();
+    let project_id = setup_test_project(&pool).await;
+    let count = EXPORT_PAGE_SIZE as usize + 1;
+    let mut csv = "title\n".to_string();
+    for i in 0..count {
+        csv.push_str(&format!("Issue {:04}\n", i));
+    }
+    let (status, _) = import(&pool, project_id, json!({ "csv": csv })).await;
+    assert_eq!(status, StatusCode::CREATED);
+
+    let (status, exported) = export(
+        &pool,
+        &format!(
+            "/api/projects/{}/issues.csv?columns=title&sort=title",
+            project_id
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(exported, csv);
+}
+
+#[tokio::test]
+async fn test_export_escapes_formulas() {
+    let pool = create_test_pool();
+    let project_id = setup_test_project(&pool).await;
+    let formula = "=HYPERLINK(\"http://evil.example\",\"Open\")";
+    create_named_issue(&pool, project_id, formula, "open", None).await;
+    create_named_issue(&pool, project_id, "@SUM(A1:A9)", "open", None).await;
+    create_named_issue(&pool, project_id, "Plain", "open", None).await;
+
+    let (status, csv) = export(
+        &pool,
+        &format!(
+            "/api/projects/{}/issues.csv?columns=title&sort=title",
+            project_id
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        csv,
+        "title\n\"'=HYPERLINK(\"\"http://evil.example\"\",\"\"Open\"\")\"\n'@SUM(A1:A9)\nPlain\n"
+    );
+
+    // Importing the export restores the original titles
+    let (status, body) = import(&pool, project_id, json!({ "csv": csv })).await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(body["issues"][0]["title"], formula);
+    assert_eq!(body["issues"][1]["title"], "@SUM(A1:A9)");
+}
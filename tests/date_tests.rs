@@ -0,0 +1,120 @@
+mod common;
+
+use axum::http::StatusCode;
+use chrono::{Days, Utc};
+use common::{
+    add_project_member, create_named_issue, create_named_project, create_test_pool,
+    create_user_token, send, send_as, setup_test_project, setup_test_user, titles,
+};
+use serde_json::json;
+use ticket_manager::models::ProjectRole;
+
+#[tokio::test]
+async fn test_start_date_cannot_be_after_due_date() {
+    let pool = create_test_pool();
+    let project_id = setup_test_project(&pool).await;
+
+    let (status, _) = send(
+        &pool,
+        "POST",
+        &format!("/api/projects/{}/issues", project_id),
+        json!({
+            "title": "Backwards",
+            "description": "Ends before it starts",
+            "start_date": "2025-05-10",
+            "due_date": "2025-05-01",
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let issue_id = create_named_issue(&pool, project_id, "Scheduled", "open", None).await;
+    let issue_uri = format!("/api/projects/{}/issues/{}", project_id, issue_id);
+    let (status, body) = send(
+        &pool,
+        "PUT",
+        &issue_uri,
+        json!({ "start_date": "2025-05-01", "due_date": "2025-05-10" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["start_date"], "2025-05-01");
+    assert_eq!(body["due_date"], "2025-05-10");
+
+    // Checked against the stored start date
+    let (status, _) = send(
+        &pool,
+        "PUT",
+        &issue_uri,
+        json!({ "due_date": "2025-04-30" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, body) = send(&pool, "PUT", &issue_uri, json!({ "due_date": null })).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["start_date"], "2025-05-01");
+    assert!(body["due_date"].is_null());
+}
+
+#[tokio::test]
+async fn test_overdue_issues_across_member_projects() {
+    let pool = create_test_pool();
+    let today = Utc::now().date_naive();
+    let yesterday = today.checked_sub_days(Days::new(1)).unwrap();
+    let last_week = today.checked_sub_days(Days::new(7)).unwrap();
+    let tomorrow = today.checked_add_days(Days::new(1)).unwrap();
+
+    let first = setup_test_project(&pool).await;
+    let second = create_named_project(&pool, "Second").await;
+    let hidden = create_named_project(&pool, "Hidden").await;
+    let user_id = setup_test_user(&pool).await;
+    add_project_member(&pool, first, user_id, ProjectRole::Viewer).await;
+    add_project_member(&pool, second, user_id, ProjectRole::Viewer).await;
+    let token = create_user_token(&pool, user_id).await;
+
+    for (project_id, title, status, due) in [
+        (first, "Late", "open", yesterday),
+        (second, "Very late", "open", last_week),
+        (first, "Upcoming", "open", tomorrow),
+        (first, "Done late", "closed", last_week),
+        (hidden, "Not mine", "open", last_week),
+    ] {
+        let issue_id = create_named_issue(&pool, project_id, title, status, None).await;
+        let (status, _) = send(
+            &pool,
+            "PUT",
+            &format!("/api/projects/{}/issues/{}", project_id, issue_id),
+            json!({ "due_date": due }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::OK);
+    }
+
+    let (status, body) = send_as(&pool, &token, "GET", "/api/issues/overdue", None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(titles(&body), ["Very late", "Late"]);
+
+    let (_, body) = send_as(
+        &pool,
+        &token,
+        "GET",
+        &format!("/api/issues/overdue?due_after={}", yesterday),
+        None,
+    )
+    .await;
+    assert_eq!(titles(&body), ["Late"]);
+
+    let (_, body) = send_as(
+        &pool,
+        &token,
+        "GET",
+        &format!(
+            "/api/projects/{}/issues?due_before={}&sort=title",
+            first, today
+        ),
+        None,
+    )
+    .await;
+    assert_eq!(titles(&body["items"]), ["Done late", "Late"]);
+}
@@ -0,0 +1,270 @@
+mod common;
+
+use axum::body::{Body, BodyDataStream};
+use axum::http::{header, Request, StatusCode};
+use axum::Router;
+use common::{
+    create_empty_request, create_named_project, create_test_pool, create_token_request,
+    create_user_token, send_to, setup_test_project, setup_test_user, TEST_TOKEN,
+};
+use serde_json::{json, Value};
+use std::time::Duration;
+use ticket_manager::routes::router::create_router;
+use tokio_stream::StreamExt;
+use tower::ServiceExt;
+
+/// One parsed server-sent event.
+#[derive(Debug)]
+struct Received {
+    id: Option<String>,
+    event: String,
+    data: Value,
+}
+
+struct EventReader {
+    body: BodyDataStream,
+    buffer: String,
+}
+
+impl EventReader {
+    /// Waits for the next event, skipping keep-alive comments.
+    async fn next(&mut self) -> Received {
+        loop {
+            if let Some(end) = self.buffer.find("\n\n") {
+                let frame: String = self.buffer.drain(..end + 2).collect();
+                let mut id = None;
+                let mut event = None;
+                let mut data = String::new();
+                for line in frame.lines() {
+                    if let Some(value) = line.strip_prefix("id:") {
+                        id = Some(value.trim().to_string());
+                    } else if let Some(value) = line.strip_prefix("event:") {
+                        event = Some(value.trim().to_string());
+                    } else if let Some(value) = line.strip_prefix("data:") {
+                        data.push_str(value.trim());
+                    }
+                }
+                if let Some(event) = event {
+                    return Received {
+                        id,
+                        event,
+                        data: serde_json::from_str(&data).expect("Event data is not JSON"),
+                    };
+                }
+                continue;
+            }
+
+            let chunk = tokio::time::timeout(Duration::from_secs(5), self.body.next())
+                .await
+                .expect("Timed out waiting for an event")
+                .expect("Event stream ended")
+                .expect("Failed to read event stream");
+            self.buffer.push_str(std::str::from_utf8(&chunk).unwrap());
+        }
+    }
+}
+
+async fn subscribe(app: &Router, project_id: i32, last_event_id: Option<&str>) -> EventReader {
+    let mut request = Request::builder()
+        .uri(format!("/api/projects/{}/events", project_id))
+        .header(header::AUTHORIZATION, format!("Bearer {}", TEST_TOKEN));
+    if let Some(id) = last_event_id {
+        request = request.header("Last-Event-ID", id);
+    }
+    let response = app
+        .clone()
+        .oneshot(request.body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        response.headers()[header::CONTENT_TYPE],
+        "text/event-stream"
+    );
+
+    EventReader {
+        body: response.into_body().into_data_stream(),
+        buffer: String::new(),
+    }
+}
+
+#[tokio::test]
+async fn test_issue_and_comment_changes_are_streamed() {
+    let pool = create_test_pool();
+    let project_id = setup_test_project(&pool).await;
+    let app = create_router(pool.clone());
+    let issues_uri = format!("/api/projects/{}/issues", project_id);
+
+    let mut events = subscribe(&app, project_id, None).await;
+
+    let (status, issue) = send_to(
+        &app,
+        "POST",
+        &issues_uri,
+        json!({ "title": "Live", "description": "Watch me" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    let created = events.next().await;
+    assert_eq!(created.event, "issue.created");
+    assert!(created.id.is_some());
+    assert_eq!(created.data["event"], "issue.created");
+    assert_eq!(created.data["project_id"], project_id);
+    assert_eq!(created.data["data"]["id"], issue["id"]);
+    assert_eq!(created.data["data"]["title"], "Live");
+
+    let issue_uri = format!("{}/{}", issues_uri, issue["id"]);
+    send_to(&app, "PUT", &issue_uri, json!({ "status": "in_progress" })).await;
+    let updated = events.next().await;
+    assert_eq!(updated.event, "issue.updated");
+    assert_eq!(updated.data["data"]["status"], "in_progress");
+
+    let comments_uri = format!("{}/comments", issue_uri);
+    let (status, comment) = send_to(&app, "POST", &comments_uri, json!({ "body": "On it" })).await;
+    assert_eq!(status, StatusCode::CREATED);
+    let commented = events.next().await;
+    assert_eq!(commented.event, "comment.created");
+    assert_eq!(commented.data["data"]["id"], comment["id"]);
+    assert_eq!(commented.data["data"]["body"], "On it");
+
+    let comment_uri = format!("{}/{}", comments_uri, comment["id"]);
+    send_to(&app, "PUT", &comment_uri, json!({ "body": "Done" })).await;
+    assert_eq!(events.next().await.event, "comment.updated");
+    send_to(&app, "DELETE", &comment_uri, json!({})).await;
+    let removed = events.next().await;
+    assert_eq!(removed.event, "comment.deleted");
+    assert_eq!(removed.data["data"]["is_deleted"], true);
+
+    // Rejected changes are not published
+    let (status, _) = send_to(&app, "PUT", &issue_uri, json!({ "status": "shipped" })).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    app.clone()
+        .oneshot(create_empty_request("DELETE", &issue_uri))
+        .await
+        .unwrap();
+    let deleted = events.next().await;
+    assert_eq!(deleted.event, "issue.deleted");
+    assert_eq!(deleted.data["data"]["id"], issue["id"]);
+
+    let other_id = create_named_project(&pool, "Elsewhere").await;
+    send_to(
+        &app,
+        "POST",
+        &format!("/api/projects/{}/issues", other_id),
+        json!({ "title": "Not yours", "description": "Other project" }),
+    )
+    .await;
+    send_to(
+        &app,
+        "POST",
+        &issues_uri,
+        json!({ "title": "Next", "description": "Back here" }),
+    )
+    .await;
+    let next = events.next().await;
+    assert_eq!(next.event, "issue.created");
+    assert_eq!(next.data["data"]["title"], "Next");
+}
+
+#[tokio::test]
+async fn test_resume_from_last_event_id() {
+    let pool = create_test_pool();
+    let project_id = setup_test_project(&pool).await;
+    let other_id = create_named_project(&pool, "Elsewhere").await;
+    let app = create_router(pool.clone());
+    let issues_uri = format!("/api/projects/{}/issues", project_id);
+
+    let mut events = subscribe(&app, project_id, None).await;
+    send_to(
+        &app,
+        "POST",
+        &issues_uri,
+        json!({ "title": "First", "description": "Seen" }),
+    )
+    .await;
+    let first = events.next().await;
+    let first_id = first.id.clone().unwrap();
+    drop(events);
+
+    // Missed while disconnected
+    for (project, title) in [
+        (project_id, "Second"),
+        (other_id, "Foreign"),
+        (project_id, "Third"),
+    ] {
+        send_to(
+            &app,
+            "POST",
+            &format!("/api/projects/{}/issues", project),
+            json!({ "title": title, "description": "Missed" }),
+        )
+        .await;
+    }
+
+    let mut events = subscribe(&app, project_id, Some(&first_id)).await;
+    let second = events.next().await;
+    assert_eq!(second.data["data"]["title"], "Second");
+    let third = events.next().await;
+    assert_eq!(third.data["data"]["title"], "Third");
+    let third_id: u64 = third.id.unwrap().parse().unwrap();
+    assert!(third_id > first_id.parse().unwrap());
+
+    // Live events follow the replay
+    send_to(
+        &app,
+        "POST",
+        &issues_uri,
+        json!({ "title": "Fourth", "description": "Live" }),
+    )
+    .await;
+    assert_eq!(events.next().await.data["data"]["title"], "Fourth");
+
+    // IDs this process never issued cannot be resumed from
+    for last_event_id in ["999999", "not-a-number"] {
+        let mut events = subscribe(&app, project_id, Some(last_event_id)).await;
+        let reset = events.next().await;
+        assert_eq!(reset.event, "reset");
+        let reset_id: u64 = reset.id.unwrap().parse().unwrap();
+        assert!(reset_id > third_id);
+
+        send_to(
+            &app,
+            "POST",
+            &issues_uri,
+            json!({ "title": "After reset", "description": "Live" }),
+        )
+        .await;
+        assert_eq!(events.next().await.data["data"]["title"], "After reset");
+    }
+}
+
+#[tokio::test]
+async fn test_event_stream_access() {
+    let pool = create_test_pool();
+    let project_id = setup_test_project(&pool).await;
+    let app = create_router(pool.clone());
+    let user_id = setup_test_user(&pool).await;
+    let token = create_user_token(&pool, user_id).await;
+
+    let response = app
+        .clone()
+        .oneshot(create_token_request(
+            "GET",
+            &format!("/api/projects/{}/events", project_id),
+            &token,
+            None,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+    let response = app
+        .clone()
+        .oneshot(create_empty_request(
+            "GET",
+            &format!("/api/projects/{}/events", project_id + 1000),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,154 @@
+mod common;
+
+use axum::http::StatusCode;
+use common::{
+    create_empty_request, create_named_issue, create_named_project, create_test_pool, read_json,
+    send, setup_test_project,
+};
+use serde_json::{json, Value};
+use ticket_manager::routes::router::create_router;
+use ticket_manager::routes::router::DbPool;
+use tower::ServiceExt;
+
+async fn create_child(
+    pool: &DbPool,
+    project_id: i32,
+    title: &str,
+    parent_id: i64,
+    estimate: i32,
+) -> (StatusCode, Value) {
+    send(
+        pool,
+        "POST",
+        &format!("/api/projects/{}/issues", project_id),
+        json!({
+            "title": title,
+            "description": "Sub-issue",
+            "parent_id": parent_id,
+            "original_estimate_minutes": estimate,
+        }),
+    )
+    .await
+}
+
+#[tokio::test]
+async fn test_subtree_rollups_and_closing_parents() {
+    let pool = create_test_pool();
+    let project_id = setup_test_project(&pool).await;
+    let epic = i64::from(create_named_issue(&pool, project_id, "Epic", "open", None).await);
+
+    let (status, story) = create_child(&pool, project_id, "Story", epic, 120).await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(story["parent_id"], epic);
+    let story = story["id"].as_i64().unwrap();
+    let (_, task) = create_child(&pool, project_id, "Task", story, 60).await;
+    let task = task["id"].as_i64().unwrap();
+    let (_, done) = create_child(&pool, project_id, "Done task", story, 30).await;
+    let done = done["id"].as_i64().unwrap();
+
+    let issue_uri = |id: i64| format!("/api/projects/{}/issues/{}", project_id, id);
+    let (status, _) = send(
+        &pool,
+        "PUT",
+        &issue_uri(done),
+        json!({ "status": "resolved" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let app = create_router(pool.clone());
+    let response = app
+        .oneshot(create_empty_request(
+            "GET",
+            &format!("{}/subtree", issue_uri(epic)),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let tree = read_json(response).await;
+    assert_eq!(
+        tree["rollup"],
+        json!({
+            "total": 3,
+            "done": 1,
+            "estimate_minutes": 210,
+            "remaining_estimate_minutes": 180,
+            "logged_minutes": 0,
+        })
+    );
+    assert_eq!(tree["children"][0]["id"], story);
+    assert_eq!(tree["children"][0]["rollup"]["total"], 2);
+    assert_eq!(tree["children"][0]["children"][0]["id"], task);
+
+    // Only direct children count, and the story still has an open task
+    let (status, body) = send(
+        &pool,
+        "PUT",
+        &issue_uri(story),
+        json!({ "status": "closed" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(body["code"], "open_children");
+    assert_eq!(body["details"]["open_children"], json!([task]));
+
+    let (status, body) = send(
+        &pool,
+        "PUT",
+        &format!("{}?force=true", issue_uri(story)),
+        json!({ "status": "closed" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["is_open"], false);
+}
+
+#[tokio::test]
+async fn test_parent_cycles_and_depth_limit() {
+    let pool = create_test_pool();
+    let project_id = setup_test_project(&pool).await;
+    let other_project = create_named_project(&pool, "Elsewhere").await;
+    let top = i64::from(create_named_issue(&pool, project_id, "Top", "open", None).await);
+    let foreign = create_named_issue(&pool, other_project, "Foreign", "open", None).await;
+
+    let (_, middle) = create_child(&pool, project_id, "Middle", top, 10).await;
+    let middle = middle["id"].as_i64().unwrap();
+
+    let (status, body) = send(
+        &pool,
+        "PUT",
+        &format!("/api/projects/{}/issues/{}", project_id, top),
+        json!({ "parent_id": middle }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(body["code"], "hierarchy_cycle");
+
+    let (status, _) = create_child(&pool, project_id, "Stray", i64::from(foreign), 10).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, project) = send(
+        &pool,
+        "PUT",
+        &format!("/api/projects/{}", project_id),
+        json!({ "max_issue_depth": 1 }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(project["max_issue_depth"], 1);
+
+    let (status, body) = create_child(&pool, project_id, "Too deep", middle, 10).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(body["code"], "depth_limit");
+
+    // Detaching makes the issue top-level again
+    let (status, body) = send(
+        &pool,
+        "PUT",
+        &format!("/api/projects/{}/issues/{}", project_id, middle),
+        json!({ "parent_id": null }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(body["parent_id"].is_null());
+}
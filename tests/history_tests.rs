@@ -0,0 +1,88 @@
+mod common;
+
+use axum::http::StatusCode;
+use common::{
+    create_empty_request, create_json_request, create_test_pool, read_json, setup_test_issue,
+    setup_test_project,
+};
+use diesel::{sql_query, RunQueryDsl};
+use serde_json::json;
+use ticket_manager::routes::router::create_router;
+use tower::ServiceExt;
+
+#[tokio::test]
+async fn test_issue_updates_are_recorded() {
+    let pool = create_test_pool();
+    let project_id = setup_test_project(&pool).await;
+    let issue_id = setup_test_issue(&pool, project_id).await;
+    let issue_uri = format!("/api/projects/{}/issues/{}", project_id, issue_id);
+
+    let app = create_router(pool.clone());
+    let response = app
+        .oneshot(create_json_request(
+            "PUT",
+            &issue_uri,
+            json!({ "status": "resolved", "description": "Test Description" }),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let app = create_router(pool.clone());
+    let response = app
+        .oneshot(create_empty_request(
+            "GET",
+            &format!("{}/history", issue_uri),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // The unchanged description is not recorded
+    let history = read_json(response).await;
+    let fields: Vec<&str> = history
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|entry| entry["field"].as_str().unwrap())
+        .collect();
+    assert_eq!(fields, ["status", "is_open"]);
+    assert_eq!(history[0]["old_value"], "open");
+    assert_eq!(history[0]["new_value"], "resolved");
+    assert_eq!(history[0]["actor"]["username"], "test_admin");
+}
+
+#[tokio::test]
+async fn test_project_rename_is_recorded() {
+    let pool = create_test_pool();
+    let project_id = setup_test_project(&pool).await;
+
+    let app = create_router(pool.clone());
+    let response = app
+        .oneshot(create_json_request(
+            "PUT",
+            &format!("/api/projects/{}", project_id),
+            json!({ "name": "Renamed Project" }),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let app = create_router(pool.clone());
+    let response = app
+        .oneshot(create_empty_request(
+            "GET",
+            &format!("/api/projects/{}/history", project_id),
+        ))
+        .await
+        .unwrap();
+    let history = read_json(response).await;
+    assert_eq!(history.as_array().unwrap().len(), 1);
+    assert_eq!(history[0]["field"], "name");
+    assert_eq!(history[0]["new_value"], "Renamed Project");
+
+    let mut conn = pool.get().unwrap();
+    assert!(sql_query("UPDATE audit_log SET new_value = 'tampered'")
+        .execute(&mut conn)
+        .is_err());
+}
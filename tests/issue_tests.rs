@@ -1,12 +1,11 @@
 mod common;
 use axum::body::Body;
-use hyper::{Request};
-
+use hyper::Request;
 
 use axum::http::StatusCode;
 use common::{
-    create_empty_request, create_json_request, create_test_pool, setup_test_issue,
-    setup_test_project,
+    create_empty_request, create_json_request, create_named_issue, create_test_pool, read_json,
+    setup_test_issue, setup_test_project, setup_test_user, TEST_TOKEN,
 };
 use serde_json::json;
 use ticket_manager::routes::router::create_router;
@@ -17,19 +16,22 @@ async fn test_create_issue() {
     let app = create_router(create_test_pool());
 
     let project_id = setup_test_project(&create_test_pool()).await;
+    let creator_id = setup_test_user(&create_test_pool()).await;
+    let assignee_id = setup_test_user(&create_test_pool()).await;
 
     let request_body = json!({
         "title": "Test Issue",
         "description": "This is a test issue",
-        "created_by": "test_user",
-        "assigned_to": "test_assignee",
+        "created_by": creator_id,
+        "assigned_to": assignee_id,
         "status": "open"
     })
     .to_string();
 
     let request = Request::builder()
         .method("POST")
-        .uri(&format!("/api/projects/{}/issues", project_id))
+        .uri(format!("/api/projects/{}/issues", project_id))
+        .header("Authorization", format!("Bearer {}", TEST_TOKEN))
         .header("Content-Type", "application/json")
         .body(Body::from(request_body))
         .unwrap();
@@ -69,7 +71,8 @@ async fn test_update_issue() {
 
     let request = Request::builder()
         .method("PUT")
-        .uri(&format!("/api/projects/{}/issues/{}", project_id, issue_id))
+        .uri(format!("/api/projects/{}/issues/{}", project_id, issue_id))
+        .header("Authorization", format!("Bearer {}", TEST_TOKEN))
         .header("Content-Type", "application/json")
         .body(Body::from(request_body))
         .unwrap();
@@ -78,7 +81,6 @@ async fn test_update_issue() {
     assert_eq!(response.status(), StatusCode::OK);
 }
 
-
 #[tokio::test]
 async fn test_delete_issue() {
     let app = create_router(create_test_pool());
@@ -89,16 +91,16 @@ async fn test_delete_issue() {
 
     let request = Request::builder()
         .method("DELETE")
-        .uri(&format!("/api/projects/{}/issues/{}", project_id, issue_id))
+        .uri(format!("/api/projects/{}/issues/{}", project_id, issue_id))
+        .header("Authorization", format!("Bearer {}", TEST_TOKEN))
         .body(Body::empty())
         .unwrap();
 
     let response = app.oneshot(request).await.unwrap();
-    
+
     assert!(response.status() == StatusCode::NO_CONTENT || response.status() == StatusCode::OK);
 }
 
-
 #[tokio::test]
 async fn test_get_nonexistent_project_issues() {
     let app = create_router(create_test_pool());
@@ -127,3 +129,252 @@ async fn test_create_issue_invalid_data() {
     let response = app.oneshot(request).await.unwrap();
     assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
 }
+
+#[tokio::test]
+async fn test_get_project_issues_filters() {
+    let pool = create_test_pool();
+    let project_id = setup_test_project(&pool).await;
+    let alice = setup_test_user(&pool).await;
+    create_named_issue(&pool, project_id, "Login page crash", "open", Some(alice)).await;
+    create_named_issue(&pool, project_id, "Login timeout", "in_progress", None).await;
+    create_named_issue(&pool, project_id, "Signup typo", "closed", Some(alice)).await;
+
+    let app = create_router(pool.clone());
+    let response = app
+        .oneshot(create_empty_request(
+            "GET",
+            &format!(
+                "/api/projects/{}/issues?status=open,in_progress&q=login&sort=title",
+                project_id
+            ),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = read_json(response).await;
+    assert_eq!(body["total"], 2);
+    assert_eq!(body["items"][0]["title"], "Login page crash");
+    assert_eq!(body["items"][1]["title"], "Login timeout");
+
+    let app = create_router(pool);
+    let response = app
+        .oneshot(create_empty_request(
+            "GET",
+            &format!("/api/projects/{}/issues?assigned_to={}", project_id, alice),
+        ))
+        .await
+        .unwrap();
+    let body = read_json(response).await;
+    assert_eq!(body["total"], 2);
+}
+
+#[tokio::test]
+async fn test_get_project_issues_paginates() {
+    let pool = create_test_pool();
+    let project_id = setup_test_project(&pool).await;
+    for title in ["First", "Second", "Third"] {
+        create_named_issue(&pool, project_id, title, "open", None).await;
+    }
+
+    let uri = format!("/api/projects/{}/issues?limit=2&order=desc", project_id);
+    let app = create_router(pool.clone());
+    let response = app
+        .oneshot(create_empty_request("GET", &uri))
+        .await
+        .unwrap();
+    let body = read_json(response).await;
+    assert_eq!(body["items"][0]["title"], "Third");
+    assert_eq!(body["items"][1]["title"], "Second");
+    let cursor = body["next_cursor"].as_str().unwrap().to_string();
+
+    let app = create_router(pool);
+    let response = app
+        .oneshot(create_empty_request(
+            "GET",
+            &format!("{}&after={}", uri, cursor),
+        ))
+        .await
+        .unwrap();
+    let body = read_json(response).await;
+    assert_eq!(body["items"].as_array().unwrap().len(), 1);
+    assert_eq!(body["items"][0]["title"], "First");
+    assert!(body["next_cursor"].is_null());
+}
+
+#[tokio::test]
+async fn test_get_project_issues_rejects_unknown_status() {
+    let pool = create_test_pool();
+    let project_id = setup_test_project(&pool).await;
+
+    let app = create_router(pool);
+    let response = app
+        .oneshot(create_empty_request(
+            "GET",
+            &format!("/api/projects/{}/issues?status=open,bogus", project_id),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_get_issue() {
+    let pool = create_test_pool();
+    let project_id = setup_test_project(&pool).await;
+    let issue_id = setup_test_issue(&pool, project_id).await;
+
+    let app = create_router(pool.clone());
+    let response = app
+        .oneshot(create_empty_request(
+            "GET",
+            &format!("/api/projects/{}/issues/{}", project_id, issue_id),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = read_json(response).await;
+    assert_eq!(body["id"], issue_id);
+    assert!(body["created_at"].is_string());
+    assert!(body["updated_at"].is_string());
+
+    let app = create_router(pool);
+    let response = app
+        .oneshot(create_empty_request(
+            "GET",
+            &format!("/api/projects/{}/issues/{}", project_id, issue_id + 1000),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_issue_embeds_users() {
+    let pool = create_test_pool();
+    let project_id = setup_test_project(&pool).await;
+    let assignee_id = setup_test_user(&pool).await;
+    let issue_id = create_named_issue(&pool, project_id, "Embed", "open", Some(assignee_id)).await;
+
+    let app = create_router(pool);
+    let response = app
+        .oneshot(create_empty_request(
+            "GET",
+            &format!("/api/projects/{}/issues/{}", project_id, issue_id),
+        ))
+        .await
+        .unwrap();
+    let body = read_json(response).await;
+    assert_eq!(body["assigned_to"]["id"], assignee_id);
+    assert!(body["assigned_to"]["username"].is_string());
+    assert!(body["created_by"]["display_name"].is_string());
+}
+
+#[tokio::test]
+async fn test_update_issue_unassigns() {
+    let pool = create_test_pool();
+    let project_id = setup_test_project(&pool).await;
+    let assignee_id = setup_test_user(&pool).await;
+    let issue_id =
+        create_named_issue(&pool, project_id, "Unassign", "open", Some(assignee_id)).await;
+
+    let app = create_router(pool);
+    let response = app
+        .oneshot(create_json_request(
+            "PUT",
+            &format!("/api/projects/{}/issues/{}", project_id, issue_id),
+            json!({ "assigned_to": null }),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = read_json(response).await;
+    assert_eq!(body["assigned_to"], serde_json::Value::Null);
+}
+
+#[tokio::test]
+async fn test_create_issue_unknown_user() {
+    let pool = create_test_pool();
+    let project_id = setup_test_project(&pool).await;
+
+    let app = create_router(pool);
+    let response = app
+        .oneshot(create_json_request(
+            "POST",
+            &format!("/api/projects/{}/issues", project_id),
+            json!({
+                "title": "Ghost",
+                "description": "Created by nobody",
+                "created_by": 999999
+            }),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_sort_issues_by_priority_then_age() {
+    let pool = create_test_pool();
+    let project_id = setup_test_project(&pool).await;
+
+    for (title, priority) in [
+        ("Old P2", "P2"),
+        ("P1", "p1"),
+        ("New P2", "P2"),
+        ("P0", "P0"),
+    ] {
+        let app = create_router(pool.clone());
+        let response = app
+            .oneshot(create_json_request(
+                "POST",
+                &format!("/api/projects/{}/issues", project_id),
+                json!({ "title": title, "description": "", "priority": priority }),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+        assert_eq!(read_json(response).await["severity"], "major");
+    }
+
+    let mut titles = Vec::new();
+    let mut after: Option<String> = None;
+    loop {
+        let mut uri = format!("/api/projects/{}/issues?sort=priority&limit=3", project_id);
+        if let Some(cursor) = &after {
+            uri.push_str(&format!("&after={}", cursor));
+        }
+        let app = create_router(pool.clone());
+        let response = app
+            .oneshot(create_empty_request("GET", &uri))
+            .await
+            .unwrap();
+        let body = read_json(response).await;
+        for issue in body["items"].as_array().unwrap() {
+            titles.push(issue["title"].as_str().unwrap().to_string());
+        }
+        match body["next_cursor"].as_str() {
+            Some(cursor) => after = Some(cursor.to_string()),
+            None => break,
+        }
+    }
+    assert_eq!(titles, ["P0", "P1", "Old P2", "New P2"]);
+}
+
+#[tokio::test]
+async fn test_create_issue_rejects_unknown_priority() {
+    let pool = create_test_pool();
+    let project_id = setup_test_project(&pool).await;
+
+    let app = create_router(pool);
+    let response = app
+        .oneshot(create_json_request(
+            "POST",
+            &format!("/api/projects/{}/issues", project_id),
+            json!({ "title": "Urgent", "description": "", "priority": "P9", "severity": "critical" }),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
@@ -0,0 +1,133 @@
+mod common;
+
+use axum::http::{header, StatusCode};
+use common::{
+    create_empty_request, create_issue, create_test_pool, get, send, send_empty, setup_test_project,
+};
+use serde_json::json;
+use ticket_manager::routes::router::create_router;
+use tower::ServiceExt;
+
+#[tokio::test]
+async fn test_issues_are_numbered_per_project() {
+    let pool = create_test_pool();
+    setup_test_project(&pool).await;
+
+    let (status, web) = send(
+        &pool,
+        "POST",
+        "/api/projects",
+        json!({ "name": "Web", "key": "web" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(web["key"], "WEB");
+    let (_, mobile) = send(
+        &pool,
+        "POST",
+        "/api/projects",
+        json!({ "name": "Mobile App" }),
+    )
+    .await;
+    assert_eq!(mobile["key"], "MOBI");
+
+    let web_id = web["id"].as_i64().unwrap() as i32;
+    let mobile_id = mobile["id"].as_i64().unwrap() as i32;
+    let issue = |title: &str| json!({ "title": title, "description": "Numbered" });
+    let first = create_issue(&pool, web_id, issue("First")).await;
+    let other = create_issue(&pool, mobile_id, issue("Other")).await;
+    let second = create_issue(&pool, web_id, issue("Second")).await;
+    assert_eq!(get(&pool, "/api/issues/WEB-1").await["id"], first);
+    assert_eq!(get(&pool, "/api/issues/MOBI-1").await["id"], other);
+
+    let body = get(&pool, "/api/issues/web-2").await;
+    assert_eq!(body["id"], second);
+    assert_eq!(body["key"], "WEB-2");
+    assert_eq!(body["number"], 2);
+    assert_eq!(body["title"], "Second");
+
+    let (status, _) = send_empty(&pool, "GET", "/api/issues/WEB-3").await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_renamed_keys_redirect() {
+    let pool = create_test_pool();
+    setup_test_project(&pool).await;
+    let (_, project) = send(
+        &pool,
+        "POST",
+        "/api/projects",
+        json!({ "name": "Payments", "key": "PAY" }),
+    )
+    .await;
+    let project_id = project["id"].as_i64().unwrap() as i32;
+    create_issue(
+        &pool,
+        project_id,
+        json!({ "title": "Refunds", "description": "Numbered" }),
+    )
+    .await;
+
+    let (status, body) = send(
+        &pool,
+        "PUT",
+        &format!("/api/projects/{}", project_id),
+        json!({ "key": "BILL" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["key"], "BILL");
+
+    let app = create_router(pool.clone());
+    let response = app
+        .oneshot(create_empty_request("GET", "/api/issues/PAY-1"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::PERMANENT_REDIRECT);
+    assert_eq!(response.headers()[header::LOCATION], "/api/issues/BILL-1");
+
+    get(&pool, "/api/issues/BILL-1").await;
+
+    // The old key stays reserved for this project
+    let (status, _) = send(
+        &pool,
+        "POST",
+        "/api/projects",
+        json!({ "name": "Payroll", "key": "PAY" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+}
+
+#[tokio::test]
+async fn test_project_key_validation() {
+    let pool = create_test_pool();
+    let project_id = setup_test_project(&pool).await;
+
+    for body in [
+        json!({ "name": "Bad", "key": "1ABC" }),
+        json!({ "name": "Long", "key": "ABCDEFGHIJK" }),
+    ] {
+        let (status, _) = send(&pool, "POST", "/api/projects", body).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    let (_, other) = send(
+        &pool,
+        "POST",
+        "/api/projects",
+        json!({ "name": "Taken", "key": "TAKEN" }),
+    )
+    .await;
+    assert_eq!(other["key"], "TAKEN");
+
+    let (status, _) = send(
+        &pool,
+        "PUT",
+        &format!("/api/projects/{}", project_id),
+        json!({ "key": "taken" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+}
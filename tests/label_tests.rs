@@ -0,0 +1,110 @@
+mod common;
+
+use axum::http::StatusCode;
+use common::{
+    create_named_issue, create_test_pool, get, issue_titles, send, send_empty, setup_test_project,
+};
+use serde_json::{json, Value};
+use ticket_manager::routes::router::DbPool;
+
+async fn create_label(pool: &DbPool, project_id: i32, name: &str) -> Value {
+    let (status, label) = send(
+        pool,
+        "POST",
+        &format!("/api/projects/{}/labels", project_id),
+        json!({ "name": name, "color": "#d73a4a" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    label
+}
+
+async fn label_issue(pool: &DbPool, project_id: i32, issue_id: i32, labels: &[&str]) -> Value {
+    let (status, issue) = send(
+        pool,
+        "PUT",
+        &format!("/api/projects/{}/issues/{}", project_id, issue_id),
+        json!({ "add_labels": labels }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    issue
+}
+
+#[tokio::test]
+async fn test_filter_issues_by_labels() {
+    let pool = create_test_pool();
+    let project_id = setup_test_project(&pool).await;
+    create_label(&pool, project_id, "bug").await;
+    create_label(&pool, project_id, "ui").await;
+
+    let both = create_named_issue(&pool, project_id, "A both", "open", None).await;
+    let bug = create_named_issue(&pool, project_id, "B bug", "open", None).await;
+    create_named_issue(&pool, project_id, "C none", "open", None).await;
+
+    let issue = label_issue(&pool, project_id, both, &["bug", "ui"]).await;
+    assert_eq!(issue["labels"].as_array().unwrap().len(), 2);
+    label_issue(&pool, project_id, bug, &["bug"]).await;
+
+    assert_eq!(
+        issue_titles(&pool, project_id, "label=bug,ui").await,
+        ["A both"]
+    );
+    assert_eq!(
+        issue_titles(&pool, project_id, "label=bug,ui&label_match=any").await,
+        ["A both", "B bug"]
+    );
+
+    let (status, _) = send_empty(
+        &pool,
+        "GET",
+        &format!("/api/projects/{}/issues?label=nope", project_id),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_detach_and_delete_labels() {
+    let pool = create_test_pool();
+    let project_id = setup_test_project(&pool).await;
+    let bug = create_label(&pool, project_id, "bug").await;
+    create_label(&pool, project_id, "ui").await;
+    let issue_id = create_named_issue(&pool, project_id, "Labelled", "open", None).await;
+    label_issue(&pool, project_id, issue_id, &["bug", "ui"]).await;
+    let issue_uri = format!("/api/projects/{}/issues/{}", project_id, issue_id);
+
+    let (status, issue) = send(&pool, "PUT", &issue_uri, json!({ "remove_labels": ["ui"] })).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(issue["labels"][0]["name"], "bug");
+
+    let (status, _) = send_empty(
+        &pool,
+        "DELETE",
+        &format!("/api/projects/{}/labels/{}", project_id, bug["id"]),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    assert_eq!(get(&pool, &issue_uri).await["labels"], json!([]));
+}
+
+#[tokio::test]
+async fn test_label_names_are_unique_per_project() {
+    let pool = create_test_pool();
+    let project_id = setup_test_project(&pool).await;
+    create_label(&pool, project_id, "bug").await;
+    let labels_uri = format!("/api/projects/{}/labels", project_id);
+
+    let (status, _) = send(&pool, "POST", &labels_uri, json!({ "name": "bug" })).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    let (status, _) = send(
+        &pool,
+        "POST",
+        &labels_uri,
+        json!({ "name": "ui", "color": "red" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
@@ -0,0 +1,113 @@
+mod common;
+
+use axum::http::StatusCode;
+use common::{
+    create_named_issue, create_named_project, create_test_pool, get, send, send_empty,
+    setup_test_project,
+};
+use serde_json::{json, Value};
+use ticket_manager::routes::router::DbPool;
+
+async fn link(
+    pool: &DbPool,
+    project_id: i32,
+    issue_id: i32,
+    link_type: &str,
+    target_id: i32,
+) -> (StatusCode, Value) {
+    send(
+        pool,
+        "POST",
+        &format!("/api/projects/{}/issues/{}/links", project_id, issue_id),
+        json!({ "link_type": link_type, "target_id": target_id }),
+    )
+    .await
+}
+
+#[tokio::test]
+async fn test_blocking_cycles_are_rejected() {
+    let pool = create_test_pool();
+    let project_id = setup_test_project(&pool).await;
+    let design = create_named_issue(&pool, project_id, "Design", "open", None).await;
+    let build = create_named_issue(&pool, project_id, "Build", "open", None).await;
+    let ship = create_named_issue(&pool, project_id, "Ship", "open", None).await;
+
+    let (status, body) = link(&pool, project_id, design, "blocks", build).await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(body["link_type"], "blocks");
+    assert_eq!(body["issue"]["title"], "Build");
+    // Given from the blocked side, stored the other way round
+    let (status, body) = link(&pool, project_id, ship, "blocked_by", build).await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(body["link_type"], "blocked_by");
+
+    let (status, body) = link(&pool, project_id, ship, "blocks", design).await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(body["code"], "dependency_cycle");
+    assert_eq!(body["details"]["path"], json!([design, build, ship]));
+
+    let links = get(
+        &pool,
+        &format!("/api/projects/{}/issues/{}/links", project_id, build),
+    )
+    .await;
+    assert_eq!(links[0]["link_type"], "blocked_by");
+    assert_eq!(links[0]["issue"]["id"], design);
+    assert_eq!(links[1]["link_type"], "blocks");
+    assert_eq!(links[1]["issue"]["id"], ship);
+
+    let graph = get(
+        &pool,
+        &format!("/api/projects/{}/issues/{}/dependencies", project_id, ship),
+    )
+    .await;
+    let blockers: Vec<&Value> = graph["blocked_by"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|issue| &issue["id"])
+        .collect();
+    assert_eq!(blockers, vec![&json!(build), &json!(design)]);
+    assert_eq!(graph["blocking"], json!([]));
+    assert_eq!(graph["edges"].as_array().unwrap().len(), 2);
+}
+
+#[tokio::test]
+async fn test_link_validation_and_removal() {
+    let pool = create_test_pool();
+    let project_id = setup_test_project(&pool).await;
+    let other_project = create_named_project(&pool, "Elsewhere").await;
+    let first = create_named_issue(&pool, project_id, "First", "open", None).await;
+    let second = create_named_issue(&pool, project_id, "Second", "open", None).await;
+    let foreign = create_named_issue(&pool, other_project, "Foreign", "open", None).await;
+
+    let (status, body) = link(&pool, project_id, first, "relates_to", second).await;
+    assert_eq!(status, StatusCode::CREATED);
+    let link_id = body["id"].as_i64().unwrap();
+
+    // Already related, whichever side asks
+    let (status, _) = link(&pool, project_id, second, "relates_to", first).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    let (status, _) = link(&pool, project_id, first, "relates_to", first).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = link(&pool, project_id, first, "clones", foreign).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, _) = send_empty(
+        &pool,
+        "DELETE",
+        &format!(
+            "/api/projects/{}/issues/{}/links/{}",
+            project_id, second, link_id
+        ),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let links = get(
+        &pool,
+        &format!("/api/projects/{}/issues/{}/links", project_id, first),
+    )
+    .await;
+    assert_eq!(links, json!([]));
+}
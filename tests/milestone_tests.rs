@@ -0,0 +1,173 @@
+mod common;
+
+use axum::http::StatusCode;
+use chrono::{Duration, Utc};
+use common::{create_issue, create_test_pool, get, send, setup_test_project};
+use serde_json::json;
+use ticket_manager::routes::router::DbPool;
+
+/// Creates a milestone due `days` from today.
+async fn create_milestone(pool: &DbPool, project_id: i32, name: &str, days: Option<i64>) -> i64 {
+    let target_date = days.map(|days| (Utc::now().date_naive() + Duration::days(days)).to_string());
+    let (status, body) = send(
+        pool,
+        "POST",
+        &format!("/api/projects/{}/milestones", project_id),
+        json!({ "name": name, "target_date": target_date }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    body["id"].as_i64().unwrap()
+}
+
+#[tokio::test]
+async fn test_milestone_progress_and_risk() {
+    let pool = create_test_pool();
+    let project_id = setup_test_project(&pool).await;
+    let late = create_milestone(&pool, project_id, "1.0", Some(-1)).await;
+    let later = create_milestone(&pool, project_id, "2.0", Some(30)).await;
+
+    let (status, _) = send(
+        &pool,
+        "POST",
+        &format!("/api/projects/{}/milestones", project_id),
+        json!({ "name": "1.0" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    let open = create_issue(
+        &pool,
+        project_id,
+        json!({ "title": "Still open", "description": "Scheduled", "milestone_id": late }),
+    )
+    .await;
+    let done = create_issue(
+        &pool,
+        project_id,
+        json!({ "title": "Finished", "description": "Scheduled", "milestone_id": late }),
+    )
+    .await;
+    let (status, _) = send(
+        &pool,
+        "PUT",
+        &format!("/api/projects/{}/issues/{}", project_id, done),
+        json!({ "status": "resolved" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+
+    let milestone = get(
+        &pool,
+        &format!("/api/projects/{}/milestones/{}", project_id, late),
+    )
+    .await;
+    assert_eq!(milestone["name"], "1.0");
+    let progress = &milestone["progress"];
+    assert_eq!(progress["total"], 2);
+    assert_eq!(progress["done"], 1);
+    assert_eq!(progress["percent_done"], 50.0);
+    assert_eq!(progress["open_by_status"], json!({ "open": 1 }));
+    assert_eq!(progress["days_remaining"], -1);
+    assert_eq!(progress["at_risk"], true);
+
+    let milestone = get(
+        &pool,
+        &format!("/api/projects/{}/milestones/{}", project_id, later),
+    )
+    .await;
+    assert_eq!(milestone["progress"]["total"], 0);
+    assert_eq!(milestone["progress"]["at_risk"], false);
+
+    let issues = get(
+        &pool,
+        &format!(
+            "/api/projects/{}/issues?milestone_id={}&is_open=true",
+            project_id, late
+        ),
+    )
+    .await;
+    assert_eq!(issues["total"], 1);
+    assert_eq!(issues["items"][0]["id"], open);
+}
+
+#[tokio::test]
+async fn test_release_moves_open_issues_to_next_milestone() {
+    let pool = create_test_pool();
+    let project_id = setup_test_project(&pool).await;
+    let current = create_milestone(&pool, project_id, "1.0", Some(10)).await;
+    let someday = create_milestone(&pool, project_id, "Someday", None).await;
+    let next = create_milestone(&pool, project_id, "1.1", Some(40)).await;
+
+    let open = create_issue(
+        &pool,
+        project_id,
+        json!({ "title": "Carry over", "description": "Scheduled", "milestone_id": current }),
+    )
+    .await;
+    let done = create_issue(
+        &pool,
+        project_id,
+        json!({ "title": "Shipped", "description": "Scheduled", "milestone_id": current }),
+    )
+    .await;
+    let issue_uri = |id: i64| format!("/api/projects/{}/issues/{}", project_id, id);
+    send(
+        &pool,
+        "PUT",
+        &issue_uri(done),
+        json!({ "status": "resolved" }),
+    )
+    .await;
+
+    let release_uri = format!(
+        "/api/projects/{}/milestones/{}/release",
+        project_id, current
+    );
+    let (status, body) = send(
+        &pool,
+        "POST",
+        &release_uri,
+        json!({ "move_open_issues": true }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["milestone"]["released"], true);
+    assert_eq!(body["moved_to"], next);
+    assert_eq!(body["moved_issues"], json!([open]));
+
+    assert_eq!(get(&pool, &issue_uri(open)).await["milestone_id"], next);
+    assert_eq!(get(&pool, &issue_uri(done)).await["milestone_id"], current);
+    let history = get(&pool, &format!("{}/history", issue_uri(open))).await;
+    let moved = history
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|entry| entry["field"] == "milestone_id")
+        .unwrap();
+    assert_eq!(moved["old_value"], current.to_string());
+    assert_eq!(moved["new_value"], next.to_string());
+
+    let (status, _) = send(&pool, "POST", &release_uri, json!({})).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    // Released milestones take no new issues
+    let (status, _) = send(
+        &pool,
+        "PUT",
+        &issue_uri(open),
+        json!({ "milestone_id": current }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (status, body) = send(
+        &pool,
+        "PUT",
+        &issue_uri(open),
+        json!({ "milestone_id": someday }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["milestone_id"], someday);
+}
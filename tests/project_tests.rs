@@ -8,8 +8,8 @@ use serde_json::json;
 use tower::ServiceExt;
 use ticket_manager::routes::router::create_router;
 use common::{
-    create_empty_request, create_json_request, create_test_pool, setup_test_issue,
-    setup_test_project,
+    cleanup_test_data, create_empty_request, create_named_issue, create_named_project,
+    create_test_pool, read_json, setup_test_project, TEST_TOKEN,
 };
 
 #[tokio::test]
@@ -19,6 +19,7 @@ async fn test_create_project_success() {
     let request = Request::builder()
         .method("POST")
         .uri("/api/projects")
+        .header("Authorization", format!("Bearer {}", TEST_TOKEN))
         .header("Content-Type", "application/json")
         .body(Body::from(
             json!({
@@ -45,6 +46,7 @@ async fn test_create_project_invalid_data() {
     let request = Request::builder()
         .method("POST")
         .uri("/api/projects")
+        .header("Authorization", format!("Bearer {}", TEST_TOKEN))
         .header("Content-Type", "application/json")
         .body(Body::from(request_body))
         .unwrap();
@@ -52,6 +54,7 @@ async fn test_create_project_invalid_data() {
     let response = app.oneshot(request).await.unwrap();
 
     assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
 
 #[tokio::test]
 async fn test_get_projects() {
@@ -60,6 +63,7 @@ async fn test_get_projects() {
     let request = Request::builder()
         .method("GET")
         .uri("/api/projects")
+        .header("Authorization", format!("Bearer {}", TEST_TOKEN))
         .body(Body::empty())
         .unwrap();
 
@@ -81,7 +85,8 @@ async fn test_update_project() {
 
     let request = Request::builder()
         .method("PUT")
-        .uri(&format!("/api/projects/{}", project_id))
+        .uri(format!("/api/projects/{}", project_id))
+        .header("Authorization", format!("Bearer {}", TEST_TOKEN))
         .header("Content-Type", "application/json")
         .body(Body::from(request_body))
         .unwrap();
@@ -97,9 +102,126 @@ async fn test_delete_project() {
     let request = Request::builder()
         .method("DELETE")
         .uri("/api/projects/1")
+        .header("Authorization", format!("Bearer {}", TEST_TOKEN))
         .body(Body::empty())
         .unwrap();
 
     let response = app.oneshot(request).await.unwrap();
     assert!(response.status().is_success());
 }
+
+#[tokio::test]
+async fn test_get_projects_paginates_with_cursor() {
+    let pool = create_test_pool();
+    cleanup_test_data(&pool).await;
+    for name in ["Alpha", "Bravo", "Charlie"] {
+        create_named_project(&pool, name).await;
+    }
+
+    let app = create_router(pool.clone());
+    let response = app
+        .oneshot(create_empty_request("GET", "/api/projects?limit=2"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = read_json(response).await;
+    assert_eq!(body["total"], 3);
+    assert_eq!(body["items"].as_array().unwrap().len(), 2);
+    assert_eq!(body["items"][0]["name"], "Alpha");
+    let cursor = body["next_cursor"].as_str().unwrap().to_string();
+
+    let app = create_router(pool);
+    let response = app
+        .oneshot(create_empty_request(
+            "GET",
+            &format!("/api/projects?limit=2&after={}", cursor),
+        ))
+        .await
+        .unwrap();
+    let body = read_json(response).await;
+    assert_eq!(body["items"].as_array().unwrap().len(), 1);
+    assert_eq!(body["items"][0]["name"], "Charlie");
+    assert!(body["next_cursor"].is_null());
+}
+
+#[tokio::test]
+async fn test_get_projects_filters_and_sorts() {
+    let pool = create_test_pool();
+    cleanup_test_data(&pool).await;
+    for name in ["Backend API", "Frontend", "Backend Jobs"] {
+        create_named_project(&pool, name).await;
+    }
+
+    let app = create_router(pool);
+    let response = app
+        .oneshot(create_empty_request(
+            "GET",
+            "/api/projects?name=backend&sort=name&order=desc",
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = read_json(response).await;
+    assert_eq!(body["total"], 2);
+    assert_eq!(body["items"][0]["name"], "Backend Jobs");
+    assert_eq!(body["items"][1]["name"], "Backend API");
+}
+
+#[tokio::test]
+async fn test_get_projects_rejects_bad_paging() {
+    let app = create_router(create_test_pool());
+    let response = app
+        .oneshot(create_empty_request("GET", "/api/projects?limit=0"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let app = create_router(create_test_pool());
+    let response = app
+        .oneshot(create_empty_request("GET", "/api/projects?after=not-a-cursor"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_get_project_with_issue_counts() {
+    let pool = create_test_pool();
+    let project_id = setup_test_project(&pool).await;
+    create_named_issue(&pool, project_id, "One", "open", None).await;
+    create_named_issue(&pool, project_id, "Two", "open", None).await;
+    create_named_issue(&pool, project_id, "Three", "in_progress", None).await;
+
+    let app = create_router(pool);
+    let response = app
+        .oneshot(create_empty_request(
+            "GET",
+            &format!("/api/projects/{}", project_id),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = read_json(response).await;
+    assert_eq!(body["id"], project_id);
+    assert!(body["created_at"].is_string());
+    assert!(body["updated_at"].is_string());
+    assert_eq!(body["issue_counts"]["total"], 3);
+    assert_eq!(body["issue_counts"]["open"], 3);
+    assert_eq!(body["issue_counts"]["by_status"]["open"], 2);
+    assert_eq!(body["issue_counts"]["by_status"]["in_progress"], 1);
+    assert_eq!(body["issue_counts"]["open_by_priority"]["P2"], 3);
+    assert_eq!(body["issue_counts"]["open_by_priority"]["P0"], 0);
+}
+
+#[tokio::test]
+async fn test_get_nonexistent_project() {
+    let app = create_router(create_test_pool());
+    let response = app
+        .oneshot(create_empty_request("GET", "/api/projects/999999"))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
@@ -0,0 +1,269 @@
+mod common;
+
+use common::{
+    add_project_member, create_named_project, create_test_pool, create_user_token,
+    setup_test_project, setup_test_user, TEST_TOKEN,
+};
+use futures_util::{SinkExt, StreamExt};
+use serde_json::{json, Value};
+use std::time::Duration;
+use ticket_manager::models::ProjectRole;
+use ticket_manager::routes::router::{create_router, DbPool};
+use tokio::net::{TcpListener, TcpStream};
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::{Error as WsError, Message};
+use tokio_tungstenite::{connect_async, MaybeTlsStream, WebSocketStream};
+
+type Socket = WebSocketStream<MaybeTlsStream<TcpStream>>;
+
+/// Serves the API on a local port and returns its address.
+async fn start_server(pool: &DbPool) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let app = create_router(pool.clone());
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    addr.to_string()
+}
+
+async fn connect(addr: &str, token: &str) -> Socket {
+    let mut request = format!("ws://{}/api/ws", addr)
+        .into_client_request()
+        .unwrap();
+    request.headers_mut().insert(
+        "Authorization",
+        format!("Bearer {}", token).parse().unwrap(),
+    );
+    let (socket, _) = connect_async(request).await.expect("Failed to connect");
+    socket
+}
+
+async fn send(socket: &mut Socket, message: Value) {
+    socket
+        .send(Message::Text(message.to_string().into()))
+        .await
+        .unwrap();
+}
+
+/// Waits for the next JSON message, skipping protocol frames.
+async fn receive(socket: &mut Socket) -> Value {
+    loop {
+        let frame = tokio::time::timeout(Duration::from_secs(5), socket.next())
+            .await
+            .expect("Timed out waiting for a message")
+            .expect("Socket closed")
+            .expect("Failed to read from socket");
+        if let Message::Text(text) = frame {
+            return serde_json::from_str(&text).unwrap();
+        }
+    }
+}
+
+/// Pings and returns everything received before the pong, which covers
+/// every event published before the call.
+async fn drain(socket: &mut Socket) -> Vec<Value> {
+    send(socket, json!({ "type": "ping" })).await;
+    let mut received = Vec::new();
+    loop {
+        let message = receive(socket).await;
+        if message["type"] == "pong" {
+            return received;
+        }
+        received.push(message);
+    }
+}
+
+async fn post(addr: &str, path: &str, body: Value) -> Value {
+    let response = reqwest::Client::new()
+        .post(format!("http://{}{}", addr, path))
+        .bearer_auth(TEST_TOKEN)
+        .header("Content-Type", "application/json")
+        .body(body.to_string())
+        .send()
+        .await
+        .unwrap();
+    assert!(response.status().is_success());
+    serde_json::from_str(&response.text().await.unwrap()).unwrap()
+}
+
+#[tokio::test]
+async fn test_project_and_issue_subscriptions() {
+    let pool = create_test_pool();
+    let project_id = setup_test_project(&pool).await;
+    let other_id = create_named_project(&pool, "Elsewhere").await;
+    let addr = start_server(&pool).await;
+    let issues_path = format!("/api/projects/{}/issues", project_id);
+    let mut socket = connect(&addr, TEST_TOKEN).await;
+
+    send(
+        &mut socket,
+        json!({ "type": "subscribe", "project_id": project_id }),
+    )
+    .await;
+    assert_eq!(
+        receive(&mut socket).await,
+        json!({ "type": "subscribed", "project_id": project_id })
+    );
+
+    let issue = post(
+        &addr,
+        &issues_path,
+        json!({ "title": "Socket", "description": "Live" }),
+    )
+    .await;
+    post(
+        &addr,
+        &format!("/api/projects/{}/issues", other_id),
+        json!({ "title": "Elsewhere", "description": "Not subscribed" }),
+    )
+    .await;
+    let received = drain(&mut socket).await;
+    assert_eq!(received.len(), 1);
+    assert_eq!(received[0]["type"], "event");
+    assert_eq!(received[0]["event"], "issue.created");
+    assert_eq!(received[0]["project_id"], project_id);
+    assert!(received[0]["id"].is_u64());
+    assert_eq!(received[0]["data"]["id"], issue["id"]);
+
+    // Narrow to the one issue
+    send(
+        &mut socket,
+        json!({ "type": "subscribe", "project_id": project_id, "issue_id": issue["id"] }),
+    )
+    .await;
+    send(
+        &mut socket,
+        json!({ "type": "unsubscribe", "project_id": project_id }),
+    )
+    .await;
+    assert_eq!(receive(&mut socket).await["type"], "subscribed");
+    assert_eq!(receive(&mut socket).await["type"], "presence");
+    assert_eq!(
+        receive(&mut socket).await,
+        json!({ "type": "unsubscribed", "project_id": project_id })
+    );
+
+    let comment = post(
+        &addr,
+        &format!("{}/{}/comments", issues_path, issue["id"]),
+        json!({ "body": "Seen live" }),
+    )
+    .await;
+    post(
+        &addr,
+        &issues_path,
+        json!({ "title": "Sibling", "description": "Other issue" }),
+    )
+    .await;
+    let received = drain(&mut socket).await;
+    assert_eq!(received.len(), 1);
+    assert_eq!(received[0]["event"], "comment.created");
+    assert_eq!(received[0]["data"]["id"], comment["id"]);
+}
+
+#[tokio::test]
+async fn test_presence_of_issue_viewers() {
+    let pool = create_test_pool();
+    let project_id = setup_test_project(&pool).await;
+    let addr = start_server(&pool).await;
+    let issue = post(
+        &addr,
+        &format!("/api/projects/{}/issues", project_id),
+        json!({ "title": "Watched", "description": "By two" }),
+    )
+    .await;
+    let viewer_id = setup_test_user(&pool).await;
+    add_project_member(&pool, project_id, viewer_id, ProjectRole::Viewer).await;
+    let viewer_token = create_user_token(&pool, viewer_id).await;
+    let topic = json!({ "type": "subscribe", "project_id": project_id, "issue_id": issue["id"] });
+
+    let mut admin = connect(&addr, TEST_TOKEN).await;
+    send(&mut admin, topic.clone()).await;
+    assert_eq!(receive(&mut admin).await["type"], "subscribed");
+    let presence = receive(&mut admin).await;
+    assert_eq!(presence["type"], "presence");
+    assert_eq!(presence["issue_id"], issue["id"]);
+    let admin_id = presence["viewers"][0]["id"].clone();
+    assert_eq!(presence["viewers"].as_array().unwrap().len(), 1);
+
+    let mut viewer = connect(&addr, &viewer_token).await;
+    send(&mut viewer, topic.clone()).await;
+    assert_eq!(receive(&mut viewer).await["type"], "subscribed");
+    for socket in [&mut admin, &mut viewer] {
+        let presence = receive(socket).await;
+        assert_eq!(presence["type"], "presence");
+        assert_eq!(presence["viewers"][0]["id"], admin_id);
+        assert_eq!(presence["viewers"][1]["id"], viewer_id);
+    }
+
+    // A second tab of the same user is not listed twice
+    let mut second_tab = connect(&addr, &viewer_token).await;
+    send(&mut second_tab, topic).await;
+    assert_eq!(receive(&mut second_tab).await["type"], "subscribed");
+    let presence = receive(&mut admin).await;
+    assert_eq!(presence["viewers"].as_array().unwrap().len(), 2);
+
+    viewer.close(None).await.unwrap();
+    second_tab.close(None).await.unwrap();
+    let presence = receive(&mut admin).await;
+    assert_eq!(presence["viewers"].as_array().unwrap().len(), 2);
+    let presence = receive(&mut admin).await;
+    assert_eq!(presence["viewers"].as_array().unwrap().len(), 1);
+    assert_eq!(presence["viewers"][0]["id"], admin_id);
+}
+
+#[tokio::test]
+async fn test_socket_authentication_and_errors() {
+    let pool = create_test_pool();
+    let project_id = setup_test_project(&pool).await;
+    let addr = start_server(&pool).await;
+    let outsider_id = setup_test_user(&pool).await;
+    let outsider_token = create_user_token(&pool, outsider_id).await;
+
+    match connect_async(format!("ws://{}/api/ws", addr)).await {
+        Err(WsError::Http(response)) => assert_eq!(response.status(), 401),
+        other => panic!("Expected a 401, got {:?}", other.map(|_| ())),
+    }
+
+    // Browsers pass the token in the query string
+    let (mut socket, _) = connect_async(format!(
+        "ws://{}/api/ws?access_token={}",
+        addr, outsider_token
+    ))
+    .await
+    .expect("Failed to connect with a query token");
+
+    send(&mut socket, json!({ "type": "ping" })).await;
+    assert_eq!(receive(&mut socket).await, json!({ "type": "pong" }));
+
+    send(
+        &mut socket,
+        json!({ "type": "subscribe", "project_id": project_id }),
+    )
+    .await;
+    let error = receive(&mut socket).await;
+    assert_eq!(error["type"], "error");
+    assert_eq!(error["status"], 403);
+    assert_eq!(error["topic"], json!({ "project_id": project_id }));
+
+    add_project_member(&pool, project_id, outsider_id, ProjectRole::Viewer).await;
+    send(
+        &mut socket,
+        json!({ "type": "subscribe", "project_id": project_id, "issue_id": 999999 }),
+    )
+    .await;
+    let error = receive(&mut socket).await;
+    assert_eq!(error["status"], 404);
+
+    send(&mut socket, json!({ "type": "shout" })).await;
+    let error = receive(&mut socket).await;
+    assert_eq!(error["type"], "error");
+    assert_eq!(error["status"], 400);
+
+    // Errors leave the connection usable
+    send(
+        &mut socket,
+        json!({ "type": "subscribe", "project_id": project_id }),
+    )
+    .await;
+    assert_eq!(receive(&mut socket).await["type"], "subscribed");
+}
@@ -0,0 +1,175 @@
+mod common;
+
+use axum::http::StatusCode;
+use chrono::{Duration, Utc};
+use common::{create_issue, create_test_pool, send, send_empty, setup_test_project};
+use serde_json::json;
+use ticket_manager::routes::router::DbPool;
+
+async fn create_sprint(pool: &DbPool, project_id: i32, name: &str) -> i64 {
+    let (status, body) = send(
+        pool,
+        "POST",
+        &format!("/api/projects/{}/sprints", project_id),
+        json!({ "name": name, "goal": "Ship it" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(body["state"], "planned");
+    body["id"].as_i64().unwrap()
+}
+
+#[tokio::test]
+async fn test_one_active_sprint_and_carry_over() {
+    let pool = create_test_pool();
+    let project_id = setup_test_project(&pool).await;
+    let first = create_sprint(&pool, project_id, "Sprint 1").await;
+    let second = create_sprint(&pool, project_id, "Sprint 2").await;
+    let sprint_uri = |id: i64| format!("/api/projects/{}/sprints/{}", project_id, id);
+    let issue_uri = |id: i64| format!("/api/projects/{}/issues/{}", project_id, id);
+
+    let unfinished = create_issue(
+        &pool,
+        project_id,
+        json!({ "title": "Unfinished", "description": "Sprint work", "sprint_id": first }),
+    )
+    .await;
+    let finished = create_issue(
+        &pool,
+        project_id,
+        json!({ "title": "Finished", "description": "Sprint work", "sprint_id": first }),
+    )
+    .await;
+    send(
+        &pool,
+        "PUT",
+        &issue_uri(finished),
+        json!({ "status": "resolved" }),
+    )
+    .await;
+
+    let (status, body) = send_empty(&pool, "POST", &format!("{}/start", sprint_uri(first))).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["state"], "active");
+    let today = Utc::now().date_naive();
+    assert_eq!(body["start_date"], today.to_string());
+    assert_eq!(body["end_date"], (today + Duration::days(14)).to_string());
+
+    let (status, _) = send_empty(&pool, "POST", &format!("{}/start", sprint_uri(second))).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    let (status, sprint) = send_empty(&pool, "GET", &sprint_uri(first)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(sprint["issue_counts"]["total"], 2);
+    assert_eq!(sprint["issue_counts"]["done"], 1);
+
+    let (status, report) = send(
+        &pool,
+        "POST",
+        &format!("{}/complete", sprint_uri(first)),
+        json!({ "move_incomplete_to": second }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(report["sprint"]["state"], "closed");
+    assert_eq!(report["sprint"]["completed_issues"], 1);
+    assert_eq!(report["sprint"]["incomplete_issues"], 1);
+    assert_eq!(report["completed"], json!([finished]));
+    assert_eq!(report["incomplete"], json!([unfinished]));
+    assert_eq!(report["moved_to"], second);
+
+    let (_, issue) = send_empty(&pool, "GET", &issue_uri(unfinished)).await;
+    assert_eq!(issue["sprint_id"], second);
+    let (_, issue) = send_empty(&pool, "GET", &issue_uri(finished)).await;
+    assert_eq!(issue["sprint_id"], first);
+    let (_, history) =
+        send_empty(&pool, "GET", &format!("{}/history", issue_uri(unfinished))).await;
+    let carried = history
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|entry| entry["field"] == "sprint_id")
+        .unwrap();
+    assert_eq!(carried["old_value"], first.to_string());
+    assert_eq!(carried["new_value"], second.to_string());
+
+    let (status, _) = send_empty(&pool, "POST", &format!("{}/complete", sprint_uri(first))).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    let (status, _) = send(
+        &pool,
+        "PUT",
+        &issue_uri(unfinished),
+        json!({ "sprint_id": first }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    // The project's next sprint can start once the first is closed
+    let (status, body) = send(
+        &pool,
+        "POST",
+        &format!("{}/start", sprint_uri(second)),
+        json!({ "start_date": "2025-06-02", "end_date": "2025-06-13" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["end_date"], "2025-06-13");
+}
+
+#[tokio::test]
+async fn test_completing_to_the_backlog() {
+    let pool = create_test_pool();
+    let project_id = setup_test_project(&pool).await;
+    let sprint = create_sprint(&pool, project_id, "Only sprint").await;
+    let sprint_uri = format!("/api/projects/{}/sprints/{}", project_id, sprint);
+    let open = create_issue(
+        &pool,
+        project_id,
+        json!({ "title": "Left over", "description": "Sprint work", "sprint_id": sprint }),
+    )
+    .await;
+
+    // Deleting a planned sprint returns its issues to the backlog
+    let (status, _) = send_empty(&pool, "DELETE", &sprint_uri).await;
+    assert_eq!(status, StatusCode::OK);
+    let issue_uri = format!("/api/projects/{}/issues/{}", project_id, open);
+    let (_, issue) = send_empty(&pool, "GET", &issue_uri).await;
+    assert!(issue["sprint_id"].is_null());
+
+    let sprint = create_sprint(&pool, project_id, "Replacement").await;
+    let sprint_uri = format!("/api/projects/{}/sprints/{}", project_id, sprint);
+    let (status, _) = send(&pool, "PUT", &issue_uri, json!({ "sprint_id": sprint })).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = send_empty(&pool, "POST", &format!("{}/start", sprint_uri)).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let (status, _) = send_empty(&pool, "DELETE", &sprint_uri).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+
+    // An active sprint is not a valid carry-over target; nothing changes
+    let (status, _) = send(
+        &pool,
+        "POST",
+        &format!("{}/complete", sprint_uri),
+        json!({ "move_incomplete_to": sprint }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (_, body) = send_empty(&pool, "GET", &sprint_uri).await;
+    assert_eq!(body["state"], "active");
+
+    let (status, report) = send_empty(&pool, "POST", &format!("{}/complete", sprint_uri)).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(report["incomplete"], json!([open]));
+    assert!(report["moved_to"].is_null());
+
+    let (_, backlog) = send_empty(
+        &pool,
+        "GET",
+        &format!("/api/projects/{}/issues?backlog=true", project_id),
+    )
+    .await;
+    assert_eq!(backlog["total"], 1);
+    assert_eq!(backlog["items"][0]["id"], open);
+    assert!(backlog["items"][0]["sprint_id"].is_null());
+}
@@ -0,0 +1,118 @@
+mod common;
+
+use axum::http::StatusCode;
+use common::{
+    cleanup_test_data, create_empty_request, create_json_request, create_test_pool, read_json,
+    setup_test_issue, setup_test_project,
+};
+use serde_json::json;
+use ticket_manager::routes::router::create_router;
+use tower::ServiceExt;
+
+#[tokio::test]
+async fn test_user_crud() {
+    let pool = create_test_pool();
+    cleanup_test_data(&pool).await;
+
+    let app = create_router(pool.clone());
+    let response = app
+        .oneshot(create_json_request(
+            "POST",
+            "/api/users",
+            json!({
+                "username": "alice",
+                "display_name": "Alice Example",
+                "email": "alice@example.com"
+            }),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let user = read_json(response).await;
+    assert_eq!(user["is_active"], true);
+    let user_uri = format!("/api/users/{}", user["id"]);
+
+    let app = create_router(pool.clone());
+    let response = app
+        .oneshot(create_json_request(
+            "PUT",
+            &user_uri,
+            json!({ "is_active": false }),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(read_json(response).await["is_active"], false);
+
+    let app = create_router(pool.clone());
+    let response = app
+        .oneshot(create_empty_request("GET", "/api/users?active=true"))
+        .await
+        .unwrap();
+    let active = read_json(response).await;
+    assert!(active
+        .as_array()
+        .unwrap()
+        .iter()
+        .all(|user| user["username"] != "alice"));
+
+    let app = create_router(pool.clone());
+    let response = app
+        .oneshot(create_empty_request("DELETE", &user_uri))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let app = create_router(pool);
+    let response = app
+        .oneshot(create_empty_request("GET", &user_uri))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_create_user_duplicate_username() {
+    let pool = create_test_pool();
+    cleanup_test_data(&pool).await;
+
+    for expected in [StatusCode::CREATED, StatusCode::CONFLICT] {
+        let app = create_router(pool.clone());
+        let response = app
+            .oneshot(create_json_request(
+                "POST",
+                "/api/users",
+                json!({ "username": "bob" }),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), expected);
+    }
+}
+
+#[tokio::test]
+async fn test_delete_user_with_issues_conflicts() {
+    let pool = create_test_pool();
+    let project_id = setup_test_project(&pool).await;
+    let issue_id = setup_test_issue(&pool, project_id).await;
+
+    let app = create_router(pool.clone());
+    let response = app
+        .oneshot(create_empty_request(
+            "GET",
+            &format!("/api/projects/{}/issues/{}", project_id, issue_id),
+        ))
+        .await
+        .unwrap();
+    let creator_id = read_json(response).await["created_by"]["id"].clone();
+
+    let app = create_router(pool);
+    let response = app
+        .oneshot(create_empty_request(
+            "DELETE",
+            &format!("/api/users/{}", creator_id),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+}
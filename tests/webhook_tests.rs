@@ -0,0 +1,436 @@
+mod common;
+
+use axum::extract::State;
+use axum::http::{HeaderMap, StatusCode};
+use axum::routing::post;
+use axum::Router;
+use common::{
+    add_project_member, create_named_project, create_test_pool, create_user_token, get, send,
+    send_as, send_to, setup_test_project, setup_test_user,
+};
+use diesel::RunQueryDsl;
+use serde_json::{json, Value};
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use ticket_manager::models::ProjectRole;
+use ticket_manager::routes::router::{build_router, AppState, DbPool};
+use ticket_manager::webhook_worker::{
+    deliver_due, http_client, signature, WorkerConfig, DELIVERY_HEADER, EVENT_HEADER,
+    SIGNATURE_HEADER, TIMESTAMP_HEADER,
+};
+use tokio::net::TcpListener;
+
+/// Local endpoint that records what it receives and answers with queued
+/// status codes, then 200.
+#[derive(Clone, Default)]
+struct StandIn {
+    received: Arc<Mutex<Vec<(HeaderMap, String)>>>,
+    responses: Arc<Mutex<VecDeque<u16>>>,
+}
+
+impl StandIn {
+    fn received(&self) -> Vec<(HeaderMap, String)> {
+        self.received.lock().unwrap().clone()
+    }
+}
+
+async fn receive(State(stand_in): State<StandIn>, headers: HeaderMap, body: String) -> StatusCode {
+    stand_in.received.lock().unwrap().push((headers, body));
+    let status = stand_in
+        .responses
+        .lock()
+        .unwrap()
+        .pop_front()
+        .unwrap_or(200);
+    StatusCode::from_u16(status).unwrap()
+}
+
+async fn start_stand_in(responses: &[u16]) -> (String, StandIn) {
+    let stand_in = StandIn::default();
+    stand_in.responses.lock().unwrap().extend(responses);
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let app = Router::new()
+        .route("/hook", post(receive))
+        .with_state(stand_in.clone());
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    (format!("http://{}/hook", addr), stand_in)
+}
+
+/// The API with private webhook hosts allowed, as the stand-in listens on
+/// loopback.
+fn loopback_app(pool: &DbPool) -> Router {
+    build_router(Arc::new(AppState {
+        allow_private_webhook_hosts: true,
+        ..AppState::new(pool.clone())
+    }))
+}
+
+/// Worker settings that deliver to the stand-in on loopback.
+fn loopback_worker() -> WorkerConfig {
+    WorkerConfig {
+        allow_private_hosts: true,
+        ..WorkerConfig::default()
+    }
+}
+
+/// Makes every pending delivery due now.
+fn make_due(pool: &DbPool) {
+    let mut conn = pool.get().unwrap();
+    diesel::sql_query(
+        "UPDATE webhook_deliveries SET next_attempt_at = now() WHERE status = 'pending'",
+    )
+    .execute(&mut conn)
+    .unwrap();
+}
+
+#[tokio::test]
+async fn test_events_are_signed_and_delivered() {
+    let pool = create_test_pool();
+    let project_id = setup_test_project(&pool).await;
+    let (url, stand_in) = start_stand_in(&[]).await;
+    let secret = "0123456789abcdef-secret";
+    let webhooks_uri = format!("/api/projects/{}/webhooks", project_id);
+
+    let (status, webhook) = send_to(
+        &loopback_app(&pool),
+        "POST",
+        &webhooks_uri,
+        json!({
+            "url": url,
+            "secret": secret,
+            "events": ["issue.created", "issue.deleted", "project.updated"]
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(webhook["secret"], secret);
+    let deliveries_uri = format!("{}/{}/deliveries", webhooks_uri, webhook["id"]);
+    let listed = get(&pool, &webhooks_uri).await;
+    assert_eq!(listed[0]["is_active"], true);
+    assert!(listed[0].get("secret").is_none());
+
+    let issues_uri = format!("/api/projects/{}/issues", project_id);
+    let (_, issue) = send(
+        &pool,
+        "POST",
+        &issues_uri,
+        json!({ "title": "Broken build", "description": "Red" }),
+    )
+    .await;
+    let issue_uri = format!("{}/{}", issues_uri, issue["id"]);
+    // Not subscribed
+    send(&pool, "PUT", &issue_uri, json!({ "status": "in_progress" })).await;
+    let (status, _) = send(&pool, "DELETE", &issue_uri, json!({})).await;
+    assert_eq!(status, StatusCode::OK);
+    send(
+        &pool,
+        "PUT",
+        &format!("/api/projects/{}", project_id),
+        json!({ "description": "Renamed" }),
+    )
+    .await;
+
+    let deliveries = get(&pool, &deliveries_uri).await;
+    let events: Vec<&str> = deliveries
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|d| d["event"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        events,
+        ["project.updated", "issue.deleted", "issue.created"]
+    );
+    assert_eq!(deliveries[0]["status"], "pending");
+    assert!(stand_in.received().is_empty());
+
+    let config = loopback_worker();
+    let attempted = deliver_due(&pool, &http_client(&config), &config)
+        .await
+        .unwrap();
+    assert_eq!(attempted, 3);
+
+    let received = stand_in.received();
+    assert_eq!(received.len(), 3);
+    for (headers, body) in &received {
+        let header = |name: &str| headers[name].to_str().unwrap().to_string();
+        let timestamp: i64 = header(TIMESTAMP_HEADER).parse().unwrap();
+        assert_eq!(header(SIGNATURE_HEADER), signature(secret, timestamp, body));
+        let payload: Value = serde_json::from_str(body).unwrap();
+        assert_eq!(payload["event"], header(EVENT_HEADER));
+        assert_eq!(payload["project_id"], project_id);
+        assert!(headers.contains_key(DELIVERY_HEADER));
+    }
+    let created: Value = serde_json::from_str(&received[0].1).unwrap();
+    assert_eq!(created["event"], "issue.created");
+    assert_eq!(created["data"]["title"], "Broken build");
+    let deleted: Value = serde_json::from_str(&received[1].1).unwrap();
+    assert_eq!(deleted["data"]["id"], issue["id"]);
+    // The snapshot is taken after the unsubscribed update
+    assert_eq!(deleted["data"]["status"], "in_progress");
+
+    let deliveries = get(&pool, &deliveries_uri).await;
+    for delivery in deliveries.as_array().unwrap() {
+        assert_eq!(delivery["status"], "delivered");
+        assert_eq!(delivery["attempts"], 1);
+        assert_eq!(delivery["response_status"], 200);
+        assert_eq!(delivery["next_attempt_at"], Value::Null);
+    }
+    assert_eq!(deliveries[0]["payload"]["data"]["description"], "Renamed");
+
+    // Paused webhooks are not sent new events
+    let (status, _) = send(
+        &pool,
+        "PUT",
+        &format!("{}/{}", webhooks_uri, webhook["id"]),
+        json!({ "is_active": false }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    send(
+        &pool,
+        "POST",
+        &issues_uri,
+        json!({ "title": "Quiet", "description": "Nobody hears" }),
+    )
+    .await;
+    assert_eq!(
+        get(&pool, &deliveries_uri).await.as_array().unwrap().len(),
+        3
+    );
+}
+
+#[tokio::test]
+async fn test_failed_deliveries_back_off_then_give_up() {
+    let pool = create_test_pool();
+    let project_id = setup_test_project(&pool).await;
+    let (url, stand_in) = start_stand_in(&[500]).await;
+    let webhooks_uri = format!("/api/projects/{}/webhooks", project_id);
+    let issues_uri = format!("/api/projects/{}/issues", project_id);
+    let config = WorkerConfig {
+        max_attempts: 3,
+        base_backoff: chrono::Duration::seconds(60),
+        ..loopback_worker()
+    };
+    let client = http_client(&config);
+    let app = loopback_app(&pool);
+
+    let (status, webhook) = send_to(
+        &app,
+        "POST",
+        &webhooks_uri,
+        json!({ "url": url, "events": ["issue.created"] }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert!(webhook["secret"].as_str().unwrap().len() >= 16);
+    let webhook_uri = format!("{}/{}", webhooks_uri, webhook["id"]);
+    let deliveries_uri = format!("{}/deliveries", webhook_uri);
+
+    send(
+        &pool,
+        "POST",
+        &issues_uri,
+        json!({ "title": "Flaky", "description": "Endpoint" }),
+    )
+    .await;
+    assert_eq!(deliver_due(&pool, &client, &config).await.unwrap(), 1);
+    let delivery = &get(&pool, &deliveries_uri).await[0];
+    assert_eq!(delivery["status"], "pending");
+    assert_eq!(delivery["attempts"], 1);
+    assert_eq!(delivery["response_status"], 500);
+    let retry_at: chrono::DateTime<chrono::Utc> =
+        serde_json::from_value(delivery["next_attempt_at"].clone()).unwrap();
+    assert!(retry_at > chrono::Utc::now() + chrono::Duration::seconds(50));
+
+    // Not due yet
+    assert_eq!(deliver_due(&pool, &client, &config).await.unwrap(), 0);
+    make_due(&pool);
+    assert_eq!(deliver_due(&pool, &client, &config).await.unwrap(), 1);
+    let delivery = &get(&pool, &deliveries_uri).await[0];
+    assert_eq!(delivery["status"], "delivered");
+    assert_eq!(delivery["attempts"], 2);
+    let received = stand_in.received();
+    assert_eq!(received.len(), 2);
+    assert_eq!(
+        received[0].0[DELIVERY_HEADER],
+        received[1].0[DELIVERY_HEADER]
+    );
+
+    // Point the webhook at a port nobody listens on
+    let closed = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let dead_url = format!("http://{}/hook", closed.local_addr().unwrap());
+    drop(closed);
+    let (status, _) = send_to(&app, "PUT", &webhook_uri, json!({ "url": dead_url })).await;
+    assert_eq!(status, StatusCode::OK);
+    send(
+        &pool,
+        "POST",
+        &issues_uri,
+        json!({ "title": "Lost", "description": "Endpoint" }),
+    )
+    .await;
+    for _ in 0..3 {
+        make_due(&pool);
+        assert_eq!(deliver_due(&pool, &client, &config).await.unwrap(), 1);
+    }
+    make_due(&pool);
+    assert_eq!(deliver_due(&pool, &client, &config).await.unwrap(), 0);
+
+    let failed = get(&pool, &format!("{}?status=failed", deliveries_uri)).await;
+    assert_eq!(failed.as_array().unwrap().len(), 1);
+    assert_eq!(failed[0]["attempts"], 3);
+    assert_eq!(failed[0]["response_status"], Value::Null);
+    assert!(failed[0]["last_error"].is_string());
+    assert_eq!(failed[0]["next_attempt_at"], Value::Null);
+}
+
+#[tokio::test]
+async fn test_webhook_validation_and_access() {
+    let pool = create_test_pool();
+    let project_id = setup_test_project(&pool).await;
+    let webhooks_uri = format!("/api/projects/{}/webhooks", project_id);
+
+    for body in [
+        json!({ "url": "ftp://example.com/hook", "events": ["issue.created"] }),
+        json!({ "url": "not a url", "events": ["issue.created"] }),
+        json!({ "url": "https://example.com/hook", "events": [] }),
+        json!({ "url": "https://example.com/hook", "events": ["issue.closed"] }),
+        json!({ "url": "https://example.com/hook", "events": ["issue.created"], "secret": "short" }),
+    ] {
+        let (status, _) = send(&pool, "POST", &webhooks_uri, body).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+
+    let (status, webhook) = send(
+        &pool,
+        "POST",
+        &webhooks_uri,
+        json!({
+            "url": "https://example.com/hook",
+            "events": ["issue.created", "issue.created", "issue.updated"]
+        }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(webhook["events"], json!(["issue.created", "issue.updated"]));
+    let (status, _) = send(
+        &pool,
+        "PUT",
+        &format!("{}/{}", webhooks_uri, webhook["id"]),
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = send(
+        &pool,
+        "GET",
+        &format!("{}/{}/deliveries?status=lost", webhooks_uri, webhook["id"]),
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let developer_id = setup_test_user(&pool).await;
+    add_project_member(&pool, project_id, developer_id, ProjectRole::Developer).await;
+    let token = create_user_token(&pool, developer_id).await;
+    let (status, _) = send_as(&pool, &token, "GET", &webhooks_uri, None).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let other_project = create_named_project(&pool, "Other").await;
+    let (status, _) = send(
+        &pool,
+        "DELETE",
+        &format!("/api/projects/{}/webhooks/{}", other_project, webhook["id"]),
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_private_hosts_are_refused() {
+    let pool = create_test_pool();
+    let project_id = setup_test_project(&pool).await;
+    let (url, stand_in) = start_stand_in(&[]).await;
+    let webhooks_uri = format!("/api/projects/{}/webhooks", project_id);
+
+    for private in [
+        url.as_str(),
+        "http://localhost/hook",
+        "http://10.1.2.3/hook",
+        "http://192.168.0.10:8080/hook",
+        "http://169.254.169.254/latest/meta-data",
+        "http://0.0.0.0/hook",
+        "http://[::1]/hook",
+        "http://[fd00::1]/hook",
+        "http://[::ffff:172.16.0.1]/hook",
+        "http://[64:ff9b::7f00:1]/hook",
+        "http://[2002:7f00:1::]/hook",
+        "http://198.18.0.1/hook",
+        "http://192.0.0.8/hook",
+        "http://203.0.113.7/hook",
+        "http://250.1.2.3/hook",
+    ] {
+        let (status, _) = send(
+            &pool,
+            "POST",
+            &webhooks_uri,
+            json!({ "url": private, "events": ["issue.created"] }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST, "{}", private);
+    }
+    let (status, webhook) = send(
+        &pool,
+        "POST",
+        &webhooks_uri,
+        json!({ "url": "http://93.184.215.14/hook", "events": ["project.updated"] }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    let (status, _) = send(
+        &pool,
+        "PUT",
+        &format!("{}/{}", webhooks_uri, webhook["id"]),
+        json!({ "url": "http://127.0.0.1:9000/hook" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    // Saved while private hosts were allowed, refused at delivery
+    let (status, webhook) = send_to(
+        &loopback_app(&pool),
+        "POST",
+        &webhooks_uri,
+        json!({ "url": url, "events": ["issue.created"] }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    send(
+        &pool,
+        "POST",
+        &format!("/api/projects/{}/issues", project_id),
+        json!({ "title": "Internal", "description": "Probe" }),
+    )
+    .await;
+    let config = WorkerConfig::default();
+    assert_eq!(
+        deliver_due(&pool, &http_client(&config), &config)
+            .await
+            .unwrap(),
+        1
+    );
+    assert!(stand_in.received().is_empty());
+    let deliveries = get(
+        &pool,
+        &format!("{}/{}/deliveries", webhooks_uri, webhook["id"]),
+    )
+    .await;
+    assert_eq!(deliveries[0]["status"], "pending");
+    assert!(deliveries[0]["last_error"]
+        .as_str()
+        .unwrap()
+        .contains("not a public address"));
+}
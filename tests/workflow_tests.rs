@@ -0,0 +1,161 @@
+mod common;
+
+use axum::http::StatusCode;
+use common::{
+    create_empty_request, create_json_request, create_test_pool, read_json, setup_test_issue,
+    setup_test_project, setup_test_user,
+};
+use serde_json::{json, Value};
+use ticket_manager::routes::router::create_router;
+use tower::ServiceExt;
+
+fn linear_workflow() -> Value {
+    json!({
+        "states": [
+            { "name": "todo", "category": "open", "is_initial": true },
+            { "name": "doing", "category": "open" },
+            { "name": "done", "category": "done" }
+        ],
+        "transitions": [
+            { "from": "todo", "to": "doing" },
+            { "from": "doing", "to": "done" }
+        ]
+    })
+}
+
+#[tokio::test]
+async fn test_new_project_gets_default_workflow() {
+    let pool = create_test_pool();
+    let project_id = setup_test_project(&pool).await;
+
+    let app = create_router(pool);
+    let response = app
+        .oneshot(create_empty_request(
+            "GET",
+            &format!("/api/projects/{}/workflow", project_id),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let body = read_json(response).await;
+    let names: Vec<&str> = body["states"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|s| s["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(names, ["open", "in_progress", "resolved", "closed"]);
+    assert_eq!(body["states"][0]["is_initial"], true);
+}
+
+#[tokio::test]
+async fn test_update_issue_follows_workflow() {
+    let pool = create_test_pool();
+    let project_id = setup_test_project(&pool).await;
+    let user_id = setup_test_user(&pool).await;
+
+    let app = create_router(pool.clone());
+    let response = app
+        .oneshot(create_json_request(
+            "PUT",
+            &format!("/api/projects/{}/workflow", project_id),
+            linear_workflow(),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let app = create_router(pool.clone());
+    let response = app
+        .oneshot(create_json_request(
+            "POST",
+            &format!("/api/projects/{}/issues", project_id),
+            json!({
+                "title": "Workflow issue",
+                "description": "Follows the linear workflow",
+                "created_by": user_id
+            }),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let issue = read_json(response).await;
+    assert_eq!(issue["status"], "todo");
+    let issue_uri = format!("/api/projects/{}/issues/{}", project_id, issue["id"]);
+
+    let app = create_router(pool.clone());
+    let response = app
+        .oneshot(create_json_request(
+            "PUT",
+            &issue_uri,
+            json!({ "status": "done" }),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNPROCESSABLE_ENTITY);
+    let body = read_json(response).await;
+    assert_eq!(body["code"], "invalid_transition");
+    assert_eq!(body["details"]["allowed"], json!(["doing"]));
+
+    for (status, is_open) in [("doing", true), ("done", false)] {
+        let app = create_router(pool.clone());
+        let response = app
+            .oneshot(create_json_request(
+                "PUT",
+                &issue_uri,
+                json!({ "status": status }),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = read_json(response).await;
+        assert_eq!(body["status"], status);
+        assert_eq!(body["is_open"], is_open);
+    }
+}
+
+#[tokio::test]
+async fn test_workflow_cannot_drop_states_in_use() {
+    let pool = create_test_pool();
+    let project_id = setup_test_project(&pool).await;
+    setup_test_issue(&pool, project_id).await;
+
+    let app = create_router(pool);
+    let response = app
+        .oneshot(create_json_request(
+            "PUT",
+            &format!("/api/projects/{}/workflow", project_id),
+            linear_workflow(),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    let body = read_json(response).await;
+    assert_eq!(body["code"], "states_in_use");
+    assert_eq!(body["details"]["states"], json!(["open"]));
+}
+
+#[tokio::test]
+async fn test_workflow_rejects_invalid_definition() {
+    let pool = create_test_pool();
+    let project_id = setup_test_project(&pool).await;
+
+    let app = create_router(pool);
+    let response = app
+        .oneshot(create_json_request(
+            "PUT",
+            &format!("/api/projects/{}/workflow", project_id),
+            json!({
+                "states": [
+                    { "name": "todo", "category": "open", "is_initial": true },
+                    { "name": "done", "category": "finished", "is_initial": true }
+                ],
+                "transitions": []
+            }),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
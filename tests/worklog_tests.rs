@@ -0,0 +1,241 @@
+mod common;
+
+use axum::http::StatusCode;
+use common::{
+    add_project_member, create_issue, create_test_pool, create_user_token, get, send, send_as,
+    send_empty, setup_test_project, setup_test_user,
+};
+use serde_json::json;
+use ticket_manager::models::ProjectRole;
+
+#[tokio::test]
+async fn test_time_summaries_for_project_and_milestone() {
+    let pool = create_test_pool();
+    let project_id = setup_test_project(&pool).await;
+    let reporter_id = setup_test_user(&pool).await;
+    add_project_member(&pool, project_id, reporter_id, ProjectRole::Reporter).await;
+    let reporter_token = create_user_token(&pool, reporter_id).await;
+
+    let (status, milestone) = send(
+        &pool,
+        "POST",
+        &format!("/api/projects/{}/milestones", project_id),
+        json!({ "name": "Billing period" }),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    let milestone_id = milestone["id"].as_i64().unwrap();
+
+    let open = create_issue(
+        &pool,
+        project_id,
+        json!({
+            "title": "Build invoices",
+            "description": "Open work",
+            "milestone_id": milestone_id,
+            "original_estimate_minutes": 240,
+            "estimate_points": 3
+        }),
+    )
+    .await;
+    let done = create_issue(
+        &pool,
+        project_id,
+        json!({
+            "title": "Send invoices",
+            "description": "Finished work",
+            "milestone_id": milestone_id,
+            "original_estimate_minutes": 60,
+            "estimate_points": 2
+        }),
+    )
+    .await;
+    let worklogs_uri = |id: i64| format!("/api/projects/{}/issues/{}/worklogs", project_id, id);
+    send(
+        &pool,
+        "PUT",
+        &format!("/api/projects/{}/issues/{}", project_id, done),
+        json!({ "status": "resolved" }),
+    )
+    .await;
+
+    let (status, worklog) = send_as(
+        &pool,
+        &reporter_token,
+        "POST",
+        &worklogs_uri(open),
+        Some(json!({ "minutes": 90, "work_date": "2025-06-02", "note": "Schema" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(worklog["user"]["id"], reporter_id);
+    assert_eq!(worklog["note"], "Schema");
+    for (issue_id, minutes, date) in [(open, 30, "2025-06-05"), (done, 45, "2025-06-03")] {
+        let (status, _) = send(
+            &pool,
+            "POST",
+            &worklogs_uri(issue_id),
+            json!({ "minutes": minutes, "work_date": date }),
+        )
+        .await;
+        assert_eq!(status, StatusCode::CREATED);
+    }
+
+    let worklogs = get(&pool, &worklogs_uri(open)).await;
+    let dates: Vec<&str> = worklogs
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|w| w["work_date"].as_str().unwrap())
+        .collect();
+    assert_eq!(dates, ["2025-06-02", "2025-06-05"]);
+
+    let project = get(&pool, &format!("/api/projects/{}", project_id)).await;
+    let time = &project["time_tracking"];
+    assert_eq!(time["logged_minutes"], 165);
+    assert_eq!(time["by_user"][0]["user"]["id"], reporter_id);
+    assert_eq!(time["by_user"][0]["minutes"], 90);
+    assert_eq!(time["by_user"][1]["minutes"], 75);
+    assert_eq!(time["estimate_minutes"], 300);
+    // 240 estimated on the open issue, 120 of it already logged
+    assert_eq!(time["remaining_estimate_minutes"], 120);
+    assert_eq!(time["estimate_points"], 5);
+    assert_eq!(time["remaining_points"], 3);
+
+    let milestone = get(
+        &pool,
+        &format!(
+            "/api/projects/{}/milestones/{}?from=2025-06-03&to=2025-06-30",
+            project_id, milestone_id
+        ),
+    )
+    .await;
+    let time = &milestone["time_tracking"];
+    assert_eq!(time["from"], "2025-06-03");
+    assert_eq!(time["logged_minutes"], 75);
+    assert_eq!(time["by_user"].as_array().unwrap().len(), 1);
+    assert_eq!(time["remaining_estimate_minutes"], 120);
+
+    let (status, _) = send_empty(
+        &pool,
+        "GET",
+        &format!("/api/projects/{}?from=2025-07-01&to=2025-06-01", project_id),
+    )
+    .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_worklog_validation_and_ownership() {
+    let pool = create_test_pool();
+    let project_id = setup_test_project(&pool).await;
+    let author_id = setup_test_user(&pool).await;
+    add_project_member(&pool, project_id, author_id, ProjectRole::Reporter).await;
+    let author_token = create_user_token(&pool, author_id).await;
+    let other_id = setup_test_user(&pool).await;
+    add_project_member(&pool, project_id, other_id, ProjectRole::Developer).await;
+    let other_token = create_user_token(&pool, other_id).await;
+
+    let parent = create_issue(
+        &pool,
+        project_id,
+        json!({ "title": "Epic", "description": "Parent" }),
+    )
+    .await;
+    let child = create_issue(
+        &pool,
+        project_id,
+        json!({
+            "title": "Story",
+            "description": "Child",
+            "parent_id": parent,
+            "original_estimate_minutes": 100
+        }),
+    )
+    .await;
+    let worklogs_uri = format!("/api/projects/{}/issues/{}/worklogs", project_id, child);
+
+    for minutes in [0, 1441] {
+        let (status, _) = send_as(
+            &pool,
+            &author_token,
+            "POST",
+            &worklogs_uri,
+            Some(json!({ "minutes": minutes })),
+        )
+        .await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    }
+    let (status, _) = send_as(
+        &pool,
+        &author_token,
+        "POST",
+        &worklogs_uri,
+        Some(json!({ "minutes": 30, "user_id": other_id })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+
+    let (status, worklog) = send_as(
+        &pool,
+        &author_token,
+        "POST",
+        &worklogs_uri,
+        Some(json!({ "minutes": 40 })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::CREATED);
+    let worklog_uri = format!("{}/{}", worklogs_uri, worklog["id"]);
+
+    let (status, _) = send_as(
+        &pool,
+        &other_token,
+        "PUT",
+        &worklog_uri,
+        Some(json!({ "minutes": 10 })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, updated) = send_as(
+        &pool,
+        &author_token,
+        "PUT",
+        &worklog_uri,
+        Some(json!({ "minutes": 60, "note": "Pairing" })),
+    )
+    .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(updated["minutes"], 60);
+    assert_eq!(updated["note"], "Pairing");
+    let (status, _) = send_as(&pool, &author_token, "PUT", &worklog_uri, Some(json!({}))).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let tree = get(
+        &pool,
+        &format!("/api/projects/{}/issues/{}/subtree", project_id, parent),
+    )
+    .await;
+    assert_eq!(tree["children"][0]["logged_minutes"], 60);
+    assert_eq!(tree["rollup"]["logged_minutes"], 60);
+    assert_eq!(tree["rollup"]["remaining_estimate_minutes"], 40);
+
+    // The worklog only exists under its own issue
+    let (status, _) = send(
+        &pool,
+        "DELETE",
+        &format!(
+            "/api/projects/{}/issues/{}/worklogs/{}",
+            project_id, parent, worklog["id"]
+        ),
+        json!({}),
+    )
+    .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    let (status, _) = send_as(&pool, &other_token, "DELETE", &worklog_uri, None).await;
+    assert_eq!(status, StatusCode::FORBIDDEN);
+    let (status, _) = send(&pool, "DELETE", &worklog_uri, json!({})).await;
+    assert_eq!(status, StatusCode::OK);
+    let worklogs = get(&pool, &worklogs_uri).await;
+    assert_eq!(worklogs, json!([]));
+}